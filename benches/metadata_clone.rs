@@ -0,0 +1,48 @@
+//! Compares the cost of cloning [`Metadata`] with the cost of deep-copying the same values.
+//!
+//! Run with `cargo bench --bench metadata_clone`.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use mpris::{Metadata, MetadataValue};
+
+const ITERATIONS: u32 = 10_000;
+
+fn fixture() -> HashMap<String, MetadataValue> {
+    let lyrics = "la ".repeat(200 * 1024 / 3);
+    let mut values = HashMap::new();
+    values.insert(
+        String::from("mpris:trackid"),
+        MetadataValue::from("/org/example/Track/1"),
+    );
+    values.insert(String::from("xesam:title"), MetadataValue::from("Song"));
+    values.insert(String::from("xesam:asText"), MetadataValue::String(lyrics));
+    values
+}
+
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let values = fixture();
+    let metadata = Metadata::from(values.clone());
+
+    // Keep the last clone alive so the compiler cannot skip the work.
+    let mut deep_sink = None;
+    let deep = measure(|| deep_sink = Some(values.clone()));
+    let mut shared_sink = None;
+    let shared = measure(|| shared_sink = Some(metadata.clone()));
+    assert_eq!(
+        deep_sink.map(|v| v.len()),
+        shared_sink.map(|m| m.keys().count())
+    );
+
+    println!("Deep copy of values (old Metadata::clone): {:?}", deep);
+    println!("Metadata::clone:                           {:?}", shared);
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use dbus::ffidisp::{ConnPath, Connection};
+
+use crate::metadata::Value;
+use crate::player::get_all_interface_properties;
+use crate::{DBusError, DBusErrorKind};
+
+/// How long each probe of [`Player::capability_report`](crate::Player::capability_report) may
+/// take, so that a hanging player can not block the report for long.
+pub(crate) const PROBE_TIMEOUT_MS: i32 = 500;
+
+const ROOT_INTERFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+const TRACK_LIST_INTERFACE: &str = "org.mpris.MediaPlayer2.TrackList";
+const PLAYLISTS_INTERFACE: &str = "org.mpris.MediaPlayer2.Playlists";
+
+/// Properties that the specification marks as optional.
+const OPTIONAL_PROPERTIES: &[&str] = &[
+    "Fullscreen",
+    "CanSetFullscreen",
+    "DesktopEntry",
+    "LoopStatus",
+    "Shuffle",
+];
+
+/// What a [`Player`](crate::Player) actually implements of the MPRIS2 specification, as
+/// returned by [`Player::capability_report`](crate::Player::capability_report).
+///
+/// Every player implements a different part of the specification. The report is meant to adapt
+/// a user interface once when connecting to a player, and to be attached to bug reports. With
+/// the `serde` feature it can be serialized.
+///
+/// The lists of properties and flags are sorted by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapabilityReport {
+    /// The MPRIS2 interfaces that the player's object declares, like
+    /// `"org.mpris.MediaPlayer2.TrackList"`.
+    pub interfaces: Vec<String>,
+
+    /// The names of all properties of the `org.mpris.MediaPlayer2` and
+    /// `org.mpris.MediaPlayer2.Player` interfaces that the player reported.
+    pub properties: Vec<String>,
+
+    /// The optional properties (like `"LoopStatus"` or `"Fullscreen"`) that the player reported.
+    pub optional_properties: Vec<String>,
+
+    /// The `Can*` and `Has*` flags that the player reported as [`true`], like `"CanSeek"`.
+    pub enabled_flags: Vec<String>,
+
+    /// If the `org.mpris.MediaPlayer2.Player` interface declares the `Seeked` signal. Players
+    /// that do not declare it usually do not send it either.
+    pub declares_seeked: bool,
+
+    /// The probes that failed, like `"Introspect"` or `"GetAll org.mpris.MediaPlayer2.Player"`,
+    /// with the error they caused. What they would have found is missing from the report.
+    pub failed_probes: Vec<(String, String)>,
+}
+
+impl CapabilityReport {
+    /// If the player declares the `org.mpris.MediaPlayer2.Player` interface.
+    pub fn has_player_interface(&self) -> bool {
+        self.has_interface(PLAYER_INTERFACE)
+    }
+
+    /// If the player declares the `org.mpris.MediaPlayer2.TrackList` interface.
+    pub fn has_track_list_interface(&self) -> bool {
+        self.has_interface(TRACK_LIST_INTERFACE)
+    }
+
+    /// If the player declares the `org.mpris.MediaPlayer2.Playlists` interface.
+    pub fn has_playlists_interface(&self) -> bool {
+        self.has_interface(PLAYLISTS_INTERFACE)
+    }
+
+    /// If the player reported the flag (like `"CanSeek"`) as [`true`].
+    pub fn is_enabled(&self, flag: &str) -> bool {
+        self.enabled_flags.iter().any(|enabled| enabled == flag)
+    }
+
+    fn has_interface(&self, interface: &str) -> bool {
+        self.interfaces.iter().any(|name| name == interface)
+    }
+
+    /// Probes the player at `connection`, which should have a timeout of [`PROBE_TIMEOUT_MS`].
+    ///
+    /// Probes that fail are recorded in the report, unless the player is gone.
+    pub(crate) fn probe(connection: ConnPath<'_, &Connection>) -> Result<Self, DBusError> {
+        use dbus::ffidisp::stdintf::OrgFreedesktopDBusIntrospectable;
+
+        let mut report = CapabilityReport::default();
+
+        match connection.introspect().map_err(DBusError::from) {
+            Ok(xml) => report.read_introspection(&xml),
+            Err(error) => report.record_failure("Introspect", error)?,
+        }
+
+        for interface in &[ROOT_INTERFACE, PLAYER_INTERFACE] {
+            match get_all_interface_properties(connection.clone(), interface) {
+                Ok(properties) => report.read_properties(&properties),
+                Err(error) => report.record_failure(&format!("GetAll {}", interface), error)?,
+            }
+        }
+
+        report.properties.sort();
+        report.optional_properties.sort();
+        report.enabled_flags.sort();
+        Ok(report)
+    }
+
+    /// Reads the interfaces and signals out of the introspection XML.
+    ///
+    /// Like the check for the track list interface, this looks for substrings instead of parsing
+    /// the XML.
+    fn read_introspection(&mut self, xml: &str) {
+        for interface in &[
+            ROOT_INTERFACE,
+            PLAYER_INTERFACE,
+            TRACK_LIST_INTERFACE,
+            PLAYLISTS_INTERFACE,
+        ] {
+            if xml.contains(&format!("\"{}\"", interface)) {
+                self.interfaces.push(String::from(*interface));
+            }
+        }
+
+        self.declares_seeked =
+            xml.find(&format!("\"{}\"", PLAYER_INTERFACE))
+                .map_or(false, |start| {
+                    // Self-closing declarations have no end tag, so stop at the next interface.
+                    let declaration = &xml[start..];
+                    let end = declaration.find("<interface").unwrap_or(declaration.len());
+                    declaration[..end].contains("<signal name=\"Seeked\"")
+                });
+    }
+
+    fn read_properties(&mut self, properties: &HashMap<String, Value>) {
+        for (name, value) in properties {
+            self.properties.push(name.clone());
+            if OPTIONAL_PROPERTIES.contains(&name.as_str()) {
+                self.optional_properties.push(name.clone());
+            }
+            let is_flag = name.starts_with("Can") || name.starts_with("Has");
+            if is_flag && value.as_bool() == Some(true) {
+                self.enabled_flags.push(name.clone());
+            }
+        }
+    }
+
+    /// Records a failed probe, or returns the error if the player is not there at all.
+    fn record_failure(&mut self, probe: &str, error: DBusError) -> Result<(), DBusError> {
+        match error.kind() {
+            DBusErrorKind::ServiceUnknown | DBusErrorKind::Disconnected => Err(error),
+            _ => {
+                self.failed_probes
+                    .push((String::from(probe), error.to_string()));
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_interfaces_and_signals_from_introspection() {
+        let mut report = CapabilityReport::default();
+        report.read_introspection(
+            r#"<node>
+                <interface name="org.mpris.MediaPlayer2"><property name="Identity"/></interface>
+                <interface name="org.mpris.MediaPlayer2.Player">
+                    <method name="Seek"/><signal name="Seeked"/>
+                </interface>
+                <interface name="org.mpris.MediaPlayer2.TrackList"/>
+            </node>"#,
+        );
+        assert_eq!(
+            report.interfaces,
+            vec![ROOT_INTERFACE, PLAYER_INTERFACE, TRACK_LIST_INTERFACE]
+        );
+        assert!(report.declares_seeked);
+        assert!(!report.has_playlists_interface());
+
+        let mut report = CapabilityReport::default();
+        report.read_introspection(
+            r#"<node><interface name="org.mpris.MediaPlayer2.Player"/>
+                <interface name="org.example.Other"><signal name="Seeked"/></interface></node>"#,
+        );
+        assert!(!report.declares_seeked);
+    }
+}
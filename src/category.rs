@@ -0,0 +1,203 @@
+use crate::metadata::Metadata;
+use crate::open_uri::probable_mime_types;
+use crate::player::Player;
+
+/// What kind of media a [`Player`] plays, as guessed by a [`PlayerClassifier`].
+///
+/// Get it from [`Player::category`], or find players of one category with
+/// [`PlayerFinder::find_active_in`](crate::PlayerFinder::find_active_in).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlayerCategory {
+    /// A music player, like Spotify or Rhythmbox.
+    Music,
+
+    /// A video player, like mpv or Totem.
+    Video,
+
+    /// A web browser, or a proxy that forwards a browser's media keys (like Plasma Browser
+    /// Integration). These mostly play videos in tabs, which many tools want to ignore.
+    Browser,
+
+    /// Anything that could not be classified.
+    Other,
+}
+
+/// Names of well-known players, matched against the lowercase identity, desktop entry and bus
+/// name of a player.
+const KNOWN_PLAYERS: &[(&str, PlayerCategory)] = &[
+    ("firefox", PlayerCategory::Browser),
+    ("librewolf", PlayerCategory::Browser),
+    ("chromium", PlayerCategory::Browser),
+    ("chrome", PlayerCategory::Browser),
+    ("brave", PlayerCategory::Browser),
+    ("vivaldi", PlayerCategory::Browser),
+    ("opera", PlayerCategory::Browser),
+    ("microsoft-edge", PlayerCategory::Browser),
+    ("epiphany", PlayerCategory::Browser),
+    ("plasma-browser-integration", PlayerCategory::Browser),
+    ("plasma browser integration", PlayerCategory::Browser),
+    ("spotify", PlayerCategory::Music),
+    ("rhythmbox", PlayerCategory::Music),
+    ("lollypop", PlayerCategory::Music),
+    ("clementine", PlayerCategory::Music),
+    ("strawberry", PlayerCategory::Music),
+    ("amarok", PlayerCategory::Music),
+    ("elisa", PlayerCategory::Music),
+    ("audacious", PlayerCategory::Music),
+    ("quodlibet", PlayerCategory::Music),
+    ("deadbeef", PlayerCategory::Music),
+    ("cmus", PlayerCategory::Music),
+    ("mpd", PlayerCategory::Music),
+    ("mpv", PlayerCategory::Video),
+    ("totem", PlayerCategory::Video),
+    ("celluloid", PlayerCategory::Video),
+    ("smplayer", PlayerCategory::Video),
+    ("kodi", PlayerCategory::Video),
+];
+
+/// Guesses the [`PlayerCategory`] of players.
+///
+/// A player is first looked up by name: every entry of the table is compared with the player's
+/// [`identity`](Player::identity), [`desktop entry`](Player::get_desktop_entry) and bus name
+/// (ignoring case), and the first entry that is contained in one of them decides. Entries added
+/// with [`add`](Self::add) are checked before the built-in ones, so they can also correct them.
+///
+/// Players that are not in the table are classified by their current track: video files are
+/// [`Video`](PlayerCategory::Video), audio files and tracks with an artist or album are
+/// [`Music`](PlayerCategory::Music), and everything else is [`Other`](PlayerCategory::Other).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use mpris::{PlayerCategory, PlayerClassifier, PlayerFinder};
+/// let mut classifier = PlayerClassifier::new();
+/// classifier.add("my-tube-client", PlayerCategory::Video);
+///
+/// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+/// println!("{:?}", classifier.classify(&player));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlayerClassifier {
+    custom: Vec<(String, PlayerCategory)>,
+}
+
+impl Default for PlayerClassifier {
+    fn default() -> Self {
+        PlayerClassifier::new()
+    }
+}
+
+impl PlayerClassifier {
+    /// Creates a classifier with the built-in table of well-known players.
+    pub fn new() -> Self {
+        PlayerClassifier { custom: Vec::new() }
+    }
+
+    /// Adds a name to the table. Players whose identity, desktop entry or bus name contains
+    /// `name` (ignoring case) are put into `category`.
+    ///
+    /// Later additions are checked before earlier ones.
+    pub fn add<S: Into<String>>(&mut self, name: S, category: PlayerCategory) {
+        self.custom
+            .insert(0, (name.into().to_lowercase(), category));
+    }
+
+    /// Guesses the category of the player.
+    ///
+    /// Errors while reading the desktop entry or the metadata are ignored; the player is then
+    /// classified with what is known.
+    pub fn classify(&self, player: &Player) -> PlayerCategory {
+        if let Some(category) = self.lookup_player(player) {
+            return category;
+        }
+
+        player
+            .get_metadata()
+            .map_or(PlayerCategory::Other, |metadata| {
+                category_from_metadata(&metadata)
+            })
+    }
+
+    /// Looks up the player's names in the table.
+    pub(crate) fn lookup_player(&self, player: &Player) -> Option<PlayerCategory> {
+        let desktop_entry = player.get_desktop_entry().ok().flatten();
+        let mut names = vec![player.identity(), player.bus_name_player_name_part()];
+        names.extend(desktop_entry.as_deref());
+        self.lookup(&names)
+    }
+
+    /// Returns the category of the first table entry that is contained in one of the names.
+    fn lookup(&self, names: &[&str]) -> Option<PlayerCategory> {
+        let names: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+        let custom = self
+            .custom
+            .iter()
+            .map(|(pattern, category)| (pattern.as_str(), *category));
+        let built_in = KNOWN_PLAYERS.iter().copied();
+
+        custom
+            .chain(built_in)
+            .find(|(pattern, _)| names.iter().any(|name| name.contains(pattern)))
+            .map(|(_, category)| category)
+    }
+}
+
+/// Guesses the category from the track that is playing.
+fn category_from_metadata(metadata: &Metadata) -> PlayerCategory {
+    let mime_type = metadata
+        .url()
+        .and_then(probable_mime_types)
+        .and_then(|types| types.first());
+    match mime_type {
+        Some(mime_type) if mime_type.starts_with("video/") => return PlayerCategory::Video,
+        Some(mime_type) if mime_type.starts_with("audio/") => return PlayerCategory::Music,
+        _ => {}
+    }
+
+    if metadata.album_name().is_some() || metadata.artists().is_some() {
+        PlayerCategory::Music
+    } else {
+        PlayerCategory::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataValue;
+
+    #[test]
+    fn it_looks_up_players_by_name() {
+        let classifier = PlayerClassifier::new();
+        assert_eq!(
+            classifier.lookup(&["Mozilla Firefox", "firefox.instance_1_23"]),
+            Some(PlayerCategory::Browser)
+        );
+        assert_eq!(
+            classifier.lookup(&["Spotify", "spotify"]),
+            Some(PlayerCategory::Music)
+        );
+        assert_eq!(classifier.lookup(&["VLC media player", "vlc"]), None);
+
+        let mut classifier = PlayerClassifier::new();
+        classifier.add("VLC", PlayerCategory::Video);
+        classifier.add("spotify", PlayerCategory::Other);
+        assert_eq!(
+            classifier.lookup(&["VLC media player"]),
+            Some(PlayerCategory::Video)
+        );
+        assert_eq!(classifier.lookup(&["Spotify"]), Some(PlayerCategory::Other));
+    }
+
+    #[test]
+    fn it_guesses_categories_from_the_track() {
+        let mut metadata = Metadata::new("/org/example/Track/1");
+        assert_eq!(category_from_metadata(&metadata), PlayerCategory::Other);
+
+        metadata.insert("xesam:album", MetadataValue::from("Philharmonics"));
+        assert_eq!(category_from_metadata(&metadata), PlayerCategory::Music);
+
+        metadata.insert("xesam:url", MetadataValue::from("file:///videos/clip.mkv"));
+        assert_eq!(category_from_metadata(&metadata), PlayerCategory::Video);
+    }
+}
@@ -0,0 +1,299 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use dbus::ffidisp::{ConnPath, Connection};
+
+use super::{DBusError, LoopStatus, TrackID, Volume};
+use crate::extensions::DurationExtensions;
+use crate::generated::OrgMprisMediaPlayer2Player;
+use crate::player::{explain_rejection, MPRIS2_PATH};
+use crate::pooled_connection::Bus;
+use crate::retry::Member;
+use crate::trace::CallSpan;
+
+/// A handle to control a [`Player`](crate::Player) from any thread.
+///
+/// Get one from [`Player::controls`](crate::Player::controls). Unlike [`Player`](crate::Player),
+/// this handle is [`Send`] and [`Sync`], and cloning it is cheap. It can only send commands; read
+/// the player's state through the [`Player`](crate::Player) itself.
+///
+/// Commands are sent from a background thread with its own connection to the bus, so they do not
+/// interfere with [`PlayerEvents`](crate::PlayerEvents) or
+/// [`ProgressTracker`](crate::ProgressTracker)s reading signals from the player's connection.
+/// Every method blocks until the player replied, and returns the player's error if there was one.
+/// The thread stops when the last clone of the handle is dropped.
+///
+/// Players that were created from a connection you opened yourself are controlled over the
+/// session bus.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use mpris::PlayerFinder;
+/// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+/// let controls = player.controls();
+///
+/// std::thread::spawn(move || {
+///     controls.play_pause().unwrap();
+/// });
+///
+/// for event in player.events().unwrap() {
+///     println!("{:?}", event);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlayerControls {
+    requests: Arc<Mutex<Sender<Request>>>,
+}
+
+#[derive(Debug)]
+struct Request {
+    command: Command,
+    reply: Sender<Result<(), DBusError>>,
+}
+
+#[derive(Debug)]
+enum Command {
+    Play,
+    Pause,
+    PlayPause,
+    Stop,
+    Next,
+    Previous,
+    Seek(i64),
+    SetPosition(TrackID, u64),
+    SetVolume(f64),
+    SetShuffle(bool),
+    SetLoopStatus(LoopStatus),
+    OpenUri(String),
+}
+
+impl Command {
+    /// The name of the method, or of the property that is set.
+    fn member(&self) -> Member<'static> {
+        match self {
+            Command::Play => Member::call("Play"),
+            Command::Pause => Member::call("Pause"),
+            Command::PlayPause => Member::call("PlayPause"),
+            Command::Stop => Member::call("Stop"),
+            Command::Next => Member::call("Next"),
+            Command::Previous => Member::call("Previous"),
+            Command::Seek(_) => Member::call("Seek"),
+            Command::SetPosition(..) => Member::call("SetPosition"),
+            Command::SetVolume(_) => Member::set("Volume"),
+            Command::SetShuffle(_) => Member::set("Shuffle"),
+            Command::SetLoopStatus(_) => Member::set("LoopStatus"),
+            Command::OpenUri(_) => Member::call("OpenUri"),
+        }
+    }
+}
+
+impl PlayerControls {
+    pub(crate) fn new(bus: Bus, bus_name: String, timeout_ms: i32) -> Self {
+        let (requests, receiver) = mpsc::channel();
+        // The connection is opened on the thread, as connections can not be moved between
+        // threads.
+        thread::spawn(move || serve(&bus, &bus_name, timeout_ms, receiver));
+
+        PlayerControls {
+            requests: Arc::new(Mutex::new(requests)),
+        }
+    }
+
+    /// Send a `Play` signal to the player. See [`Player::play`](crate::Player::play).
+    pub fn play(&self) -> Result<(), DBusError> {
+        self.send(Command::Play)
+    }
+
+    /// Send a `Pause` signal to the player. See [`Player::pause`](crate::Player::pause).
+    pub fn pause(&self) -> Result<(), DBusError> {
+        self.send(Command::Pause)
+    }
+
+    /// Send a `PlayPause` signal to the player. See
+    /// [`Player::play_pause`](crate::Player::play_pause).
+    pub fn play_pause(&self) -> Result<(), DBusError> {
+        self.send(Command::PlayPause)
+    }
+
+    /// Send a `Stop` signal to the player. See [`Player::stop`](crate::Player::stop).
+    pub fn stop(&self) -> Result<(), DBusError> {
+        self.send(Command::Stop)
+    }
+
+    /// Send a `Next` signal to the player. See [`Player::next`](crate::Player::next).
+    pub fn next(&self) -> Result<(), DBusError> {
+        self.send(Command::Next)
+    }
+
+    /// Send a `Previous` signal to the player. See [`Player::previous`](crate::Player::previous).
+    pub fn previous(&self) -> Result<(), DBusError> {
+        self.send(Command::Previous)
+    }
+
+    /// Send a `Seek` signal to the player. See [`Player::seek`](crate::Player::seek).
+    pub fn seek(&self, offset_in_microseconds: i64) -> Result<(), DBusError> {
+        self.send(Command::Seek(offset_in_microseconds))
+    }
+
+    /// Sets the position of the current track. See
+    /// [`Player::set_position`](crate::Player::set_position).
+    pub fn set_position(&self, track_id: TrackID, position: &Duration) -> Result<(), DBusError> {
+        self.send(Command::SetPosition(
+            track_id,
+            DurationExtensions::as_micros(position),
+        ))
+    }
+
+    /// Set the volume of the player. See [`Player::set_volume`](crate::Player::set_volume).
+    pub fn set_volume(&self, value: f64) -> Result<(), DBusError> {
+        self.send(Command::SetVolume(value))
+    }
+
+    /// Set the volume of the player to a [`Volume`]. See
+    /// [`Player::set_volume_level`](crate::Player::set_volume_level).
+    pub fn set_volume_level(&self, volume: Volume) -> Result<(), DBusError> {
+        self.send(Command::SetVolume(volume.as_fraction()))
+    }
+
+    /// Set the "Shuffle" setting of the player. See
+    /// [`Player::set_shuffle`](crate::Player::set_shuffle).
+    pub fn set_shuffle(&self, state: bool) -> Result<(), DBusError> {
+        self.send(Command::SetShuffle(state))
+    }
+
+    /// Set the "LoopStatus" setting of the player. See
+    /// [`Player::set_loop_status`](crate::Player::set_loop_status).
+    pub fn set_loop_status(&self, status: LoopStatus) -> Result<(), DBusError> {
+        self.send(Command::SetLoopStatus(status))
+    }
+
+    /// Send an `OpenUri` signal to the player, asking it to open and play the given URI. See
+    /// [`Player::open_uri`](crate::Player::open_uri).
+    pub fn open_uri(&self, uri: &str) -> Result<(), DBusError> {
+        self.send(Command::OpenUri(String::from(uri)))
+    }
+
+    fn send(&self, command: Command) -> Result<(), DBusError> {
+        let stopped =
+            || DBusError::Miscellaneous(String::from("The player's control thread has stopped"));
+        let (reply, result) = mpsc::channel();
+
+        self.requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .send(Request { command, reply })
+            .map_err(|_| stopped())?;
+        result.recv().map_err(|_| stopped())?
+    }
+}
+
+/// Runs commands until every [`PlayerControls`] for the player is dropped.
+///
+/// The connection is opened when the first command arrives, and again after a failed attempt.
+fn serve(bus: &Bus, bus_name: &str, timeout_ms: i32, requests: Receiver<Request>) {
+    let mut connection: Option<Connection> = None;
+
+    for Request { command, reply } in requests {
+        if connection.is_none() {
+            match bus.connect() {
+                Ok(new_connection) => connection = Some(new_connection),
+                Err(error) => {
+                    let _ = reply.send(Err(error.into()));
+                    continue;
+                }
+            }
+        }
+
+        if let Some(ref connection) = connection {
+            let path = connection.with_path(bus_name, MPRIS2_PATH, timeout_ms);
+            let member = command.member();
+            let span = CallSpan::enter(bus_name, member.name);
+            let result = run(path, command)
+                .map_err(|error| error.in_call(member.operation, member.name, bus_name));
+            span.finish(result.is_ok(), 1);
+            let _ = reply.send(result);
+        }
+    }
+}
+
+fn run(path: ConnPath<'_, &Connection>, command: Command) -> Result<(), DBusError> {
+    let rejected = |action: &str, result: Result<(), dbus::Error>| {
+        result.map_err(|error| explain_rejection(path.clone(), action, error.into()))
+    };
+
+    match command {
+        Command::Play => path.play().map_err(DBusError::from),
+        Command::Pause => path.pause().map_err(DBusError::from),
+        Command::PlayPause => path.play_pause().map_err(DBusError::from),
+        Command::Stop => path.stop().map_err(DBusError::from),
+        Command::Next => rejected("go to the next track", path.next()),
+        Command::Previous => rejected("go to the previous track", path.previous()),
+        Command::Seek(offset) => rejected("seek", path.seek(offset)),
+        Command::SetPosition(track_id, position) => rejected(
+            "seek",
+            path.set_position(track_id.as_path(), position as i64),
+        ),
+        Command::SetVolume(value) => path.set_volume(value.max(0.0)).map_err(DBusError::from),
+        Command::SetShuffle(state) => path.set_shuffle(state).map_err(DBusError::from),
+        Command::SetLoopStatus(status) => path
+            .set_loop_status(status.dbus_value())
+            .map_err(DBusError::from),
+        Command::OpenUri(uri) => path.open_uri(&uri).map_err(DBusError::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::connect_to_fake;
+    use crate::{MetadataValue, PlaybackStatus};
+    use dbus::ffidisp::BusType;
+
+    fn assert_send_sync<T: Send + Sync + Clone>(_: &T) {}
+
+    #[test]
+    fn it_controls_the_player_from_another_thread() {
+        let (fake, player) = connect_to_fake("controls");
+        let mut events = player.events().unwrap();
+
+        let controls = player.controls();
+        assert_send_sync(&controls);
+        let other = controls.clone();
+        thread::spawn(move || {
+            other.play().unwrap();
+            other.set_volume(0.5).unwrap();
+            other.set_loop_status(LoopStatus::Track).unwrap();
+            other.open_uri("file:///music/song.ogg").unwrap();
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(fake.calls(), vec!["Play", "OpenUri"]);
+        assert_eq!(fake.property("Volume"), Some(MetadataValue::F64(0.5)));
+        assert_eq!(
+            fake.property("LoopStatus"),
+            Some(MetadataValue::from("Track"))
+        );
+        assert_eq!(
+            player.get_playback_status().unwrap(),
+            PlaybackStatus::Playing
+        );
+        assert!(matches!(events.next(), Some(Ok(crate::Event::Playing))));
+    }
+
+    #[test]
+    fn it_returns_errors_from_each_call() {
+        let controls = PlayerControls::new(
+            Bus::Type(BusType::Session),
+            format!("org.mpris.MediaPlayer2.missing.pid{}", std::process::id()),
+            1_000,
+        );
+
+        let error = controls.play().unwrap_err();
+        assert_eq!(error.kind(), crate::DBusErrorKind::ServiceUnknown);
+        assert!(controls.pause().is_err());
+    }
+}
@@ -0,0 +1,350 @@
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+use crate::metadata::Value;
+use crate::trace;
+use crate::{DBusError, LoopStatus, PlaybackStatus};
+
+/// A property value that a [`Player`](crate::Player) could not decode, and replaced with a
+/// default.
+///
+/// See [`Player::decode_warnings`](crate::Player::decode_warnings).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeWarning {
+    /// The name of the property, like `"LoopStatus"`.
+    pub property: String,
+
+    /// The value that the player sent.
+    pub value: Value,
+
+    /// The default that was used instead, like `"None"`.
+    pub fallback: String,
+}
+
+/// How a property of the `org.mpris.MediaPlayer2.Player` interface is decoded.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PropertyDecoder<T> {
+    pub(crate) name: &'static str,
+
+    /// Decodes the value like the specification says. Used in strict mode.
+    strict: fn(&str, &Value) -> Result<T, DBusError>,
+
+    /// Also accepts values that players get wrong in common ways.
+    lenient: fn(&Value) -> Option<T>,
+
+    /// Used when even lenient decoding fails.
+    fallback: T,
+}
+
+pub(crate) const PLAYBACK_STATUS: PropertyDecoder<PlaybackStatus> = PropertyDecoder {
+    name: "PlaybackStatus",
+    strict: strict_status,
+    lenient: lenient_playback_status,
+    fallback: PlaybackStatus::Stopped,
+};
+
+pub(crate) const LOOP_STATUS: PropertyDecoder<LoopStatus> = PropertyDecoder {
+    name: "LoopStatus",
+    strict: strict_status,
+    lenient: lenient_loop_status,
+    fallback: LoopStatus::None,
+};
+
+pub(crate) const SHUFFLE: PropertyDecoder<bool> = PropertyDecoder {
+    name: "Shuffle",
+    strict: strict_bool,
+    lenient: lenient_bool,
+    fallback: false,
+};
+
+pub(crate) const VOLUME: PropertyDecoder<f64> = PropertyDecoder {
+    name: "Volume",
+    strict: strict_f64,
+    lenient: lenient_f64,
+    fallback: 1.0,
+};
+
+pub(crate) const RATE: PropertyDecoder<f64> = PropertyDecoder {
+    name: "Rate",
+    strict: strict_f64,
+    lenient: lenient_f64,
+    fallback: 1.0,
+};
+
+pub(crate) const POSITION: PropertyDecoder<u64> = PropertyDecoder {
+    name: "Position",
+    strict: strict_position,
+    lenient: lenient_position,
+    fallback: 0,
+};
+
+/// Decoder for one of the `Can*` properties, which fall back to [`false`].
+pub(crate) fn capability(name: &'static str) -> PropertyDecoder<bool> {
+    PropertyDecoder {
+        name,
+        strict: strict_bool,
+        lenient: lenient_bool,
+        fallback: false,
+    }
+}
+
+/// Decodes the properties of one player, and keeps the warnings of lenient decoding.
+#[derive(Debug, Default)]
+pub(crate) struct Decoding {
+    pub(crate) strict: bool,
+    warnings: RefCell<Vec<DecodeWarning>>,
+}
+
+impl Decoding {
+    pub(crate) fn decode<T>(
+        &self,
+        decoder: &PropertyDecoder<T>,
+        value: &Value,
+    ) -> Result<T, DBusError>
+    where
+        T: Copy + Display,
+    {
+        if self.strict {
+            return (decoder.strict)(decoder.name, value);
+        }
+
+        Ok((decoder.lenient)(value).unwrap_or_else(|| {
+            self.warn(DecodeWarning {
+                property: String::from(decoder.name),
+                value: value.clone(),
+                fallback: decoder.fallback.to_string(),
+            });
+            decoder.fallback
+        }))
+    }
+
+    /// Like [`decode`](Self::decode), but gives [`None`] instead of the fallback when lenient
+    /// decoding fails, for properties where an unknown value is better than a made up one.
+    pub(crate) fn decode_or_unknown<T>(
+        &self,
+        decoder: &PropertyDecoder<T>,
+        value: &Value,
+    ) -> Result<Option<T>, DBusError>
+    where
+        T: Copy + Display,
+    {
+        if self.strict {
+            return (decoder.strict)(decoder.name, value).map(Some);
+        }
+
+        Ok((decoder.lenient)(value).or_else(|| {
+            self.warn(DecodeWarning {
+                property: String::from(decoder.name),
+                value: value.clone(),
+                fallback: String::from("unknown"),
+            });
+            None
+        }))
+    }
+
+    pub(crate) fn warnings(&self) -> Vec<DecodeWarning> {
+        self.warnings.borrow().clone()
+    }
+
+    /// Records a warning, unless the same value of the property was already recorded.
+    fn warn(&self, warning: DecodeWarning) {
+        let mut warnings = self.warnings.borrow_mut();
+        if !warnings.contains(&warning) {
+            trace::decode_fallback(&warning);
+            warnings.push(warning);
+        }
+    }
+}
+
+/// Converts a property value, failing like a single read of a property with an unexpected type
+/// would.
+pub(crate) fn unsupported<'a, T, F>(
+    name: &str,
+    value: &'a Value,
+    convert: F,
+) -> Result<T, DBusError>
+where
+    F: FnOnce(&'a Value) -> Option<T>,
+{
+    convert(value).ok_or_else(|| {
+        DBusError::Miscellaneous(format!("{} has an unsupported value: {:?}", name, value))
+    })
+}
+
+fn strict_status<T>(name: &str, value: &Value) -> Result<T, DBusError>
+where
+    T: std::str::FromStr,
+    DBusError: From<T::Err>,
+{
+    Ok(unsupported(name, value, Value::as_str)?.parse()?)
+}
+
+fn strict_bool(name: &str, value: &Value) -> Result<bool, DBusError> {
+    unsupported(name, value, Value::as_bool)
+}
+
+fn strict_f64(name: &str, value: &Value) -> Result<f64, DBusError> {
+    unsupported(name, value, Value::coerce_f64)
+}
+
+fn strict_position(name: &str, value: &Value) -> Result<u64, DBusError> {
+    unsupported(name, value, |value| {
+        value
+            .coerce_integer::<i128>()
+            .and_then(|position| u64::try_from(position.max(0)).ok())
+    })
+}
+
+/// The lowercase, trimmed text of a string value.
+fn text(value: &Value) -> Option<String> {
+    value.as_str().map(|text| text.trim().to_ascii_lowercase())
+}
+
+/// Returns [`true`] if the value is a playback status that can be decoded leniently, and not one
+/// outside of the specification (like `"Buffering"`) that is replaced with the fallback.
+pub(crate) fn is_known_playback_status(value: &Value) -> bool {
+    lenient_playback_status(value).is_some()
+}
+
+fn lenient_playback_status(value: &Value) -> Option<PlaybackStatus> {
+    match text(value)?.as_str() {
+        "playing" | "play" => Some(PlaybackStatus::Playing),
+        "paused" | "pause" => Some(PlaybackStatus::Paused),
+        "stopped" | "stop" | "idle" => Some(PlaybackStatus::Stopped),
+        _ => None,
+    }
+}
+
+fn lenient_loop_status(value: &Value) -> Option<LoopStatus> {
+    match text(value)?.as_str() {
+        "none" | "off" | "no" => Some(LoopStatus::None),
+        "track" | "one" | "single" | "song" => Some(LoopStatus::Track),
+        "playlist" | "all" | "list" => Some(LoopStatus::Playlist),
+        _ => None,
+    }
+}
+
+fn lenient_bool(value: &Value) -> Option<bool> {
+    value
+        .as_bool()
+        .or_else(|| value.coerce_integer::<i64>().map(|number| number != 0))
+        .or_else(|| match text(value)?.as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        })
+}
+
+fn lenient_f64(value: &Value) -> Option<f64> {
+    value
+        .coerce_f64()
+        .or_else(|| text(value)?.parse::<f64>().ok())
+        .filter(|number| number.is_finite())
+}
+
+fn lenient_position(value: &Value) -> Option<u64> {
+    value
+        .coerce_integer::<i128>()
+        .or_else(|| text(value)?.parse::<i128>().ok())
+        .and_then(|position| u64::try_from(position.max(0)).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_accepts_common_mistakes_leniently() {
+        let decoding = Decoding::default();
+
+        assert_eq!(
+            decoding
+                .decode(&PLAYBACK_STATUS, &Value::from("playing"))
+                .unwrap(),
+            PlaybackStatus::Playing
+        );
+        assert_eq!(
+            decoding.decode(&LOOP_STATUS, &Value::from("off")).unwrap(),
+            LoopStatus::None
+        );
+        assert_eq!(decoding.decode(&VOLUME, &Value::I32(1)).unwrap(), 1.0);
+        assert_eq!(decoding.decode(&POSITION, &Value::U64(5)).unwrap(), 5);
+        assert!(decoding.decode(&SHUFFLE, &Value::U8(1)).unwrap());
+        assert!(decoding.warnings().is_empty());
+    }
+
+    #[test]
+    fn it_falls_back_to_defaults_and_records_warnings_once() {
+        let decoding = Decoding::default();
+
+        for _ in 0..2 {
+            assert_eq!(
+                decoding
+                    .decode(&LOOP_STATUS, &Value::from("sometimes"))
+                    .unwrap(),
+                LoopStatus::None
+            );
+        }
+        assert_eq!(
+            decoding.warnings(),
+            vec![DecodeWarning {
+                property: String::from("LoopStatus"),
+                value: Value::from("sometimes"),
+                fallback: String::from("None"),
+            }]
+        );
+    }
+
+    #[test]
+    fn it_can_leave_values_unknown_instead_of_falling_back() {
+        let decoding = Decoding::default();
+
+        assert_eq!(
+            decoding
+                .decode_or_unknown(&POSITION, &Value::from("5"))
+                .unwrap(),
+            Some(5)
+        );
+        assert_eq!(
+            decoding
+                .decode_or_unknown(&POSITION, &Value::from("soon"))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            decoding.warnings(),
+            vec![DecodeWarning {
+                property: String::from("Position"),
+                value: Value::from("soon"),
+                fallback: String::from("unknown"),
+            }]
+        );
+
+        let strict = Decoding {
+            strict: true,
+            ..Decoding::default()
+        };
+        assert!(strict
+            .decode_or_unknown(&POSITION, &Value::from("soon"))
+            .is_err());
+    }
+
+    #[test]
+    fn it_keeps_failing_in_strict_mode() {
+        let decoding = Decoding {
+            strict: true,
+            ..Decoding::default()
+        };
+
+        assert_eq!(
+            decoding
+                .decode(&PLAYBACK_STATUS, &Value::from("playing"))
+                .unwrap(),
+            PlaybackStatus::Playing
+        );
+        assert!(decoding.decode(&LOOP_STATUS, &Value::from("off")).is_err());
+        assert!(decoding.decode(&SHUFFLE, &Value::U8(1)).is_err());
+        assert!(decoding.warnings().is_empty());
+    }
+}
@@ -1,15 +1,27 @@
 use super::{
-    DBusError, LoopStatus, Metadata, PlaybackStatus, Player, Progress, TrackID, TrackList,
-    TrackListError,
+    DBusError, DBusErrorKind, LoopStatus, Metadata, PlaybackStatus, Player, Progress, TrackID,
+    TrackList, TrackListError, Volume,
 };
-use crate::pooled_connection::MprisEvent;
+use crate::decode;
+use crate::extensions::DurationExtensions;
+use crate::pooled_connection::{MprisEvent, Subscription};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+/// How often a blocked [`PlayerEvents`] checks if it was stopped through a [`StopHandle`].
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Represents a change in [`Player`] state.
 ///
 /// Note that this does not include position changes (seeking in a track or normal progress of time
 /// for playing media).
+///
+/// New kinds of events may be added in minor releases, so matches on this need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Event {
     /// [`Player`] was shut down / quit.
     PlayerShutDown,
@@ -23,6 +35,14 @@ pub enum Event {
     /// [`Player`] was stopped.
     Stopped,
 
+    /// [`Player`] reported a playback status that is not in the specification, like
+    /// `"Buffering"`. The status as reported is provided.
+    ///
+    /// [`Progress::playback_status`] is [`PlaybackStatus::Stopped`] while the status is unknown.
+    /// When the player goes back to a known status, [`Event::Playing`], [`Event::Paused`] or
+    /// [`Event::Stopped`] is emitted, even if it is the same as the status before.
+    PlaybackStatusUnknown(String),
+
     /// Loop status of [`Player`] was changed. New [`LoopStatus`] is provided.
     LoopingChanged(LoopStatus),
 
@@ -30,6 +50,9 @@ pub enum Event {
     ShuffleToggled(bool),
 
     /// [`Player`]'s volume was changed. The new volume is provided.
+    ///
+    /// This is the raw value from the player, which can be above `1.0`. Use
+    /// [`Event::volume`] to get it as a [`Volume`].
     VolumeChanged(f64),
 
     /// [`Player`]'s playback rate was changed. New playback rate is provided.
@@ -38,6 +61,30 @@ pub enum Event {
     /// [`Player`]'s track changed. [`Metadata`] of the new track is provided.
     TrackChanged(Metadata),
 
+    /// [`Player`]'s track changed, with details about the track that was playing before.
+    ///
+    /// This is emitted instead of [`Event::TrackChanged`] when
+    /// [`PlayerEvents::detailed_track_changes`] is enabled.
+    TrackChangedDetailed {
+        /// [`Metadata`] of the new track.
+        new: Metadata,
+
+        /// How far the previous track got before the change, as last known. This is calculated
+        /// from the last time the player's state was read (or the last [`Event::Seeked`]), so it
+        /// is accurate as long as the previous track played without interruptions. It is
+        /// [`None`] if the player's position could not be read.
+        ///
+        /// Useful for scrobblers, which only submit tracks that were played for long enough.
+        previous_final_position: Option<Duration>,
+    },
+
+    /// The length of the current track changed, without the track itself changing. The new
+    /// length is provided.
+    ///
+    /// Live streams and some podcasts only report the real length after they started playing.
+    /// Not emitted when the length becomes unknown.
+    TrackLengthChanged(Duration),
+
     /// [`Player`] seeked (changed position in the current track).
     ///
     /// This will only be emitted when the player in question emits this signal. Some players do
@@ -77,10 +124,18 @@ pub enum Event {
 
     /// The track list was replaced.
     TrackListReplaced,
+
+    /// Reading events failed in a way that is likely to be temporary, like a timeout while the
+    /// player is busy or restarting.
+    ///
+    /// This is only emitted when [`PlayerEvents::tolerate_transient_errors`] is enabled;
+    /// otherwise the error is returned as an [`Err`] item instead.
+    TransientError(EventError),
 }
 
 /// Errors that can occur while processing event streams.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum EventError {
     /// Something went wrong with the D-Bus communication. See the [`DBusError`] type.
     #[error("D-Bus communication failed: {0}")]
@@ -91,6 +146,50 @@ pub enum EventError {
     TrackListError(#[from] TrackListError),
 }
 
+impl Event {
+    /// Returns the new volume of an [`Event::VolumeChanged`] as a [`Volume`], or [`None`] for
+    /// other events.
+    pub fn volume(&self) -> Option<Volume> {
+        match self {
+            Event::VolumeChanged(volume) => Some(Volume::from_fraction(*volume)),
+            _ => None,
+        }
+    }
+}
+
+impl EventError {
+    /// Returns [`true`] if this error is likely to go away by itself, like when a player did not
+    /// reply in time or was restarting.
+    ///
+    /// Errors caused by the player sending unexpected data are not considered transient.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            EventError::DBusError(error) => is_transient_dbus_error(error),
+            EventError::TrackListError(TrackListError::DBusError(error)) => {
+                is_transient_dbus_error(error)
+            }
+            EventError::TrackListError(TrackListError::BorrowError(_)) => false,
+        }
+    }
+
+    /// Returns the [`DBusErrorKind`] of the underlying [`DBusError`], or [`None`] if the error
+    /// did not come from D-Bus.
+    pub fn kind(&self) -> Option<DBusErrorKind> {
+        match self {
+            EventError::DBusError(error) => Some(error.kind()),
+            EventError::TrackListError(TrackListError::DBusError(error)) => Some(error.kind()),
+            EventError::TrackListError(TrackListError::BorrowError(_)) => None,
+        }
+    }
+}
+
+pub(crate) fn is_transient_dbus_error(error: &DBusError) -> bool {
+    matches!(
+        error.kind(),
+        DBusErrorKind::Timeout | DBusErrorKind::ServiceUnknown
+    )
+}
+
 /// Iterator that blocks forever until the player has an [`Event`].
 ///
 /// Iteration will stop if player stops running. If the player was running before this iterator
@@ -98,57 +197,499 @@ pub enum EventError {
 ///
 /// If multiple events are found between processing D-Bus events then all of them will be iterated
 /// in rapid succession before processing more events.
+///
+/// ## Ordering
+///
+/// When several properties of the player change at once, the events are emitted in this order:
+///
+/// 1. [`Event::TrackChanged`] (or [`Event::TrackChangedDetailed`]), or
+///    [`Event::TrackLengthChanged`]
+/// 2. [`Event::Playing`], [`Event::Paused`], [`Event::Stopped`] or
+///    [`Event::PlaybackStatusUnknown`]
+/// 3. [`Event::LoopingChanged`]
+/// 4. [`Event::ShuffleToggled`]
+/// 5. [`Event::VolumeChanged`]
+/// 6. [`Event::PlaybackRateChanged`]
+///
+/// This means that the new track is known when a "play" event for it arrives. Events from other
+/// signals (like [`Event::Seeked`] and the track list events) are emitted in the order the
+/// signals arrived, before the property changes that were found in the same batch.
+///
+/// ## Several watchers
+///
+/// Any number of [`PlayerEvents`] and [`ProgressTracker`](crate::ProgressTracker)s can watch the
+/// same player, and players found by the same [`PlayerFinder`](crate::PlayerFinder) share one
+/// D-Bus connection. Signals are read from the connection once and then delivered to all active
+/// watchers of the player, exactly once each.
 #[derive(Debug)]
 pub struct PlayerEvents<'a> {
     /// [`Player`] to watch.
     player: &'a Player,
 
-    /// Queued up events found after the last signal.
-    buffer: Vec<Event>,
+    /// This iterator's queue of the player's signals.
+    subscription: Subscription,
+
+    /// Queued up events, oldest first. Each read appends its events at the back, so events from
+    /// an earlier read are always yielded before the ones from a later read.
+    buffer: VecDeque<Event>,
+
+    /// Used to diff older state to find events.
+    state: EventState,
+
+    /// Emit transient errors as [`Event::TransientError`] instead of as [`Err`] items.
+    tolerate_transient_errors: bool,
 
+    /// Set from other threads to stop iteration.
+    stopped: Arc<AtomicBool>,
+
+    /// Holds back [`Event::Stopped`] for a while, if enabled.
+    stopped_debounce: Option<StoppedDebounce>,
+
+    /// Read the player's state after this long without a signal, if enabled.
+    poll_interval: Option<Duration>,
+
+    /// When the player's state was last read, to know when the next poll is due.
+    last_read: Instant,
+}
+
+/// Collects the options for a [`PlayerEvents`] iterator, to create several iterators with the
+/// same configuration or to keep the configuration apart from the [`Player`].
+///
+/// Every option has the same default as [`Player::events`] uses.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use mpris::{PlayerEventsBuilder, PlayerFinder};
+/// # use std::time::Duration;
+/// # let player = PlayerFinder::new().unwrap().find_active().unwrap();
+/// let events = PlayerEventsBuilder::new()
+///     .tolerate_transient_errors(true)
+///     .detailed_track_changes(true)
+///     .stabilize_stopped(Duration::from_secs(1))
+///     .playback_status_transitions(true)
+///     .with_poll_interval(Duration::from_secs(2))
+///     .build(&player)
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerEventsBuilder {
+    tolerate_transient_errors: bool,
+    detailed_track_changes: bool,
+    stabilize_stopped: Duration,
+    playback_status_transitions: bool,
+    poll_interval: Duration,
+}
+
+impl PlayerEventsBuilder {
+    /// Create a builder with the default options.
+    pub fn new() -> Self {
+        PlayerEventsBuilder::default()
+    }
+
+    /// See [`PlayerEvents::tolerate_transient_errors`].
+    pub fn tolerate_transient_errors(mut self, tolerate: bool) -> Self {
+        self.tolerate_transient_errors = tolerate;
+        self
+    }
+
+    /// See [`PlayerEvents::detailed_track_changes`].
+    pub fn detailed_track_changes(mut self, enabled: bool) -> Self {
+        self.detailed_track_changes = enabled;
+        self
+    }
+
+    /// See [`PlayerEvents::stabilize_stopped`].
+    pub fn stabilize_stopped(mut self, window: Duration) -> Self {
+        self.stabilize_stopped = window;
+        self
+    }
+
+    /// See [`PlayerEvents::playback_status_transitions`].
+    pub fn playback_status_transitions(mut self, enabled: bool) -> Self {
+        self.playback_status_transitions = enabled;
+        self
+    }
+
+    /// See [`PlayerEvents::with_poll_interval`].
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Start watching `player` with these options.
+    ///
+    /// Fails if the player's current state could not be read.
+    pub fn build<'a>(&self, player: &'a Player) -> Result<PlayerEvents<'a>, DBusError> {
+        Ok(PlayerEvents::new(player)?
+            .tolerate_transient_errors(self.tolerate_transient_errors)
+            .detailed_track_changes(self.detailed_track_changes)
+            .stabilize_stopped(self.stabilize_stopped)
+            .playback_status_transitions(self.playback_status_transitions)
+            .with_poll_interval(self.poll_interval))
+    }
+}
+
+/// A handle to stop a [`PlayerEvents`] iterator from another thread.
+///
+/// Get one from [`PlayerEvents::stop_handle`]. After [`stop`](Self::stop) is called, the iterator
+/// returns [`None`] from its next call to `next`, or within a fraction of a second if it is
+/// currently blocked waiting for events.
+#[derive(Debug, Clone)]
+pub struct StopHandle(Arc<AtomicBool>);
+
+impl StopHandle {
+    /// Stop the [`PlayerEvents`] iterator. This can't be undone.
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns [`true`] if [`stop`](Self::stop) has been called.
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// The last known state of a [`Player`], used to turn D-Bus signals into [`Event`]s.
+#[derive(Debug)]
+pub(crate) struct EventState {
     /// Used to diff older state to find events.
     last_progress: Progress,
 
     /// Current tracklist of the player. Will be kept up to date.
     track_list: Option<TrackList>,
+
+    /// Emit [`Event::TrackChangedDetailed`] instead of [`Event::TrackChanged`].
+    detailed_track_changes: bool,
+
+    /// Emit an event for every `PlaybackStatus` in the signals, not only for the net change.
+    playback_status_transitions: bool,
 }
 
 impl PlayerEvents<'_> {
     pub(crate) fn new(player: &Player) -> Result<PlayerEvents, DBusError> {
-        let progress = Progress::from_player(player)?;
+        // Subscribe before taking the snapshot so no changes are missed in between.
+        let subscription = player.subscribe();
         Ok(PlayerEvents {
             player,
-            buffer: Vec::new(),
-            last_progress: progress,
-            track_list: player.checked_get_track_list()?,
+            subscription,
+            buffer: VecDeque::new(),
+            state: EventState::new(player)?,
+            tolerate_transient_errors: false,
+            stopped: Arc::new(AtomicBool::new(false)),
+            stopped_debounce: None,
+            poll_interval: None,
+            last_read: Instant::now(),
         })
     }
 
+    /// Returns a [`StopHandle`] that can be sent to other threads to stop this iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use mpris::PlayerFinder;
+    /// # let player = PlayerFinder::new().unwrap().find_active().unwrap();
+    /// let events = player.events().unwrap();
+    /// let stop_handle = events.stop_handle();
+    ///
+    /// std::thread::spawn(move || {
+    ///     std::thread::sleep(std::time::Duration::from_secs(10));
+    ///     stop_handle.stop();
+    /// });
+    ///
+    /// for event in events {
+    ///     println!("{:?}", event);
+    /// }
+    /// // Stops after 10 seconds.
+    /// ```
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle(Arc::clone(&self.stopped))
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Choose how errors that are likely to be temporary are reported. See
+    /// [`EventError::is_transient`] for what is considered transient.
+    ///
+    /// When enabled, transient errors are emitted as [`Event::TransientError`] so consumers that
+    /// stop on the first [`Err`] keep watching the player. Other errors are still returned as
+    /// [`Err`]. Iteration still stops when the player shuts down. A player that leaves the bus
+    /// while its state is being read is reported as [`Event::PlayerShutDown`] either way.
+    ///
+    /// Disabled by default.
+    pub fn tolerate_transient_errors(mut self, tolerate: bool) -> Self {
+        self.tolerate_transient_errors = tolerate;
+        self
+    }
+
+    /// Choose to emit [`Event::TrackChangedDetailed`] instead of [`Event::TrackChanged`], which
+    /// also tells how far the previous track was played.
+    ///
+    /// Disabled by default.
+    pub fn detailed_track_changes(mut self, enabled: bool) -> Self {
+        self.state.detailed_track_changes = enabled;
+        self
+    }
+
+    /// Choose to emit an event for every playback status that the player reports, instead of only
+    /// for the net change since the player's state was last read.
+    ///
+    /// Signals that arrive close together are handled as one batch, so a player that goes from
+    /// paused to playing and back to paused within a batch normally produces no event at all.
+    /// When enabled, the `PlaybackStatus` values in the player's `PropertiesChanged` signals are
+    /// used to emit [`Event::Playing`] and [`Event::Paused`] (or [`Event::Stopped`]) in order,
+    /// which is useful to count plays accurately. Repeated statuses only produce one event.
+    ///
+    /// Players that change their status without including it in the signal still only produce
+    /// the net change. Disabled by default.
+    pub fn playback_status_transitions(mut self, enabled: bool) -> Self {
+        self.state.playback_status_transitions = enabled;
+        self
+    }
+
+    /// Hold back [`Event::Stopped`] for `window`, and drop it if the player starts playing or
+    /// pauses again before that.
+    ///
+    /// Some players briefly report that they are stopped between tracks. With a window of a
+    /// second or so, those players produce a single [`Event::TrackChanged`] instead of a
+    /// [`Event::Stopped`] followed by [`Event::Playing`]. When the stop is cancelled, the event for
+    /// the status that the player returns to is only emitted if it differs from the status before
+    /// the stop. A stop that lasts for the whole window is emitted at the end of it.
+    ///
+    /// A zero `window` disables this again. Disabled by default.
+    pub fn stabilize_stopped(mut self, window: Duration) -> Self {
+        self.stopped_debounce = if window > Duration::new(0, 0) {
+            Some(StoppedDebounce {
+                window,
+                pending: None,
+            })
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Read the player's state when it sent no signal for `interval`, and emit events for what
+    /// changed since the last read.
+    ///
+    /// Some players update their state but never send `PropertiesChanged`, so no events are ever
+    /// detected for them. With polling, changes of those players are found at most `interval`
+    /// late. Every poll reads all properties of the player, so only enable this for players that
+    /// are known to need it, and with an interval of a second or more.
+    ///
+    /// A zero `interval` disables this again. Disabled by default.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = if interval > Duration::new(0, 0) {
+            Some(interval)
+        } else {
+            None
+        };
+        self
+    }
+
     /// Current tracklist of the player. Will be kept up to date.
     pub fn track_list(&self) -> Option<&TrackList> {
-        self.track_list.as_ref()
+        self.state.track_list.as_ref()
+    }
+
+    /// The [`Progress`] snapshot that the last events were compared against, as of the last time
+    /// the player's state was read.
+    ///
+    /// Use this instead of reading a new [`Progress`] right after handling an event; it costs no
+    /// D-Bus calls. The state in it is as old as the last event, and [`Progress::position`] is
+    /// calculated from the position read back then, so it drifts when the player stalls or seeks
+    /// without a signal.
+    pub fn last_progress(&self) -> &Progress {
+        &self.state.last_progress
     }
 
     fn read_events(&mut self) -> Result<(), EventError> {
-        self.player.process_events_blocking_until_received();
+        let mut poll_due = false;
+
+        // Wake up regularly to see if we were stopped, or if a held back stop or a poll is due.
+        while !self.subscription.has_pending_events() {
+            if self.is_stopped() {
+                return Ok(());
+            }
+
+            let now = Instant::now();
+            let mut timeout = STOP_POLL_INTERVAL;
+            if let Some(interval) = self.poll_interval {
+                let next_poll = self.last_read + interval;
+                if now >= next_poll {
+                    poll_due = true;
+                    break;
+                }
+                timeout = timeout.min(next_poll - now);
+            }
+            if let Some(ref mut debounce) = self.stopped_debounce {
+                if debounce.take_expired(now) {
+                    self.buffer.push_back(Event::Stopped);
+                    return Ok(());
+                }
+                if let Some(time_left) = debounce.time_left(now) {
+                    timeout = timeout.min(time_left);
+                }
+            }
 
+            self.subscription
+                .connection()
+                .process_next_event_blocking_for(timeout);
+        }
+
+        let mut events = self.subscription.pending_events();
+        if poll_due {
+            // Read the state just like after a signal that the player did not send.
+            events.push(MprisEvent::PlayerPropertiesChanged {
+                metadata_changed: true,
+                playback_status: None,
+            });
+        }
+        self.last_read = Instant::now();
+
+        let status_before = self.state.last_progress.playback_status();
+        let mut batch = Vec::new();
+        let result = self.state.process(self.player, events, &mut batch);
+        if let Some(ref mut debounce) = self.stopped_debounce {
+            debounce.filter(status_before, &mut batch, Instant::now());
+        }
+        self.buffer.extend(batch);
+        result
+    }
+}
+
+/// Holds back [`Event::Stopped`] for a window of time. See [`PlayerEvents::stabilize_stopped`].
+#[derive(Debug)]
+struct StoppedDebounce {
+    window: Duration,
+    pending: Option<PendingStop>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingStop {
+    deadline: Instant,
+    status_before: PlaybackStatus,
+}
+
+impl StoppedDebounce {
+    /// Removes [`Event::Stopped`] from a new batch of events, and cancels a held back stop when
+    /// the player is playing or paused again, in the batch or in a later one.
+    fn filter(&mut self, status_before: PlaybackStatus, buffer: &mut Vec<Event>, now: Instant) {
+        if buffer
+            .iter()
+            .any(|event| matches!(event, Event::PlayerShutDown))
+        {
+            self.pending = None;
+            return;
+        }
+
+        // A batch can have several status events with `PlayerEvents::playback_status_transitions`.
+        let mut status = status_before;
+        let mut index = 0;
+        while index < buffer.len() {
+            let next = match buffer[index] {
+                Event::Playing => PlaybackStatus::Playing,
+                Event::Paused => PlaybackStatus::Paused,
+                Event::Stopped => PlaybackStatus::Stopped,
+                _ => {
+                    index += 1;
+                    continue;
+                }
+            };
+
+            if next == PlaybackStatus::Stopped {
+                buffer.remove(index);
+                if self.pending.is_none() {
+                    self.pending = Some(PendingStop {
+                        deadline: now + self.window,
+                        status_before: status,
+                    });
+                }
+            } else if self.pending.take().map(|pending| pending.status_before) == Some(next) {
+                buffer.remove(index);
+            } else {
+                index += 1;
+            }
+            status = next;
+        }
+    }
+
+    /// Returns [`true`] once if a held back stop has lasted for the whole window.
+    fn take_expired(&mut self, now: Instant) -> bool {
+        match self.pending {
+            Some(pending) if now >= pending.deadline => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn time_left(&self, now: Instant) -> Option<Duration> {
+        self.pending
+            .map(|pending| pending.deadline.saturating_duration_since(now))
+    }
+}
+
+impl EventState {
+    pub(crate) fn new(player: &Player) -> Result<Self, DBusError> {
+        Ok(EventState {
+            last_progress: Progress::from_player(player)?,
+            track_list: player.checked_get_track_list()?,
+            detailed_track_changes: false,
+            playback_status_transitions: false,
+        })
+    }
+
+    /// Updates the state from the pending D-Bus events of the player, and pushes the resulting
+    /// [`Event`]s to `buffer`.
+    pub(crate) fn process(
+        &mut self,
+        player: &Player,
+        events: Vec<MprisEvent>,
+        buffer: &mut Vec<Event>,
+    ) -> Result<(), EventError> {
         let mut new_progress: Option<Progress> = None;
         let mut reload_track_list = false;
+        let mut status_transitions = Vec::new();
 
-        for event in self.player.pending_events().into_iter() {
+        for event in events.into_iter() {
             match event {
                 MprisEvent::PlayerQuit => {
-                    self.buffer.push(Event::PlayerShutDown);
+                    buffer.push(Event::PlayerShutDown);
                     return Ok(());
                 }
-                MprisEvent::PlayerPropertiesChanged => {
+                MprisEvent::PlayerPropertiesChanged {
+                    playback_status, ..
+                } => {
+                    // Statuses outside of the specification are reported as
+                    // Event::PlaybackStatusUnknown from the refreshed Progress instead.
+                    let status = playback_status
+                        .filter(decode::is_known_playback_status)
+                        .and_then(|status| {
+                            player
+                                .decode_property(&decode::PLAYBACK_STATUS, &status)
+                                .ok()
+                        });
+                    if let Some(status) = status {
+                        if self.playback_status_transitions {
+                            status_transitions.push(status);
+                        }
+                    }
                     if new_progress.is_none() {
-                        new_progress = Some(Progress::from_player(self.player)?);
+                        new_progress = Some(Progress::from_player(player)?);
                     }
                 }
                 MprisEvent::Seeked { position_in_us } => {
-                    self.buffer.push(Event::Seeked { position_in_us })
+                    // Keep the position up to date for Event::TrackChangedDetailed.
+                    self.last_progress
+                        .apply_seek(Duration::from_micros_ext(position_in_us));
+                    buffer.push(Event::Seeked { position_in_us })
                 }
+                MprisEvent::RootPropertiesChanged => {}
                 MprisEvent::TrackListPropertiesChanged => {
                     reload_track_list = true;
                 }
@@ -156,27 +697,34 @@ impl PlayerEvents<'_> {
                     if let Some(ref mut list) = self.track_list {
                         list.replace(ids.into_iter().map(TrackID::from).collect());
                     }
-                    self.buffer.push(Event::TrackListReplaced);
+                    buffer.push(Event::TrackListReplaced);
                 }
-                MprisEvent::TrackAdded { after_id, metadata } => {
+                MprisEvent::TrackAdded {
+                    after_id,
+                    mut metadata,
+                } => {
+                    player.normalize_metadata(&mut metadata);
                     if let Some(id) = metadata.track_id() {
                         if let Some(ref mut list) = self.track_list {
                             list.insert(&after_id, metadata);
                         }
-                        self.buffer.push(Event::TrackAdded(id));
+                        buffer.push(Event::TrackAdded(id));
                     }
                 }
                 MprisEvent::TrackRemoved { id } => {
                     if let Some(ref mut list) = self.track_list {
                         list.remove(&id);
                     }
-                    self.buffer.push(Event::TrackRemoved(id));
+                    buffer.push(Event::TrackRemoved(id));
                 }
-                MprisEvent::TrackMetadataChanged { old_id, metadata } => {
+                MprisEvent::TrackMetadataChanged {
+                    old_id,
+                    mut metadata,
+                } => {
+                    player.normalize_metadata(&mut metadata);
                     if let Some(ref mut list) = self.track_list {
                         if let Some(new_id) = list.replace_track_metadata(&old_id, metadata) {
-                            self.buffer
-                                .push(Event::TrackMetadataChanged { old_id, new_id });
+                            buffer.push(Event::TrackMetadataChanged { old_id, new_id });
                         }
                     }
                 }
@@ -184,107 +732,766 @@ impl PlayerEvents<'_> {
         }
 
         if let Some(progress) = new_progress {
-            self.detect_playback_status_events(&progress);
-            self.detect_loop_status_events(&progress);
-            reload_track_list |= self.detect_shuffle_events(&progress);
-            self.detect_volume_events(&progress);
-            self.detect_playback_rate_events(&progress);
-            self.detect_metadata_events(&progress);
+            reload_track_list |= detect_changes(
+                &self.last_progress,
+                &progress,
+                &status_transitions,
+                self.detailed_track_changes,
+                buffer,
+            );
             self.last_progress = progress;
         }
 
         if reload_track_list && self.track_list.is_some() {
-            if let Some(new_tracks) = self.player.checked_get_track_list()? {
+            if let Some(new_tracks) = player.checked_get_track_list()? {
                 match self.track_list {
                     Some(ref mut list) => list.replace(new_tracks),
                     None => self.track_list = Some(new_tracks),
                 }
-                self.buffer.push(Event::TrackListReplaced);
+                buffer.push(Event::TrackListReplaced);
             }
         }
 
         Ok(())
     }
+}
 
-    fn detect_playback_status_events(&mut self, new_progress: &Progress) {
-        match new_progress.playback_status() {
-            status if self.last_progress.playback_status() == status => {}
-            PlaybackStatus::Playing => self.buffer.push(Event::Playing),
-            PlaybackStatus::Paused => self.buffer.push(Event::Paused),
-            PlaybackStatus::Stopped => self.buffer.push(Event::Stopped),
-        }
-    }
+/// Compares two snapshots of a player and pushes an [`Event`] for each change to `buffer`, in the
+/// order documented on [`PlayerEvents`]. Returns [`true`] if shuffle changed, as the track list
+/// then needs to be reloaded.
+///
+/// `status_transitions` are the playback statuses that signals reported between the snapshots,
+/// in order. An event is emitted for each of them that differs from the one before.
+fn detect_changes(
+    old: &Progress,
+    new: &Progress,
+    status_transitions: &[PlaybackStatus],
+    detailed_track_changes: bool,
+    buffer: &mut Vec<Event>,
+) -> bool {
+    detect_metadata_events(old, new, detailed_track_changes, buffer);
+    detect_playback_status_events(old, new, status_transitions, buffer);
+    detect_loop_status_events(old, new, buffer);
+    let shuffle_changed = detect_shuffle_events(old, new, buffer);
+    detect_volume_events(old, new, buffer);
+    detect_playback_rate_events(old, new, buffer);
+    shuffle_changed
+}
 
-    fn detect_loop_status_events(&mut self, new_progress: &Progress) {
-        let loop_status = new_progress.loop_status();
-        if self.last_progress.loop_status() != loop_status {
-            self.buffer.push(Event::LoopingChanged(loop_status));
+fn detect_playback_status_events(
+    old: &Progress,
+    new: &Progress,
+    transitions: &[PlaybackStatus],
+    buffer: &mut Vec<Event>,
+) {
+    // Unknown statuses are not part of the transitions, so they are only found in the snapshots.
+    let known = |progress: &Progress| match progress.unknown_playback_status() {
+        Some(_) => None,
+        None => Some(progress.playback_status()),
+    };
+    let mut current = known(old);
+    for &status in transitions.iter().chain(known(new).as_ref()) {
+        if Some(status) == current {
+            continue;
         }
+        current = Some(status);
+        buffer.push(match status {
+            PlaybackStatus::Playing => Event::Playing,
+            PlaybackStatus::Paused => Event::Paused,
+            PlaybackStatus::Stopped => Event::Stopped,
+        });
     }
 
-    fn detect_shuffle_events(&mut self, new_progress: &Progress) -> bool {
-        let status = new_progress.shuffle();
-        if self.last_progress.shuffle() != status {
-            self.buffer.push(Event::ShuffleToggled(status));
-            true
-        } else {
-            false
+    if let Some(status) = new.unknown_playback_status() {
+        if current.is_some() || old.unknown_playback_status() != Some(status) {
+            buffer.push(Event::PlaybackStatusUnknown(String::from(status)));
         }
     }
+}
 
-    fn detect_volume_events(&mut self, new_progress: &Progress) {
-        let volume = new_progress.current_volume();
-        if is_different_float(self.last_progress.current_volume(), volume) {
-            self.buffer.push(Event::VolumeChanged(volume));
-        }
+fn detect_loop_status_events(old: &Progress, new: &Progress, buffer: &mut Vec<Event>) {
+    let loop_status = new.loop_status();
+    if old.loop_status() != loop_status {
+        buffer.push(Event::LoopingChanged(loop_status));
     }
+}
 
-    fn detect_playback_rate_events(&mut self, new_progress: &Progress) {
-        let rate = new_progress.playback_rate();
-        if is_different_float(self.last_progress.playback_rate(), rate) {
-            self.buffer.push(Event::PlaybackRateChanged(rate));
-        }
+fn detect_shuffle_events(old: &Progress, new: &Progress, buffer: &mut Vec<Event>) -> bool {
+    let status = new.shuffle();
+    if old.shuffle() != status {
+        buffer.push(Event::ShuffleToggled(status));
+        true
+    } else {
+        false
     }
+}
 
-    fn detect_metadata_events(&mut self, new_progress: &Progress) {
-        let new_metadata = new_progress.metadata();
-        let old_metadata = self.last_progress.metadata();
+fn detect_volume_events(old: &Progress, new: &Progress, buffer: &mut Vec<Event>) {
+    let volume = new.current_volume();
+    if !old.volume_equals(new) {
+        buffer.push(Event::VolumeChanged(volume));
+    }
+}
 
-        // As a workaround for Players not setting a valid track ID, we also check against the URL
-        // Title and artists are checked to detect changes for streams (radios) because track ID and URL don't change.
-        // Title is checked first because most radios set title to `Artist - Title` and have the station name in artists.
+fn detect_playback_rate_events(old: &Progress, new: &Progress, buffer: &mut Vec<Event>) {
+    let rate = new.playback_rate();
+    if !old.rate_equals(new) {
+        buffer.push(Event::PlaybackRateChanged(rate));
+    }
+}
 
-        if old_metadata.track_id() != new_metadata.track_id()
-            || old_metadata.url() != new_metadata.url()
-            || old_metadata.title() != new_metadata.title()
-            || old_metadata.artists() != new_metadata.artists()
-        {
-            self.buffer.push(Event::TrackChanged(new_metadata.clone()));
+fn detect_metadata_events(old: &Progress, new: &Progress, detailed: bool, buffer: &mut Vec<Event>) {
+    if !is_track_change(old.metadata(), new.metadata()) {
+        let length = new.length();
+        if length.is_some() && length != old.length() {
+            buffer.extend(length.map(Event::TrackLengthChanged));
         }
+        return;
+    }
+
+    let metadata = new.metadata().clone();
+    if detailed {
+        buffer.push(Event::TrackChangedDetailed {
+            new: metadata,
+            previous_final_position: old.last_position_at(*new.created_at()),
+        });
+    } else {
+        buffer.push(Event::TrackChanged(metadata));
     }
 }
 
-fn is_different_float(a: f64, b: f64) -> bool {
-    (a - b).abs() >= ::std::f64::EPSILON
+/// Returns [`true`] if the metadata is for a different track. Used for both
+/// [`Event::TrackChanged`] and [`ProgressTick::track_changed`](crate::ProgressTick::track_changed)
+/// so that they always agree.
+pub(crate) fn is_track_change(old_metadata: &Metadata, new_metadata: &Metadata) -> bool {
+    // As a workaround for Players not setting a valid track ID, we also check against the URL
+    // Title and artists are checked to detect changes for streams (radios) because track ID and URL don't change.
+    // Title is checked first because most radios set title to `Artist - Title` and have the station name in artists.
+
+    old_metadata.track_id() != new_metadata.track_id()
+        || old_metadata.url() != new_metadata.url()
+        || old_metadata.title() != new_metadata.title()
+        || old_metadata.artists() != new_metadata.artists()
 }
 
 impl<'a> Iterator for PlayerEvents<'a> {
     type Item = Result<Event, EventError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.is_stopped() {
+            return None;
+        }
+
         while self.buffer.is_empty() {
             // Stop iteration when player is not running. Why beat a dead horse?
-            if !self.player.is_running() {
+            if self.is_stopped() || !self.player.is_running() {
                 return None;
             }
 
             match self.read_events() {
                 Ok(_) => {}
+                // The player left the bus between the signal and reading its state.
+                Err(err) if err.kind() == Some(DBusErrorKind::ServiceUnknown) => {
+                    return Some(Ok(Event::PlayerShutDown))
+                }
+                Err(err) if self.tolerate_transient_errors && err.is_transient() => {
+                    return Some(Ok(Event::TransientError(err)))
+                }
                 Err(err) => return Some(Err(err)),
             };
         }
 
-        let event = self.buffer.remove(0);
-        Some(Ok(event))
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+/// Iterator over the tracks that a [`Player`] changes to.
+///
+/// Get one from [`Player::track_change_stream`]. It yields the [`Metadata`] of every new track,
+/// skips all other changes of the player, and ends when the player shuts down. Like
+/// [`PlayerEvents`], it blocks until the next change.
+#[derive(Debug)]
+pub struct TrackChanges<'a> {
+    events: PlayerEvents<'a>,
+}
+
+impl<'a> TrackChanges<'a> {
+    pub(crate) fn new(player: &'a Player) -> Result<Self, DBusError> {
+        Ok(TrackChanges {
+            events: PlayerEvents::new(player)?,
+        })
+    }
+
+    /// Returns a [`StopHandle`] that stops this iterator from another thread. See
+    /// [`PlayerEvents::stop_handle`].
+    pub fn stop_handle(&self) -> StopHandle {
+        self.events.stop_handle()
+    }
+}
+
+impl<'a> Iterator for TrackChanges<'a> {
+    type Item = Result<Metadata, DBusError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.events.next()? {
+                Ok(Event::TrackChanged(metadata)) => return Some(Ok(metadata)),
+                Ok(Event::PlayerShutDown) => return None,
+                Ok(_) => {}
+                Err(EventError::DBusError(error))
+                | Err(EventError::TrackListError(TrackListError::DBusError(error))) => {
+                    return Some(Err(error))
+                }
+                Err(error @ EventError::TrackListError(_)) => {
+                    return Some(Err(DBusError::Miscellaneous(error.to_string())))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::connect_to_fake;
+
+    fn transport_error(name: &str) -> EventError {
+        EventError::DBusError(DBusError::TransportError(dbus::Error::new_custom(
+            name, "message",
+        )))
+    }
+
+    #[test]
+    fn it_shares_stops_between_handles() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let handle = StopHandle(Arc::new(AtomicBool::new(false)));
+        let other = handle.clone();
+        assert_send(&other);
+        assert!(!handle.is_stopped());
+
+        std::thread::spawn(move || other.stop()).join().unwrap();
+        assert!(handle.is_stopped());
+    }
+
+    #[test]
+    fn it_treats_timeouts_and_restarts_as_transient() {
+        assert!(transport_error("org.freedesktop.DBus.Error.NoReply").is_transient());
+        assert!(transport_error("org.freedesktop.DBus.Error.ServiceUnknown").is_transient());
+
+        let track_list_error =
+            EventError::TrackListError(TrackListError::DBusError(DBusError::TransportError(
+                dbus::Error::new_custom("org.freedesktop.DBus.Error.Timeout", "message"),
+            )));
+        assert!(track_list_error.is_transient());
+    }
+
+    #[test]
+    fn it_treats_bad_data_as_fatal() {
+        assert!(!transport_error("org.freedesktop.DBus.Error.AccessDenied").is_transient());
+        assert!(
+            !EventError::DBusError(DBusError::EnumParseError(String::from("Buffering")))
+                .is_transient()
+        );
+    }
+
+    fn progress(track_id: &str, status: PlaybackStatus, volume: f64) -> Progress {
+        Progress::for_test(
+            Metadata::new(track_id),
+            status,
+            false,
+            LoopStatus::None,
+            volume,
+            1.0,
+        )
+    }
+
+    fn names(events: &[Event]) -> Vec<&'static str> {
+        events
+            .iter()
+            .map(|event| match event {
+                Event::TrackChanged(_) => "TrackChanged",
+                Event::TrackChangedDetailed { .. } => "TrackChangedDetailed",
+                Event::TrackLengthChanged(_) => "TrackLengthChanged",
+                Event::Playing => "Playing",
+                Event::Paused => "Paused",
+                Event::Stopped => "Stopped",
+                Event::PlaybackStatusUnknown(_) => "PlaybackStatusUnknown",
+                Event::LoopingChanged(_) => "LoopingChanged",
+                Event::ShuffleToggled(_) => "ShuffleToggled",
+                Event::VolumeChanged(_) => "VolumeChanged",
+                Event::PlaybackRateChanged(_) => "PlaybackRateChanged",
+                _ => "other",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_emits_track_changes_before_playback_changes() {
+        let old = progress("/1", PlaybackStatus::Paused, 1.0);
+        let new = progress("/2", PlaybackStatus::Playing, 1.0);
+
+        let mut buffer = Vec::new();
+        detect_changes(&old, &new, &[], false, &mut buffer);
+        assert_eq!(names(&buffer), vec!["TrackChanged", "Playing"]);
+    }
+
+    #[test]
+    fn it_emits_all_changes_in_documented_order() {
+        let old = progress("/1", PlaybackStatus::Playing, 1.0);
+        let new = Progress::for_test(
+            Metadata::new("/2"),
+            PlaybackStatus::Paused,
+            true,
+            LoopStatus::Track,
+            0.5,
+            2.0,
+        );
+
+        let mut buffer = Vec::new();
+        let shuffle_changed = detect_changes(&old, &new, &[], false, &mut buffer);
+        assert!(shuffle_changed);
+        assert_eq!(
+            names(&buffer),
+            vec![
+                "TrackChanged",
+                "Paused",
+                "LoopingChanged",
+                "ShuffleToggled",
+                "VolumeChanged",
+                "PlaybackRateChanged",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reports_how_far_the_previous_track_got() {
+        let mut old = progress("/1", PlaybackStatus::Paused, 1.0);
+        old.apply_seek(Duration::from_secs(42));
+        let new = progress("/2", PlaybackStatus::Playing, 1.0);
+
+        let mut buffer = Vec::new();
+        detect_changes(&old, &new, &[], true, &mut buffer);
+        assert_eq!(names(&buffer), vec!["TrackChangedDetailed", "Playing"]);
+        match buffer[0] {
+            Event::TrackChangedDetailed {
+                ref new,
+                previous_final_position,
+            } => {
+                assert_eq!(new.track_id().unwrap().as_str(), "/2");
+                assert_eq!(previous_final_position, Some(Duration::from_secs(42)));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn it_emits_length_changes_within_a_track() {
+        fn with_length(track_id: &str, length_in_us: Option<i64>) -> Progress {
+            let mut metadata = Metadata::new(track_id);
+            if let Some(length) = length_in_us {
+                metadata.insert("mpris:length", crate::MetadataValue::I64(length));
+            }
+            Progress::for_test(
+                metadata,
+                PlaybackStatus::Playing,
+                false,
+                LoopStatus::None,
+                1.0,
+                1.0,
+            )
+        }
+
+        let unknown = with_length("/1", None);
+        let known = with_length("/1", Some(90_000_000));
+        let mut buffer = Vec::new();
+        detect_changes(&unknown, &known, &[], false, &mut buffer);
+        assert!(matches!(
+            buffer[..],
+            [Event::TrackLengthChanged(length)] if length == Duration::from_secs(90)
+        ));
+
+        for (old, new) in &[
+            (&known, &with_length("/1", Some(90_000_000))),
+            (&known, &unknown),
+            (&unknown, &with_length("/2", Some(90_000_000))),
+        ] {
+            let mut buffer = Vec::new();
+            detect_changes(old, new, &[], false, &mut buffer);
+            assert!(!names(&buffer).contains(&"other"), "{:?}", buffer);
+        }
+    }
+
+    #[test]
+    fn it_emits_every_status_transition_in_order() {
+        let old = progress("/1", PlaybackStatus::Paused, 1.0);
+        let new = progress("/1", PlaybackStatus::Paused, 1.0);
+
+        let mut buffer = Vec::new();
+        detect_changes(
+            &old,
+            &new,
+            &[
+                PlaybackStatus::Playing,
+                PlaybackStatus::Playing,
+                PlaybackStatus::Paused,
+            ],
+            false,
+            &mut buffer,
+        );
+        assert_eq!(names(&buffer), vec!["Playing", "Paused"]);
+
+        let mut buffer = Vec::new();
+        let new = progress("/1", PlaybackStatus::Playing, 1.0);
+        detect_changes(&old, &new, &[PlaybackStatus::Paused], false, &mut buffer);
+        assert_eq!(names(&buffer), vec!["Playing"]);
+    }
+
+    #[test]
+    fn it_emits_changes_into_and_out_of_unknown_statuses() {
+        let detect = |old: &Progress, new: &Progress| {
+            let mut buffer = Vec::new();
+            detect_changes(old, new, &[], false, &mut buffer);
+            buffer
+        };
+        let playing = progress("/1", PlaybackStatus::Playing, 1.0);
+        let stopped = progress("/1", PlaybackStatus::Stopped, 1.0);
+        let buffering =
+            progress("/1", PlaybackStatus::Playing, 1.0).with_unknown_playback_status("Buffering");
+        let loading =
+            progress("/1", PlaybackStatus::Playing, 1.0).with_unknown_playback_status("Loading");
+
+        let events = detect(&playing, &buffering);
+        assert!(
+            matches!(events.as_slice(), [Event::PlaybackStatusUnknown(status)] if status == "Buffering")
+        );
+        assert!(detect(&buffering, &buffering).is_empty());
+        assert_eq!(
+            names(&detect(&buffering, &loading)),
+            vec!["PlaybackStatusUnknown"]
+        );
+        assert_eq!(names(&detect(&buffering, &stopped)), vec!["Stopped"]);
+        assert_eq!(
+            names(&detect(&stopped, &buffering)),
+            vec!["PlaybackStatusUnknown"]
+        );
+
+        let mut buffer = Vec::new();
+        detect_changes(
+            &buffering,
+            &buffering,
+            &[PlaybackStatus::Paused],
+            false,
+            &mut buffer,
+        );
+        assert_eq!(names(&buffer), vec!["Paused", "PlaybackStatusUnknown"]);
+    }
+
+    #[test]
+    fn it_emits_nothing_without_changes() {
+        let old = progress("/1", PlaybackStatus::Playing, 1.0);
+        let new = progress("/1", PlaybackStatus::Playing, 1.0);
+
+        let mut buffer = Vec::new();
+        assert!(!detect_changes(&old, &new, &[], false, &mut buffer));
+        assert!(buffer.is_empty());
+    }
+
+    fn debounce() -> StoppedDebounce {
+        StoppedDebounce {
+            window: Duration::from_secs(1),
+            pending: None,
+        }
+    }
+
+    #[test]
+    fn it_drops_stops_that_are_cancelled_within_the_window() {
+        let mut debounce = debounce();
+        let start = Instant::now();
+
+        let mut buffer = vec![Event::Stopped];
+        debounce.filter(PlaybackStatus::Playing, &mut buffer, start);
+        assert!(buffer.is_empty());
+        assert!(!debounce.take_expired(start + Duration::from_millis(500)));
+
+        let mut buffer = vec![Event::TrackChanged(Metadata::new("/b")), Event::Playing];
+        debounce.filter(
+            PlaybackStatus::Stopped,
+            &mut buffer,
+            start + Duration::from_millis(500),
+        );
+        assert_eq!(buffer.len(), 1);
+        assert!(matches!(buffer[0], Event::TrackChanged(_)));
+        assert!(!debounce.take_expired(start + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn it_keeps_the_new_status_when_a_stop_ends_in_another_status() {
+        let mut debounce = debounce();
+        let start = Instant::now();
+
+        let mut buffer = vec![Event::Stopped];
+        debounce.filter(PlaybackStatus::Playing, &mut buffer, start);
+
+        let mut buffer = vec![Event::Paused];
+        debounce.filter(PlaybackStatus::Stopped, &mut buffer, start);
+        assert!(matches!(buffer[..], [Event::Paused]));
+    }
+
+    #[test]
+    fn it_cancels_stops_within_a_batch_of_transitions() {
+        let mut debounce = debounce();
+        let start = Instant::now();
+
+        let mut buffer = vec![Event::Stopped, Event::Playing, Event::Paused];
+        debounce.filter(PlaybackStatus::Playing, &mut buffer, start);
+        assert!(matches!(buffer[..], [Event::Paused]));
+        assert!(!debounce.take_expired(start + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn it_emits_stops_that_last_for_the_window() {
+        let mut debounce = debounce();
+        let start = Instant::now();
+
+        let mut buffer = vec![Event::Stopped, Event::VolumeChanged(0.5)];
+        assert_eq!(buffer[1].volume(), Some(Volume::from_percent(50)));
+        assert_eq!(buffer[0].volume(), None);
+        debounce.filter(PlaybackStatus::Paused, &mut buffer, start);
+        assert!(matches!(buffer[..], [Event::VolumeChanged(_)]));
+        assert_eq!(
+            debounce.time_left(start + Duration::from_millis(400)),
+            Some(Duration::from_millis(600))
+        );
+
+        assert!(debounce.take_expired(start + Duration::from_secs(1)));
+        assert!(!debounce.take_expired(start + Duration::from_secs(2)));
+        assert_eq!(debounce.time_left(start), None);
+
+        // The player is known to be stopped now, so playing again is a real change.
+        let mut buffer = vec![Event::Playing];
+        debounce.filter(PlaybackStatus::Stopped, &mut buffer, start);
+        assert!(matches!(buffer[..], [Event::Playing]));
+    }
+
+    #[test]
+    fn it_shares_signals_between_events_and_a_tracker_on_one_player() {
+        use crate::{Metadata, MetadataValue, ProgressTick, ProgressTracker};
+
+        fn track(id: &str) -> Metadata {
+            let mut metadata = Metadata::new(id);
+            metadata.insert("mpris:length", MetadataValue::I64(300_000_000));
+            metadata
+        }
+
+        fn wait_for(tracker: &mut ProgressTracker<'_>, check: fn(&ProgressTick<'_>) -> bool) {
+            for _ in 0..20 {
+                if check(&tracker.tick()) {
+                    return;
+                }
+            }
+            panic!("The tracker never saw the change");
+        }
+
+        let (fake, player) = connect_to_fake("shared_signals");
+        fake.set_metadata(track("/org/example/Track/1"));
+        fake.set_playback_status(PlaybackStatus::Playing);
+
+        let mut events = player.events().unwrap();
+        let mut tracker = player.track_progress(50).unwrap();
+
+        fake.set_playback_status(PlaybackStatus::Paused);
+        fake.emit_properties_changed(&["PlaybackStatus"]);
+        assert!(matches!(events.next(), Some(Ok(Event::Paused))));
+        wait_for(&mut tracker, |tick| tick.status_changed);
+
+        fake.emit_seeked(Duration::from_secs(60));
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::Seeked {
+                position_in_us: 60_000_000
+            }))
+        ));
+        wait_for(&mut tracker, |tick| tick.position_jumped);
+
+        fake.set_metadata(track("/org/example/Track/2"));
+        fake.emit_properties_changed(&["Metadata"]);
+        match events.next() {
+            Some(Ok(Event::TrackChanged(metadata))) => assert_eq!(
+                metadata.track_id().map(|id| id.to_string()),
+                Some(String::from("/org/example/Track/2"))
+            ),
+            other => panic!("Expected a track change, got {:?}", other),
+        }
+        wait_for(&mut tracker, |tick| tick.track_changed);
+    }
+
+    #[test]
+    fn it_decodes_status_transitions_like_the_player() {
+        let (fake, player) = connect_to_fake("events_lenient");
+        let mut events = PlayerEventsBuilder::new()
+            .playback_status_transitions(true)
+            .build(&player)
+            .unwrap();
+
+        // Both signals arrive before the events are read, so they end up in one batch.
+        fake.set_property("PlaybackStatus", crate::MetadataValue::from("playing"));
+        fake.emit_properties_changed(&["PlaybackStatus"]);
+        fake.set_property("PlaybackStatus", crate::MetadataValue::from("paused"));
+        fake.emit_properties_changed(&["PlaybackStatus"]);
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert!(matches!(events.next(), Some(Ok(Event::Playing))));
+        assert!(matches!(events.next(), Some(Ok(Event::Paused))));
+    }
+
+    #[test]
+    fn it_builds_events_with_all_options() {
+        use crate::Metadata;
+
+        let builder = PlayerEventsBuilder::new()
+            .tolerate_transient_errors(true)
+            .detailed_track_changes(true)
+            .stabilize_stopped(Duration::from_secs(1))
+            .playback_status_transitions(true)
+            .with_poll_interval(Duration::from_secs(5));
+        assert_eq!(
+            PlayerEventsBuilder::new().stabilize_stopped(Duration::from_secs(0)),
+            PlayerEventsBuilder::default()
+        );
+
+        let (fake, player) = connect_to_fake("events_builder");
+        fake.set_metadata(Metadata::new("/org/example/Track/1"));
+
+        let events = builder.build(&player).unwrap();
+        assert!(events.tolerate_transient_errors);
+        assert!(events.state.detailed_track_changes);
+        assert!(events.state.playback_status_transitions);
+        assert_eq!(
+            events
+                .stopped_debounce
+                .as_ref()
+                .map(|debounce| debounce.window),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(events.poll_interval, Some(Duration::from_secs(5)));
+
+        let mut events = builder.build(&player).unwrap();
+        fake.set_metadata(Metadata::new("/org/example/Track/2"));
+        fake.emit_properties_changed(&["Metadata"]);
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::TrackChangedDetailed { .. }))
+        ));
+        assert_eq!(
+            events.last_progress().metadata().track_id(),
+            Some(TrackID::new("/org/example/Track/2").unwrap())
+        );
+    }
+
+    #[test]
+    fn it_reports_statuses_outside_of_the_specification() {
+        use crate::MetadataValue;
+
+        let (fake, player) = connect_to_fake("events_buffering");
+        let mut events = player.events().unwrap();
+
+        fake.set_property("PlaybackStatus", MetadataValue::from("Buffering"));
+        fake.emit_properties_changed(&["PlaybackStatus"]);
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::PlaybackStatusUnknown(ref status))) if status == "Buffering"
+        ));
+        assert!(!events.last_progress().playback_status_known());
+        assert_eq!(
+            events.last_progress().raw_playback_status(),
+            Some("Buffering")
+        );
+
+        fake.set_property("PlaybackStatus", MetadataValue::from("Stopped"));
+        fake.emit_properties_changed(&["PlaybackStatus"]);
+        assert!(matches!(events.next(), Some(Ok(Event::Stopped))));
+        assert!(events.last_progress().playback_status_known());
+    }
+
+    #[test]
+    fn it_polls_players_that_send_no_signals() {
+        use crate::MetadataValue;
+
+        let (fake, player) = connect_to_fake("events_poll");
+        let mut events = player
+            .events()
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(50));
+
+        fake.set_property("LoopStatus", MetadataValue::from("Track"));
+        let started = Instant::now();
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::LoopingChanged(LoopStatus::Track)))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            events.with_poll_interval(Duration::new(0, 0)).poll_interval,
+            None
+        );
+    }
+
+    #[test]
+    fn it_yields_events_from_earlier_reads_first() {
+        use crate::MetadataValue;
+
+        let (fake, player) = connect_to_fake("events_order");
+        let mut events = player.events().unwrap();
+
+        fake.set_property("LoopStatus", MetadataValue::from("Track"));
+        fake.set_property("Volume", MetadataValue::F64(0.5));
+        fake.emit_properties_changed(&["LoopStatus", "Volume"]);
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::LoopingChanged(LoopStatus::Track)))
+        ));
+
+        // Read the next burst while an event of the first one is still queued.
+        fake.set_property("Shuffle", MetadataValue::Bool(true));
+        fake.emit_properties_changed(&["Shuffle"]);
+        while events.buffer.len() < 2 {
+            events.read_events().unwrap();
+        }
+
+        let rest: Vec<Event> = events.by_ref().take(2).map(Result::unwrap).collect();
+        assert_eq!(names(&rest), vec!["VolumeChanged", "ShuffleToggled"]);
+    }
+
+    #[test]
+    fn it_streams_only_track_changes() {
+        use crate::{Metadata, MetadataValue};
+
+        let (fake, player) = connect_to_fake("track_changes");
+        fake.set_metadata(Metadata::new("/org/example/Track/1"));
+        let mut tracks = player.track_change_stream().unwrap();
+
+        let mut next_id = || {
+            tracks
+                .next()
+                .unwrap()
+                .unwrap()
+                .track_id()
+                .unwrap()
+                .to_string()
+        };
+
+        fake.set_metadata(Metadata::new("/org/example/Track/2"));
+        fake.emit_properties_changed(&["Metadata"]);
+        assert_eq!(next_id(), "/org/example/Track/2");
+
+        fake.set_property("Volume", MetadataValue::F64(0.5));
+        fake.emit_properties_changed(&["Volume"]);
+        fake.set_metadata(Metadata::new("/org/example/Track/3"));
+        fake.emit_properties_changed(&["Metadata"]);
+        assert_eq!(next_id(), "/org/example/Track/3");
+
+        fake.disappear();
+        assert!(tracks.next().is_none());
     }
 }
@@ -0,0 +1,768 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use dbus::arg::{RefArg, Variant};
+use dbus::ffidisp::{BusType, Connection, NameFlag, RequestNameReply};
+use dbus::strings::ErrorName;
+use dbus::{Message, MessageType, Path};
+
+use crate::metadata::{Metadata, Value};
+use crate::player::{MPRIS2_PATH, MPRIS2_PREFIX};
+use crate::{DBusError, LoopStatus, PlaybackStatus};
+
+const ROOT_INTERFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const INTROSPECTABLE_INTERFACE: &str = "org.freedesktop.DBus.Introspectable";
+
+/// How long the server thread waits for method calls before it checks for new signals to emit.
+const POLL_INTERVAL_MS: u32 = 10;
+
+/// A fake MPRIS2 player for tests, registered as a real service on its own private connection to
+/// the session bus.
+///
+/// Everything about the player is controlled by the test: properties are changed with
+/// [`set_property`](Self::set_property) and friends, and signals are only sent when asked for
+/// with [`emit_properties_changed`](Self::emit_properties_changed) and
+/// [`emit_seeked`](Self::emit_seeked). This makes it possible to test code that uses
+/// [`PlayerEvents`](crate::PlayerEvents) or [`ProgressTracker`](crate::ProgressTracker)
+/// deterministically, without any installed player.
+///
+/// Method calls from clients are answered from a background thread. The playback methods
+/// (`Play`, `Pause`, `PlayPause` and `Stop`) change `PlaybackStatus`, and `Seek` and
+/// `SetPosition` change `Position`, emitting the same signals that a real player would. All
+/// other methods are accepted without doing anything. Every call is recorded and can be read
+/// with [`calls`](Self::calls). Unlike a real player, the position does not advance on its own.
+///
+/// Slow players can be simulated with [`set_reply_delay`](Self::set_reply_delay), failing
+/// players with [`fail_next_calls`](Self::fail_next_calls), and players that quit with
+/// [`disappear`](Self::disappear). The player also disappears from the bus when
+/// this value is dropped.
+///
+/// Only available with the `test-support` feature.
+#[derive(Debug)]
+pub struct FakePlayer {
+    bus_name: String,
+    state: Arc<Mutex<FakeState>>,
+    commands: Sender<Command>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Something for the server thread to do, in between answering method calls.
+#[derive(Debug)]
+enum Command {
+    Emit(Message),
+    ReleaseName,
+}
+
+#[derive(Debug)]
+struct FakeState {
+    root: HashMap<String, Value>,
+    player: HashMap<String, Value>,
+    calls: Vec<String>,
+    property_calls: Vec<String>,
+    reply_delay: Duration,
+    failing_calls: usize,
+    failure: String,
+}
+
+impl FakePlayer {
+    /// Registers a new player on the session bus, with the bus name
+    /// `org.mpris.MediaPlayer2.<name>`.
+    ///
+    /// The player starts out stopped, without a track, and with every capability set to
+    /// [`true`]. Its identity is `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session bus could not be reached, or if the bus name is already
+    /// taken.
+    pub fn start(name: &str) -> Result<FakePlayer, DBusError> {
+        FakePlayer::start_on(BusType::Session, name)
+    }
+
+    /// Registers a new player on the given bus, with the bus name
+    /// `org.mpris.MediaPlayer2.<name>`.
+    ///
+    /// See [`start`](Self::start).
+    pub fn start_on(bus: BusType, name: &str) -> Result<FakePlayer, DBusError> {
+        FakePlayer::register_as(bus, format!("{}{}", MPRIS2_PREFIX, name), name)
+    }
+
+    /// Registers a new player on the session bus with a complete bus name, which does not have to
+    /// start with `org.mpris.MediaPlayer2.`. Its identity is the bus name.
+    ///
+    /// Useful to test discovery of players under other prefixes; see
+    /// [`PlayerFinder::set_extra_bus_name_prefixes`](crate::PlayerFinder::set_extra_bus_name_prefixes).
+    pub fn start_with_bus_name(bus_name: &str) -> Result<FakePlayer, DBusError> {
+        FakePlayer::register_as(BusType::Session, String::from(bus_name), bus_name)
+    }
+
+    fn register_as(bus: BusType, bus_name: String, name: &str) -> Result<FakePlayer, DBusError> {
+        let state = Arc::new(Mutex::new(FakeState::new(name)));
+        let (commands, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::channel();
+
+        // Connections can not be moved between threads, so the server thread opens its own.
+        let thread = {
+            let bus_name = bus_name.clone();
+            let state = Arc::clone(&state);
+            thread::spawn(move || match register(bus, &bus_name) {
+                Ok(connection) => {
+                    let _ = ready_sender.send(Ok(()));
+                    serve(&connection, &bus_name, &state, &receiver);
+                }
+                Err(error) => {
+                    let _ = ready_sender.send(Err(error));
+                }
+            })
+        };
+
+        match ready.recv() {
+            Ok(Ok(())) => Ok(FakePlayer {
+                bus_name,
+                state,
+                commands,
+                thread: Some(thread),
+            }),
+            Ok(Err(error)) => Err(error),
+            Err(_) => Err(DBusError::Miscellaneous(String::from(
+                "The fake player's server thread exited during startup",
+            ))),
+        }
+    }
+
+    /// The bus name of the player, like `org.mpris.MediaPlayer2.<name>`.
+    pub fn bus_name(&self) -> &str {
+        &self.bus_name
+    }
+
+    /// Sets a property on the `org.mpris.MediaPlayer2.Player` interface, without emitting any
+    /// signal.
+    ///
+    /// The value is sent with the D-Bus type of the [`Value`] variant, so use the type that the
+    /// MPRIS2 specification requires (like [`Value::I64`] for `Position`).
+    pub fn set_property<S: Into<String>>(&self, name: S, value: Value) {
+        self.state().player.insert(name.into(), value);
+    }
+
+    /// Sets a property on the `org.mpris.MediaPlayer2` interface, without emitting any signal.
+    pub fn set_root_property<S: Into<String>>(&self, name: S, value: Value) {
+        self.state().root.insert(name.into(), value);
+    }
+
+    /// Removes a property from the `org.mpris.MediaPlayer2.Player` interface, to act like a
+    /// player that does not implement it.
+    pub fn remove_property(&self, name: &str) -> Option<Value> {
+        self.state().player.remove(name)
+    }
+
+    /// Returns the current value of a property on the `org.mpris.MediaPlayer2.Player`
+    /// interface. This includes changes made by clients, like calls to `Play` or setting
+    /// `Volume`.
+    pub fn property(&self, name: &str) -> Option<Value> {
+        self.state().player.get(name).cloned()
+    }
+
+    /// Sets the `Metadata` property, without emitting any signal.
+    pub fn set_metadata(&self, metadata: Metadata) {
+        self.set_property("Metadata", Value::Map(metadata.into()));
+    }
+
+    /// Sets the `PlaybackStatus` property, without emitting any signal.
+    pub fn set_playback_status(&self, status: PlaybackStatus) {
+        self.set_property("PlaybackStatus", Value::from(status_string(status)));
+    }
+
+    /// Sets the `LoopStatus` property, without emitting any signal.
+    pub fn set_loop_status(&self, status: LoopStatus) {
+        self.set_property("LoopStatus", Value::String(status.dbus_value()));
+    }
+
+    /// Sets the `Position` property, without emitting any signal.
+    ///
+    /// Real players do not emit signals for position changes either; use
+    /// [`emit_seeked`](Self::emit_seeked) to act like the user seeked.
+    pub fn set_position(&self, position: Duration) {
+        self.set_property("Position", Value::I64(position.as_micros() as i64));
+    }
+
+    /// Emits a `PropertiesChanged` signal for the `org.mpris.MediaPlayer2.Player` interface with
+    /// the current values of the given properties.
+    ///
+    /// Properties that are not set are listed as invalidated.
+    pub fn emit_properties_changed(&self, names: &[&str]) {
+        let signal = {
+            let state = self.state();
+            properties_changed(PLAYER_INTERFACE, &state.player, names)
+        };
+        self.send(signal);
+    }
+
+    /// Emits a `PropertiesChanged` signal for the `org.mpris.MediaPlayer2` interface with the
+    /// current values of the given properties.
+    ///
+    /// Properties that are not set are listed as invalidated.
+    pub fn emit_root_properties_changed(&self, names: &[&str]) {
+        let signal = {
+            let state = self.state();
+            properties_changed(ROOT_INTERFACE, &state.root, names)
+        };
+        self.send(signal);
+    }
+
+    /// Sets the `Position` property and emits a `Seeked` signal with it.
+    pub fn emit_seeked(&self, position: Duration) {
+        self.set_position(position);
+        self.send(seeked(position.as_micros() as i64));
+    }
+
+    /// Makes the player wait this long before answering each method call, to act like a player
+    /// that is busy or hanging. Clients with a shorter timeout get a
+    /// [`Timeout`](crate::DBusErrorKind::Timeout) error.
+    ///
+    /// Signals are delayed too, since they are sent from the same thread.
+    pub fn set_reply_delay(&self, delay: Duration) {
+        self.state().reply_delay = delay;
+    }
+
+    /// Answers the next `count` method calls with the D-Bus error
+    /// `org.freedesktop.DBus.Error.<name>` (like `"NoReply"`) instead of handling them, to act
+    /// like a player that is briefly unable to reply. The failed calls are not recorded.
+    pub fn fail_next_calls(&self, count: usize, name: &str) {
+        let mut state = self.state();
+        state.failing_calls = count;
+        state.failure = String::from(name);
+    }
+
+    /// Releases the bus name, like a player that quits would.
+    ///
+    /// Clients see the player disappear from the bus, and
+    /// [`PlayerEvents`](crate::PlayerEvents) ends with
+    /// [`Event::PlayerShutDown`](crate::Event::PlayerShutDown). Unlike dropping the
+    /// [`FakePlayer`], its state can still be inspected afterwards.
+    pub fn disappear(&self) {
+        let _ = self.commands.send(Command::ReleaseName);
+    }
+
+    /// Returns the names of all methods that clients have called on the
+    /// `org.mpris.MediaPlayer2` and `org.mpris.MediaPlayer2.Player` interfaces, in the order that
+    /// they were called. Property access is not included.
+    pub fn calls(&self) -> Vec<String> {
+        self.state().calls.clone()
+    }
+
+    /// Returns the names of all methods that clients have called on the
+    /// `org.freedesktop.DBus.Properties` interface (`Get`, `GetAll` and `Set`), in the order that
+    /// they were called.
+    ///
+    /// This can be used to check how many round trips an operation needs.
+    pub fn property_calls(&self) -> Vec<String> {
+        self.state().property_calls.clone()
+    }
+
+    fn send(&self, signal: Message) {
+        // The thread only stops when this value is dropped, so the send can not fail here.
+        let _ = self.commands.send(Command::Emit(signal));
+    }
+
+    fn state(&self) -> MutexGuard<'_, FakeState> {
+        lock(&self.state)
+    }
+}
+
+impl Drop for FakePlayer {
+    fn drop(&mut self) {
+        // Replace the sender so that the channel disconnects, which stops the server thread.
+        let (disconnected, _) = mpsc::channel();
+        drop(std::mem::replace(&mut self.commands, disconnected));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Starts a [`FakePlayer`] whose name is unique to this test process, so test runs don't collide
+/// on a shared session bus.
+#[cfg(test)]
+pub(crate) fn start_fake(name: &str) -> FakePlayer {
+    FakePlayer::start(&format!("{}.pid{}", name, std::process::id()))
+        .expect("Could not start fake player")
+}
+
+/// Starts a [`FakePlayer`] like [`start_fake`] and connects a [`Player`](crate::Player) to it on a
+/// new private connection.
+#[cfg(test)]
+pub(crate) fn connect_to_fake(name: &str) -> (FakePlayer, crate::Player) {
+    let fake = start_fake(name);
+    let connection =
+        Connection::get_private(BusType::Session).expect("Could not open a connection");
+    let player = crate::Player::from_bus_name(connection, fake.bus_name())
+        .expect("Could not find fake player");
+    (fake, player)
+}
+
+impl FakeState {
+    fn new(identity: &str) -> Self {
+        let root = vec![
+            ("Identity", Value::from(identity)),
+            ("CanQuit", Value::Bool(true)),
+            ("CanRaise", Value::Bool(true)),
+            ("HasTrackList", Value::Bool(false)),
+            ("SupportedUriSchemes", Value::Array(Vec::new())),
+            ("SupportedMimeTypes", Value::Array(Vec::new())),
+        ];
+        let player = vec![
+            ("PlaybackStatus", Value::from("Stopped")),
+            ("LoopStatus", Value::from("None")),
+            ("Rate", Value::F64(1.0)),
+            ("MinimumRate", Value::F64(1.0)),
+            ("MaximumRate", Value::F64(1.0)),
+            ("Shuffle", Value::Bool(false)),
+            ("Metadata", Value::Map(HashMap::new())),
+            ("Volume", Value::F64(1.0)),
+            ("Position", Value::I64(0)),
+            ("CanGoNext", Value::Bool(true)),
+            ("CanGoPrevious", Value::Bool(true)),
+            ("CanPlay", Value::Bool(true)),
+            ("CanPause", Value::Bool(true)),
+            ("CanSeek", Value::Bool(true)),
+            ("CanControl", Value::Bool(true)),
+        ];
+
+        FakeState {
+            root: to_properties(root),
+            player: to_properties(player),
+            calls: Vec::new(),
+            property_calls: Vec::new(),
+            reply_delay: Duration::from_secs(0),
+            failing_calls: 0,
+            failure: String::new(),
+        }
+    }
+
+    fn interface(&self, interface: &str) -> Option<&HashMap<String, Value>> {
+        match interface {
+            ROOT_INTERFACE => Some(&self.root),
+            PLAYER_INTERFACE => Some(&self.player),
+            _ => None,
+        }
+    }
+
+    fn interface_mut(&mut self, interface: &str) -> Option<&mut HashMap<String, Value>> {
+        match interface {
+            ROOT_INTERFACE => Some(&mut self.root),
+            PLAYER_INTERFACE => Some(&mut self.player),
+            _ => None,
+        }
+    }
+
+    fn position(&self) -> i64 {
+        self.player
+            .get("Position")
+            .and_then(Value::coerce_integer)
+            .unwrap_or(0)
+    }
+}
+
+fn to_properties(properties: Vec<(&str, Value)>) -> HashMap<String, Value> {
+    properties
+        .into_iter()
+        .map(|(name, value)| (String::from(name), value))
+        .collect()
+}
+
+fn lock(state: &Mutex<FakeState>) -> MutexGuard<'_, FakeState> {
+    // A panicking test should not take every other test down with it.
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn register(bus: BusType, bus_name: &str) -> Result<Connection, DBusError> {
+    let connection = Connection::get_private(bus)?;
+    connection.register_object_path(MPRIS2_PATH)?;
+
+    match connection.register_name(bus_name, NameFlag::DoNotQueue.value())? {
+        RequestNameReply::PrimaryOwner => Ok(connection),
+        _ => Err(DBusError::Miscellaneous(format!(
+            "Could not register the fake player as {}; the name is already taken",
+            bus_name
+        ))),
+    }
+}
+
+/// Answers method calls and runs commands until the [`FakePlayer`] is dropped.
+fn serve(
+    connection: &Connection,
+    bus_name: &str,
+    state: &Mutex<FakeState>,
+    commands: &Receiver<Command>,
+) {
+    loop {
+        loop {
+            match commands.try_recv() {
+                Ok(Command::Emit(signal)) => {
+                    let _ = connection.send(signal);
+                }
+                Ok(Command::ReleaseName) => {
+                    let _ = connection.release_name(bus_name);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+
+        for message in connection.incoming(POLL_INTERVAL_MS) {
+            if message.msg_type() != MessageType::MethodCall {
+                continue;
+            }
+
+            let delay = lock(state).reply_delay;
+            if delay > Duration::from_secs(0) {
+                thread::sleep(delay);
+            }
+
+            let (reply, signals) = handle_call(&mut lock(state), &message);
+            let _ = connection.send(reply);
+            for signal in signals {
+                let _ = connection.send(signal);
+            }
+        }
+    }
+}
+
+/// Returns the reply to a method call, and the signals to emit after it.
+fn handle_call(state: &mut FakeState, call: &Message) -> (Message, Vec<Message>) {
+    let interface = call.interface().map(|i| i.to_string()).unwrap_or_default();
+    let member = call.member().map(|m| m.to_string()).unwrap_or_default();
+
+    if state.failing_calls > 0 {
+        state.failing_calls -= 1;
+        return (
+            error(call, &state.failure, "Failing on purpose"),
+            Vec::new(),
+        );
+    }
+
+    if interface == PROPERTIES_INTERFACE {
+        state.property_calls.push(member.clone());
+    }
+
+    match (interface.as_str(), member.as_str()) {
+        (PROPERTIES_INTERFACE, "Get") => {
+            let value = call
+                .read2::<&str, &str>()
+                .ok()
+                .and_then(|(interface, name)| {
+                    state
+                        .interface(interface)
+                        .and_then(|properties| properties.get(name))
+                });
+            match value {
+                Some(value) => (call.method_return().append1(variant(value)), Vec::new()),
+                None => (error(call, "InvalidArgs", "No such property"), Vec::new()),
+            }
+        }
+        (PROPERTIES_INTERFACE, "GetAll") => {
+            let properties = call
+                .read1::<&str>()
+                .ok()
+                .and_then(|interface| state.interface(interface))
+                .map(to_dict)
+                .unwrap_or_default();
+            (call.method_return().append1(properties), Vec::new())
+        }
+        (PROPERTIES_INTERFACE, "Set") => match call.read3::<&str, &str, Value>() {
+            Ok((interface, name, value)) => match state.interface_mut(interface) {
+                Some(properties) if properties.contains_key(name) => {
+                    properties.insert(String::from(name), value);
+                    let signals = if interface == PLAYER_INTERFACE {
+                        vec![properties_changed(PLAYER_INTERFACE, &state.player, &[name])]
+                    } else {
+                        Vec::new()
+                    };
+                    (call.method_return(), signals)
+                }
+                _ => (error(call, "InvalidArgs", "No such property"), Vec::new()),
+            },
+            Err(_) => (error(call, "InvalidArgs", "Invalid arguments"), Vec::new()),
+        },
+        (INTROSPECTABLE_INTERFACE, "Introspect") => {
+            (call.method_return().append1(introspection()), Vec::new())
+        }
+        (PLAYER_INTERFACE, _) => {
+            state.calls.push(member.clone());
+            let signals = player_method(state, call, &member);
+            (call.method_return(), signals)
+        }
+        (ROOT_INTERFACE, "Raise") | (ROOT_INTERFACE, "Quit") => {
+            state.calls.push(member.clone());
+            (call.method_return(), Vec::new())
+        }
+        _ => (error(call, "UnknownMethod", "No such method"), Vec::new()),
+    }
+}
+
+/// Applies a call to the `org.mpris.MediaPlayer2.Player` interface to the state, and returns the
+/// signals that it causes.
+fn player_method(state: &mut FakeState, call: &Message, member: &str) -> Vec<Message> {
+    let current_status = state
+        .player
+        .get("PlaybackStatus")
+        .and_then(Value::as_str)
+        .unwrap_or("Stopped")
+        .to_string();
+    let new_status = match member {
+        "Play" => Some("Playing"),
+        "Pause" => Some("Paused"),
+        "PlayPause" if current_status == "Playing" => Some("Paused"),
+        "PlayPause" => Some("Playing"),
+        "Stop" => Some("Stopped"),
+        _ => None,
+    };
+    if let Some(status) = new_status {
+        if status == current_status {
+            return Vec::new();
+        }
+        state
+            .player
+            .insert(String::from("PlaybackStatus"), Value::from(status));
+        return vec![properties_changed(
+            PLAYER_INTERFACE,
+            &state.player,
+            &["PlaybackStatus"],
+        )];
+    }
+
+    let new_position = match member {
+        "Seek" => call
+            .read1::<i64>()
+            .ok()
+            .map(|offset| state.position().saturating_add(offset).max(0)),
+        "SetPosition" => call
+            .read2::<Path<'_>, i64>()
+            .ok()
+            .map(|(_, position)| position),
+        _ => None,
+    };
+    match new_position {
+        Some(position) => {
+            state
+                .player
+                .insert(String::from("Position"), Value::I64(position));
+            vec![seeked(position)]
+        }
+        None => Vec::new(),
+    }
+}
+
+fn error(call: &Message, name: &str, text: &str) -> Message {
+    let name = ErrorName::from(format!("org.freedesktop.DBus.Error.{}", name));
+    let text = std::ffi::CString::new(text).unwrap_or_default();
+    call.error(&name, &text)
+}
+
+fn introspection() -> String {
+    format!(
+        r#"<node><interface name="{}"/><interface name="{}"/><interface name="{}"/></node>"#,
+        ROOT_INTERFACE, PLAYER_INTERFACE, PROPERTIES_INTERFACE
+    )
+}
+
+fn properties_changed(
+    interface: &str,
+    properties: &HashMap<String, Value>,
+    names: &[&str],
+) -> Message {
+    let mut changed = HashMap::new();
+    let mut invalidated = Vec::new();
+    for name in names {
+        match properties.get(*name) {
+            Some(value) => {
+                changed.insert(String::from(*name), variant(value));
+            }
+            None => invalidated.push(String::from(*name)),
+        }
+    }
+
+    Message::new_signal(MPRIS2_PATH, PROPERTIES_INTERFACE, "PropertiesChanged")
+        .unwrap()
+        .append3(interface, changed, invalidated)
+}
+
+fn seeked(position_in_us: i64) -> Message {
+    Message::new_signal(MPRIS2_PATH, PLAYER_INTERFACE, "Seeked")
+        .unwrap()
+        .append1(position_in_us)
+}
+
+fn to_dict(properties: &HashMap<String, Value>) -> HashMap<String, Variant<Box<dyn RefArg>>> {
+    properties
+        .iter()
+        .map(|(name, value)| (name.clone(), variant(value)))
+        .collect()
+}
+
+fn variant(value: &Value) -> Variant<Box<dyn RefArg>> {
+    Variant(to_ref_arg(value))
+}
+
+/// Converts a [`Value`] to something that can be sent over D-Bus, using the D-Bus type that
+/// matches the variant.
+fn to_ref_arg(value: &Value) -> Box<dyn RefArg> {
+    match value {
+        Value::String(string) => Box::new(string.clone()),
+        Value::I16(number) => Box::new(*number),
+        Value::I32(number) => Box::new(*number),
+        Value::I64(number) => Box::new(*number),
+        Value::U8(number) => Box::new(*number),
+        Value::U16(number) => Box::new(*number),
+        Value::U32(number) => Box::new(*number),
+        Value::U64(number) => Box::new(*number),
+        Value::F64(number) => Box::new(*number),
+        Value::Bool(boolean) => Box::new(*boolean),
+        // Lists of strings (like `xesam:artist`) have the type `as` in the specification.
+        Value::Array(values) if values.iter().all(Value::is_string) => Box::new(
+            values
+                .iter()
+                .filter_map(|value| value.as_string().cloned())
+                .collect::<Vec<String>>(),
+        ),
+        Value::Array(values) => Box::new(values.iter().map(variant).collect::<Vec<_>>()),
+        Value::Map(map) => Box::new(
+            map.iter()
+                .map(|(key, value)| (key.clone(), Variant(map_entry(key, value))))
+                .collect::<HashMap<_, _>>(),
+        ),
+        Value::Unsupported => Box::new(String::new()),
+    }
+}
+
+fn map_entry(key: &str, value: &Value) -> Box<dyn RefArg> {
+    // Track IDs are object paths, and some clients only accept them as such.
+    match (key, value) {
+        ("mpris:trackid", Value::String(track_id)) => match Path::new(track_id.as_str()) {
+            Ok(path) => Box::new(path.into_static()),
+            Err(_) => to_ref_arg(value),
+        },
+        _ => to_ref_arg(value),
+    }
+}
+
+fn status_string(status: PlaybackStatus) -> &'static str {
+    match status {
+        PlaybackStatus::Playing => "Playing",
+        PlaybackStatus::Paused => "Paused",
+        PlaybackStatus::Stopped => "Stopped",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DBusErrorKind, Event, Player};
+
+    #[test]
+    fn it_serves_properties() {
+        let (fake, player) = connect_to_fake("properties");
+        let mut metadata = Metadata::new("/org/example/Track/1");
+        metadata.insert("xesam:title", Value::from("A song"));
+        metadata.insert("xesam:artist", Value::Array(vec![Value::from("An artist")]));
+        metadata.insert("mpris:length", Value::I64(180_000_000));
+        fake.set_metadata(metadata);
+        fake.set_playback_status(PlaybackStatus::Paused);
+        fake.set_position(Duration::from_secs(42));
+
+        assert!(player.identity().starts_with("properties"));
+        assert_eq!(
+            player.get_playback_status().unwrap(),
+            PlaybackStatus::Paused
+        );
+        assert_eq!(player.get_position().unwrap(), Duration::from_secs(42));
+
+        let metadata = player.get_metadata().unwrap();
+        assert_eq!(metadata.title(), Some("A song"));
+        assert_eq!(metadata.artists(), Some(vec!["An artist"]));
+        assert_eq!(metadata.length(), Some(Duration::from_secs(180)));
+        assert_eq!(
+            metadata.track_id().map(|id| id.to_string()),
+            Some(String::from("/org/example/Track/1"))
+        );
+    }
+
+    #[test]
+    fn it_records_calls_and_updates_state() {
+        let (fake, player) = connect_to_fake("calls");
+
+        player.play().unwrap();
+        player.seek_forwards(&Duration::from_secs(10)).unwrap();
+        player.next().unwrap();
+
+        assert_eq!(fake.calls(), vec!["Play", "Seek", "Next"]);
+        assert_eq!(
+            fake.property("PlaybackStatus"),
+            Some(Value::from("Playing"))
+        );
+        assert_eq!(fake.property("Position"), Some(Value::I64(10_000_000)));
+    }
+
+    #[test]
+    fn it_emits_signals_for_player_events() {
+        let (fake, player) = connect_to_fake("events");
+        let mut events = player.events().unwrap();
+
+        fake.set_playback_status(PlaybackStatus::Playing);
+        fake.emit_properties_changed(&["PlaybackStatus"]);
+        assert!(matches!(events.next(), Some(Ok(Event::Playing))));
+
+        fake.emit_seeked(Duration::from_secs(5));
+        match events.next() {
+            Some(Ok(Event::Seeked { position_in_us })) => assert_eq!(position_in_us, 5_000_000),
+            other => panic!("Expected a seek, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_disappears_from_the_bus_when_dropped() {
+        let fake = start_fake("dropped");
+        let bus_name = fake.bus_name().to_string();
+        drop(fake);
+
+        let connection =
+            Connection::get_private(BusType::Session).expect("Could not open a connection");
+        let error = Player::from_bus_name(connection, &bus_name).unwrap_err();
+        assert_eq!(error.kind(), DBusErrorKind::ServiceUnknown);
+    }
+
+    #[test]
+    fn it_can_be_slow_to_reply() {
+        let (fake, mut player) = connect_to_fake("slow");
+        player.set_dbus_timeout_ms(50);
+
+        fake.set_reply_delay(Duration::from_millis(200));
+        let error = player.get_playback_status().unwrap_err();
+        assert_eq!(error.kind(), DBusErrorKind::Timeout);
+
+        fake.set_reply_delay(Duration::from_secs(0));
+        player.set_dbus_timeout_ms(5_000);
+        assert_eq!(
+            player.get_playback_status().unwrap(),
+            PlaybackStatus::Stopped
+        );
+    }
+
+    #[test]
+    fn it_can_disappear_while_events_are_read() {
+        let (fake, player) = connect_to_fake("disappearing");
+        let mut events = player.events().unwrap();
+
+        fake.disappear();
+        assert!(matches!(events.next(), Some(Ok(Event::PlayerShutDown))));
+        assert!(events.next().is_none());
+        assert!(!player.is_running());
+    }
+}
@@ -1,20 +1,30 @@
 use thiserror::Error;
 
+use std::convert::TryFrom;
 use std::iter::FusedIterator;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use dbus::ffidisp::{BusType, Connection};
 use dbus::{arg, Message};
 
-use super::DBusError;
-use crate::player::{Player, DEFAULT_TIMEOUT_MS, MPRIS2_PREFIX};
-use crate::pooled_connection::PooledConnection;
-use crate::PlaybackStatus;
+use super::{dbus_error_kind, DBusError, DBusErrorKind, PlaybackStatus};
+use crate::category::{PlayerCategory, PlayerClassifier};
+use crate::decode::{self, Decoding};
+use crate::generated::OrgMprisMediaPlayer2Player;
+use crate::metadata::{Metadata, Value};
+use crate::now_playing::NowPlaying;
+use crate::player::{
+    get_all_player_properties, Player, DEFAULT_TIMEOUT_MS, MAX_ACTIVITY_SCORE, MPRIS2_PATH,
+    MPRIS2_PREFIX,
+};
+use crate::pooled_connection::{Bus, PooledConnection};
 
 const LIST_NAMES_TIMEOUT_MS: i32 = 500;
 
 /// This enum encodes possible error cases that could happen when finding players.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum FindingError {
     /// No player was found matching the requirements of the calling method.
     #[error("No player found")]
@@ -38,6 +48,8 @@ impl From<dbus::Error> for FindingError {
 pub struct PlayerFinder {
     connection: Rc<PooledConnection>,
     player_timeout_ms: i32,
+    extra_bus_name_prefixes: Vec<String>,
+    classifier: PlayerClassifier,
 }
 
 impl PlayerFinder {
@@ -50,6 +62,24 @@ impl PlayerFinder {
         )?))
     }
 
+    /// Creates a new [`PlayerFinder`] with a new connection to the system D-Bus.
+    ///
+    /// Most desktop players register on the session bus, which is what [`new`](Self::new) uses.
+    /// Some kiosk or embedded setups have players on the system bus instead.
+    pub fn on_system_bus() -> Result<Self, DBusError> {
+        let bus = Bus::Type(BusType::System);
+        Ok(PlayerFinder::for_pooled_connection(bus.connect()?, bus))
+    }
+
+    /// Creates a new [`PlayerFinder`] with a new connection to the D-Bus at the given address.
+    ///
+    /// The address uses the normal D-Bus address format, like
+    /// `unix:path=/run/user/1000/bus`.
+    pub fn for_address(address: &str) -> Result<Self, DBusError> {
+        let bus = Bus::Address(String::from(address));
+        Ok(PlayerFinder::for_pooled_connection(bus.connect()?, bus))
+    }
+
     /// Create a new [`PlayerFinder`] with the given connection.
     ///
     /// Use [`new`](Self::new) if you want a new default connection rather than manually managing the D-Bus
@@ -58,6 +88,17 @@ impl PlayerFinder {
         PlayerFinder {
             connection: Rc::new(connection.into()),
             player_timeout_ms: DEFAULT_TIMEOUT_MS,
+            extra_bus_name_prefixes: Vec::new(),
+            classifier: PlayerClassifier::new(),
+        }
+    }
+
+    fn for_pooled_connection(connection: Connection, bus: Bus) -> Self {
+        PlayerFinder {
+            connection: Rc::new(PooledConnection::on_bus(connection, bus)),
+            player_timeout_ms: DEFAULT_TIMEOUT_MS,
+            extra_bus_name_prefixes: Vec::new(),
+            classifier: PlayerClassifier::new(),
         }
     }
 
@@ -73,6 +114,60 @@ impl PlayerFinder {
         self.player_timeout_ms = timeout_ms;
     }
 
+    /// Get the bus name prefixes that are accepted as players in addition to
+    /// `org.mpris.MediaPlayer2.`.
+    ///
+    /// Can be set with [`set_extra_bus_name_prefixes`][Self::set_extra_bus_name_prefixes]
+    pub fn extra_bus_name_prefixes(&self) -> &[String] {
+        &self.extra_bus_name_prefixes
+    }
+
+    /// Also treat services whose bus name starts with any of `prefixes` as players, replacing
+    /// any previously set prefixes.
+    ///
+    /// Services under the standard `org.mpris.MediaPlayer2.` prefix are always found. Extra
+    /// prefixes are useful in sandboxed or containerized setups that rename the player's bus name.
+    /// The services still have to implement the MPRIS2 interfaces at `/org/mpris/MediaPlayer2`.
+    ///
+    /// This affects every method that lists players, like [`find_all`](Self::find_all) and
+    /// [`running_count`](Self::running_count), but not [`activate`](Self::activate).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use mpris::PlayerFinder;
+    /// let mut finder = PlayerFinder::new().unwrap();
+    /// finder.set_extra_bus_name_prefixes(vec!["org.example.Sandboxed.MediaPlayer2."]);
+    /// let players = finder.find_all().unwrap();
+    /// ```
+    pub fn set_extra_bus_name_prefixes<I, S>(&mut self, prefixes: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extra_bus_name_prefixes = prefixes.into_iter().map(Into::into).collect();
+    }
+
+    /// Get the [`PlayerClassifier`] that [`find_active_in`](Self::find_active_in) uses.
+    pub fn classifier(&self) -> &PlayerClassifier {
+        &self.classifier
+    }
+
+    /// Set the [`PlayerClassifier`] that [`find_active_in`](Self::find_active_in) uses, to
+    /// classify players that the built-in table does not know.
+    pub fn set_classifier(&mut self, classifier: PlayerClassifier) {
+        self.classifier = classifier;
+    }
+
+    /// Returns [`true`] if a service with this bus name is a player.
+    fn is_player_bus(&self, bus_name: &str) -> bool {
+        bus_name.starts_with(MPRIS2_PREFIX)
+            || self
+                .extra_bus_name_prefixes
+                .iter()
+                .any(|prefix| bus_name.starts_with(prefix.as_str()))
+    }
+
     /// Find all available [`Player`]s in the connection.
     ///
     /// Will return an empty [`Vec`] and not [`NoPlayerFound`](FindingError::NoPlayerFound) if there are no players.
@@ -94,46 +189,140 @@ impl PlayerFinder {
     /// Try to find the "active" [`Player`] in the connection.
     ///
     /// This method will try to determine which player a user is most likely to use. First it will look for a player with
-    /// the playback status [`Playing`](PlaybackStatus::Playing), then for a [`Paused`](PlaybackStatus::Paused), then one with
-    /// track metadata, after that it will just return the first it finds. [`NoPlayerFound`](FindingError::NoPlayerFound) is returned
+    /// the playback status [`Playing`](crate::PlaybackStatus::Playing), then for a [`Paused`](crate::PlaybackStatus::Paused), then one with
+    /// track metadata, after that it will just return the first it finds. Players that can be controlled are preferred
+    /// over ones with the same status that can't. [`NoPlayerFound`](FindingError::NoPlayerFound) is returned
     /// only if there is no player on the DBus.
+    ///
+    /// This picks the player with the highest [`Player::activity_score`], or the first one found if several have the
+    /// same score.
     pub fn find_active(&self) -> Result<Player, FindingError> {
         let players: PlayerIter = self.iter_players()?;
 
-        match self.find_active_player(players)? {
+        match self.find_active_player(players, |_| true)? {
+            Some(player) => Ok(player),
+            None => Err(FindingError::NoPlayerFound),
+        }
+    }
+
+    /// Like [`find_active`](Self::find_active), but only considers players of the given
+    /// [`PlayerCategory`], as guessed by the finder's [`classifier`](Self::classifier).
+    ///
+    /// This can be used to skip browser tabs, for example with
+    /// [`PlayerCategory::Music`]. Returns [`NoPlayerFound`](FindingError::NoPlayerFound) if
+    /// there is no player of that category.
+    pub fn find_active_in(&self, category: PlayerCategory) -> Result<Player, FindingError> {
+        let players: PlayerIter = self.iter_players()?;
+
+        match self.find_active_player(players, |player| {
+            self.classifier.classify(player) == category
+        })? {
             Some(player) => Ok(player),
             None => Err(FindingError::NoPlayerFound),
         }
     }
 
-    /// Finds the index of an "active" player. Follows the order mentioned in [`find_active`](Self::find_active).
-    fn find_active_player(&self, players: PlayerIter) -> Result<Option<Player>, DBusError> {
+    /// Finds the index of an "active" player among the ones that `accept` returns [`true`] for.
+    /// Follows the order mentioned in [`find_active`](Self::find_active).
+    fn find_active_player<F>(
+        &self,
+        players: PlayerIter,
+        accept: F,
+    ) -> Result<Option<Player>, DBusError>
+    where
+        F: Fn(&Player) -> bool,
+    {
         if players.len() == 0 {
             return Ok(None);
         }
 
-        let mut first_paused: Option<Player> = None;
-        let mut first_with_track: Option<Player> = None;
-        let mut first_found: Option<Player> = None;
+        let mut best: Option<(i64, Player)> = None;
 
         for player in players {
             let player = player?;
-            let player_status = player.get_playback_status()?;
+            if !accept(&player) {
+                continue;
+            }
+            let score = player.activity_score()?;
 
-            if player_status == PlaybackStatus::Playing {
+            if score >= MAX_ACTIVITY_SCORE {
                 return Ok(Some(player));
             }
 
-            if first_paused.is_none() && player_status == PlaybackStatus::Paused {
-                first_paused.replace(player);
-            } else if first_with_track.is_none() && !player.get_metadata()?.is_empty() {
-                first_with_track.replace(player);
-            } else if first_found.is_none() {
-                first_found.replace(player);
+            if best
+                .as_ref()
+                .map_or(true, |(best_score, _)| score > *best_score)
+            {
+                best = Some((score, player));
+            }
+        }
+
+        Ok(best.map(|(_, player)| player))
+    }
+
+    /// Finds out what is playing, on any player.
+    ///
+    /// The player is chosen like [`find_active`](Self::find_active) does, and its state is read
+    /// with a single D-Bus call. Players that fail to reply are skipped instead of failing the
+    /// whole search, so one hanging player does not hide the others. Returns [`None`] if no
+    /// player could be read.
+    ///
+    /// Like [`find_active`](Self::find_active), this can return a stopped player if no player is
+    /// playing or paused.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use mpris::PlayerFinder;
+    /// if let Some(now_playing) = PlayerFinder::new().unwrap().now_playing().unwrap() {
+    ///     println!(
+    ///         "{}: {} at {:?}",
+    ///         now_playing.player().identity(),
+    ///         now_playing.metadata().now_playing(),
+    ///         now_playing.position()
+    ///     );
+    /// }
+    /// ```
+    pub fn now_playing(&self) -> Result<Option<NowPlaying>, FindingError> {
+        let mut best: Option<(i64, NowPlaying)> = None;
+
+        for now_playing in self.read_all_now_playing()? {
+            let score = now_playing.activity_score();
+            if score >= MAX_ACTIVITY_SCORE {
+                return Ok(Some(now_playing));
+            }
+            if best
+                .as_ref()
+                .map_or(true, |(best_score, _)| score > *best_score)
+            {
+                best = Some((score, now_playing));
             }
         }
 
-        Ok(first_paused.or(first_with_track).or(first_found))
+        Ok(best.map(|(_, now_playing)| now_playing))
+    }
+
+    /// Like [`now_playing`](Self::now_playing), but returns every player that is playing or
+    /// paused, ordered like [`find_active`](Self::find_active) would prefer them.
+    ///
+    /// Players that fail to reply are skipped. Use [`now_playing_all`](Self::now_playing_all) to
+    /// see which players failed, and why.
+    pub fn now_playing_list(&self) -> Result<Vec<NowPlaying>, FindingError> {
+        let mut list: Vec<NowPlaying> = self
+            .read_all_now_playing()?
+            .filter(|now_playing| now_playing.playback_status() != PlaybackStatus::Stopped)
+            .collect();
+        // The sort is stable, so players with the same score stay in the order they were found.
+        list.sort_by_key(|now_playing| std::cmp::Reverse(now_playing.activity_score()));
+        Ok(list)
+    }
+
+    /// Reads the state of every player that replies, skipping the others.
+    fn read_all_now_playing(&self) -> Result<impl Iterator<Item = NowPlaying>, DBusError> {
+        Ok(self
+            .iter_players()?
+            .filter_map(Result::ok)
+            .filter_map(|player| NowPlaying::read(player).ok()))
     }
 
     /// Find a [`Player`] by it's MPRIS [`Identity`][identity]. Returns [`NoPlayerFound`](FindingError::NoPlayerFound) if no direct match found.
@@ -149,31 +338,247 @@ impl PlayerFinder {
         Err(FindingError::NoPlayerFound)
     }
 
-    /// Returns all of the MPRIS DBus paths
-    fn all_player_buses(&self) -> Result<Vec<String>, DBusError> {
-        let list_names = Message::new_method_call(
+    /// Blocks until any [`Player`] is playing, and returns it.
+    ///
+    /// A player that is already playing is returned right away. Otherwise this waits for a player
+    /// to start playing, or for a new player to appear on the bus that is playing. Returns an
+    /// [`Err`] if `timeout` runs out first, or if the list of players could not be read. Without
+    /// a timeout this waits forever.
+    ///
+    /// The players are checked again each time one of them emits a signal or a player appears,
+    /// and at least once every second to support players that do not emit signals when changing
+    /// status.
+    pub fn wait_for_any_playing(&self, timeout: Option<Duration>) -> Result<Player, DBusError> {
+        self.wait_for_playing(timeout, |_| true)
+    }
+
+    /// Like [`wait_for_any_playing`](Self::wait_for_any_playing), but only for players whose bus
+    /// name is accepted by `include`.
+    fn wait_for_playing<F>(
+        &self,
+        timeout: Option<Duration>,
+        include: F,
+    ) -> Result<Player, DBusError>
+    where
+        F: Fn(&str) -> bool,
+    {
+        const MAX_WAIT: Duration = Duration::from_secs(1);
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        // Players are lenient by default, so statuses like "playing" count too.
+        let decoding = Decoding::default();
+
+        loop {
+            for bus_name in self.all_player_buses()? {
+                if !include(&bus_name) {
+                    continue;
+                }
+
+                let status = self
+                    .connection
+                    .with_path(
+                        bus_name.as_str().into(),
+                        MPRIS2_PATH.into(),
+                        self.player_timeout_ms,
+                    )
+                    .playback_status();
+                let playing = status.ok().map_or(false, |status| {
+                    decoding
+                        .decode(&decode::PLAYBACK_STATUS, &Value::String(status))
+                        .ok()
+                        == Some(PlaybackStatus::Playing)
+                });
+                if !playing {
+                    continue;
+                }
+
+                // The player might quit before it is created. Keep waiting for another one then.
+                if let Ok(player) = Player::for_pooled_connection(
+                    self.connection.clone(),
+                    bus_name,
+                    self.player_timeout_ms,
+                ) {
+                    return Ok(player);
+                }
+            }
+
+            let wait = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(left) if left > Duration::from_millis(0) => left.min(MAX_WAIT),
+                    _ => {
+                        return Err(DBusError::Miscellaneous(format!(
+                            "No player started playing within {:?}",
+                            timeout.unwrap_or_default()
+                        )))
+                    }
+                },
+                None => MAX_WAIT,
+            };
+            self.connection.process_next_event_blocking_for(wait);
+        }
+    }
+
+    /// Starts a player with D-Bus activation, and returns it once it is ready.
+    ///
+    /// `desktop_name` is the part of the bus name after `org.mpris.MediaPlayer2.`, which usually
+    /// matches the player's `.desktop` file (like `vlc` or `spotify`). The bus starts the player
+    /// if it installed a D-Bus service file for that name. A player that is already running is
+    /// returned right away.
+    ///
+    /// Waits up to `timeout` for the player to start and to answer its first calls. Returns an
+    /// [`Err`] if no service file provides the name, if the player could not be started, or if it
+    /// did not become ready in time.
+    pub fn activate(&self, desktop_name: &str, timeout: Duration) -> Result<Player, DBusError> {
+        const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+        let bus_name = format!("{}{}", MPRIS2_PREFIX, desktop_name);
+        let deadline = Instant::now() + timeout;
+        let time_left = || deadline.saturating_duration_since(Instant::now());
+
+        if self.connection.name_has_owner(bus_name.as_str()) != Some(true) {
+            self.start_service(&bus_name, time_left())?;
+        }
+
+        // The player owns its name now, but might still be setting up its object.
+        loop {
+            let error = match Player::for_pooled_connection(
+                self.connection.clone(),
+                bus_name.clone(),
+                self.player_timeout_ms,
+            ) {
+                Ok(player) => return Ok(player),
+                Err(error) => error,
+            };
+
+            let wait = time_left();
+            if wait == Duration::from_millis(0) {
+                return Err(DBusError::Miscellaneous(format!(
+                    "{} did not become ready within {:?}: {}",
+                    bus_name, timeout, error
+                )));
+            }
+            std::thread::sleep(wait.min(RETRY_INTERVAL));
+        }
+    }
+
+    /// Asks the bus to start the service that provides `bus_name`.
+    fn start_service(&self, bus_name: &str, timeout: Duration) -> Result<(), DBusError> {
+        let start = Message::new_method_call(
             "org.freedesktop.DBus",
-            "/",
+            "/org/freedesktop/DBus",
             "org.freedesktop.DBus",
-            "ListNames",
+            "StartServiceByName",
         )
-        .unwrap();
-
-        let reply = self
-            .connection
+        .unwrap()
+        .append2(bus_name, 0u32);
+        let timeout_ms = i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX);
+        self.connection
             .underlying()
-            .send_with_reply_and_block(list_names, LIST_NAMES_TIMEOUT_MS)?;
+            .send_with_reply_and_block(start, timeout_ms.max(1))
+            .map_err(|error| match error.name().map(dbus_error_kind) {
+                Some(DBusErrorKind::ServiceUnknown) => DBusError::Miscellaneous(format!(
+                    "{} is not running and can not be activated: {}",
+                    bus_name,
+                    error
+                        .message()
+                        .unwrap_or("no D-Bus service file provides it")
+                )),
+                _ => DBusError::from(error),
+            })?;
+        Ok(())
+    }
+
+    /// Reads the playback status and the current track of every running player.
+    ///
+    /// Each player is read with a single D-Bus call, without creating [`Player`]s. The result
+    /// has one entry per player, with its bus name (like `org.mpris.MediaPlayer2.vlc`) and either
+    /// its status and metadata, or the error that reading it caused. A player that fails to reply
+    /// does not stop the others from being read. The metadata is [`None`] when the player has no
+    /// current track.
+    ///
+    /// Only fails if the list of players could not be read.
+    #[allow(clippy::type_complexity)]
+    pub fn now_playing_all(
+        &self,
+    ) -> Result<
+        Vec<(
+            String,
+            Result<(PlaybackStatus, Option<Metadata>), DBusError>,
+        )>,
+        DBusError,
+    > {
+        Ok(self
+            .all_player_buses()?
+            .into_iter()
+            .map(|bus_name| {
+                let now_playing = self.read_now_playing(&bus_name);
+                (bus_name, now_playing)
+            })
+            .collect())
+    }
 
+    fn read_now_playing(
+        &self,
+        bus_name: &str,
+    ) -> Result<(PlaybackStatus, Option<Metadata>), DBusError> {
+        let mut properties = get_all_player_properties(self.connection.with_path(
+            bus_name.into(),
+            MPRIS2_PATH.into(),
+            self.player_timeout_ms,
+        ))?;
+
+        // Decode like a lenient player would, as there is no Player to take the strictness from.
+        let status = properties
+            .get("PlaybackStatus")
+            .ok_or_else(|| {
+                DBusError::Miscellaneous(String::from("Player has no readable PlaybackStatus"))
+            })
+            .and_then(|value| Decoding::default().decode(&decode::PLAYBACK_STATUS, value))?;
+        let metadata = match properties.remove("Metadata") {
+            Some(Value::Map(values)) if !values.is_empty() => Some(Metadata::from(values)),
+            _ => None,
+        };
+
+        Ok((status, metadata))
+    }
+
+    /// Count the running [`Player`]s without connecting to any of them.
+    ///
+    /// This only asks the D-Bus which MPRIS names are taken, so it is much cheaper than
+    /// `find_all()?.len()`. Useful for a quick "are there any players?" check.
+    pub fn running_count(&self) -> Result<usize, DBusError> {
+        let reply = self.list_names()?;
+        let names: arg::Array<'_, &str, _> = reply.read1().map_err(DBusError::from)?;
+        Ok(names.filter(|name| self.is_player_bus(name)).count())
+    }
+
+    /// Returns all of the MPRIS DBus paths
+    fn all_player_buses(&self) -> Result<Vec<String>, DBusError> {
+        let reply = self.list_names()?;
         let names: arg::Array<'_, &str, _> = reply.read1().map_err(DBusError::from)?;
 
         let mut all_busses = names
-            .filter(|name| name.starts_with(MPRIS2_PREFIX))
+            .filter(|name| self.is_player_bus(name))
             .map(|str_ref| str_ref.to_owned())
             .collect::<Vec<String>>();
         all_busses.sort_by_key(|a| a.to_lowercase());
         Ok(all_busses)
     }
 
+    /// Asks the D-Bus for every name on the bus.
+    fn list_names(&self) -> Result<Message, DBusError> {
+        let list_names = Message::new_method_call(
+            "org.freedesktop.DBus",
+            "/",
+            "org.freedesktop.DBus",
+            "ListNames",
+        )
+        .unwrap();
+
+        Ok(self
+            .connection
+            .underlying()
+            .send_with_reply_and_block(list_names, LIST_NAMES_TIMEOUT_MS)?)
+    }
+
     /// Returns a [`PlayerIter`] iterator, or an [`DBusError`] if there was a problem with the D-Bus
     ///
     /// For more details see [`PlayerIter`] documentation
@@ -233,3 +638,218 @@ impl Iterator for PlayerIter {
 impl ExactSizeIterator for PlayerIter {}
 
 impl FusedIterator for PlayerIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::start_fake;
+    use crate::FakePlayer;
+
+    #[test]
+    fn it_reads_what_every_player_is_playing() {
+        let playing = start_fake("now_playing");
+        playing.set_playback_status(PlaybackStatus::Playing);
+        playing.set_metadata(Metadata::new("/org/example/Track/1"));
+        let idle = start_fake("now_playing_idle");
+        let broken = start_fake("now_playing_broken");
+        broken.remove_property("PlaybackStatus");
+        let lenient = start_fake("now_playing_lenient");
+        lenient.set_property("PlaybackStatus", Value::from("paused"));
+
+        let finder = PlayerFinder::new().unwrap();
+        let all = finder.now_playing_all().unwrap();
+        let entry = |fake: &FakePlayer| {
+            all.iter()
+                .find(|(bus_name, _)| bus_name == fake.bus_name())
+                .map(|(_, now_playing)| now_playing)
+                .expect("Player is missing")
+        };
+
+        let (status, metadata) = entry(&playing).as_ref().unwrap();
+        assert_eq!(*status, PlaybackStatus::Playing);
+        assert_eq!(
+            metadata
+                .as_ref()
+                .and_then(Metadata::track_id)
+                .map(|id| id.to_string()),
+            Some(String::from("/org/example/Track/1"))
+        );
+
+        let (status, metadata) = entry(&idle).as_ref().unwrap();
+        assert_eq!(*status, PlaybackStatus::Stopped);
+        assert!(metadata.is_none());
+
+        assert!(entry(&broken).is_err());
+
+        let (status, _) = entry(&lenient).as_ref().unwrap();
+        assert_eq!(*status, PlaybackStatus::Paused);
+    }
+
+    #[test]
+    fn it_finds_what_is_playing_skipping_broken_players() {
+        let playing = start_fake("now_playing_list");
+        playing.set_metadata(Metadata::new("/org/example/Track/1"));
+        playing.set_playback_status(PlaybackStatus::Playing);
+        let paused = start_fake("now_playing_paused");
+        paused.set_playback_status(PlaybackStatus::Paused);
+        let stopped = start_fake("now_playing_stopped");
+        let broken = start_fake("now_playing_failing");
+        broken.remove_property("PlaybackStatus");
+
+        let finder = PlayerFinder::new().unwrap();
+        let list = finder.now_playing_list().unwrap();
+        let position = |fake: &FakePlayer| {
+            list.iter()
+                .position(|now_playing| now_playing.player().bus_name() == fake.bus_name())
+        };
+        let playing_index = position(&playing).expect("Playing player is missing");
+        assert!(playing_index < position(&paused).expect("Paused player is missing"));
+        assert_eq!(position(&stopped), None);
+        assert_eq!(position(&broken), None);
+        assert_eq!(
+            list[playing_index]
+                .metadata()
+                .track_id()
+                .map(|id| id.to_string()),
+            Some(String::from("/org/example/Track/1"))
+        );
+
+        let now_playing = finder.now_playing().unwrap().expect("Nothing is playing");
+        assert_eq!(now_playing.playback_status(), PlaybackStatus::Playing);
+    }
+
+    #[test]
+    fn it_counts_running_players() {
+        let fake = start_fake("running_count");
+        let finder = PlayerFinder::new().unwrap();
+
+        assert!(finder.running_count().unwrap() >= 1);
+        assert!(finder
+            .all_player_buses()
+            .unwrap()
+            .iter()
+            .any(|name| name == fake.bus_name()));
+    }
+
+    #[test]
+    fn it_finds_players_under_extra_prefixes() {
+        let prefix = format!("org.example.Sandboxed{}.", std::process::id());
+        let fake = FakePlayer::start_with_bus_name(&format!("{}player", prefix)).unwrap();
+        let mut finder = PlayerFinder::new().unwrap();
+        let found = |finder: &PlayerFinder| {
+            finder
+                .all_player_buses()
+                .unwrap()
+                .iter()
+                .any(|name| name == fake.bus_name())
+        };
+
+        assert!(!found(&finder));
+
+        finder.set_extra_bus_name_prefixes(vec![prefix.as_str()]);
+        assert!(found(&finder));
+        let player = finder
+            .find_all()
+            .unwrap()
+            .into_iter()
+            .find(|player| player.bus_name() == fake.bus_name())
+            .unwrap();
+        assert_eq!(player.identity(), fake.bus_name());
+    }
+
+    #[test]
+    fn it_finds_active_players_by_category() {
+        let browser = start_fake("firefox");
+        browser.set_playback_status(PlaybackStatus::Playing);
+        let music = start_fake("jukebox");
+        let mut metadata = Metadata::new("/org/example/Track/1");
+        metadata.insert("xesam:url", Value::from("file:///music/song.flac"));
+        music.set_metadata(metadata);
+        let finder = PlayerFinder::new().unwrap();
+
+        let player = finder.find_active_in(PlayerCategory::Browser).unwrap();
+        assert_eq!(player.bus_name(), browser.bus_name());
+        assert!(player.is_browser());
+
+        let player = finder
+            .find_by_name(&format!("jukebox.pid{}", std::process::id()))
+            .unwrap();
+        assert!(!player.is_browser());
+        assert_eq!(player.category(), PlayerCategory::Music);
+
+        let mut classifier = PlayerClassifier::new();
+        classifier.add("jukebox", PlayerCategory::Video);
+        assert_eq!(classifier.classify(&player), PlayerCategory::Video);
+    }
+
+    #[test]
+    fn it_activates_running_players_and_rejects_unknown_ones() {
+        let fake = start_fake("activate");
+        let finder = PlayerFinder::new().unwrap();
+
+        let player = finder
+            .activate(
+                &format!("activate.pid{}", std::process::id()),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+        assert_eq!(player.bus_name(), fake.bus_name());
+
+        let error = finder
+            .activate(
+                &format!("missing.pid{}", std::process::id()),
+                Duration::from_secs(5),
+            )
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("can not be activated"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn it_waits_for_a_player_to_start_playing() {
+        let fake = start_fake("wait_for_playing");
+        let bus_name = fake.bus_name().to_string();
+        let finder = PlayerFinder::new().unwrap();
+
+        let starter = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            // A lenient spelling, which the player would decode as playing as well.
+            fake.set_property("PlaybackStatus", Value::from("playing"));
+            fake.emit_properties_changed(&["PlaybackStatus"]);
+            fake
+        });
+
+        // Other tests might have playing players on the same bus, so only look at this one.
+        let player = finder
+            .wait_for_playing(Some(Duration::from_secs(5)), |name| name == bus_name)
+            .unwrap();
+        assert_eq!(player.bus_name(), bus_name);
+        assert_eq!(
+            player.get_playback_status().unwrap(),
+            PlaybackStatus::Playing
+        );
+        drop(starter.join().unwrap());
+    }
+
+    #[test]
+    fn it_times_out_when_no_player_starts_playing() {
+        let fake = start_fake("never_playing");
+        let finder = PlayerFinder::new().unwrap();
+
+        let started = Instant::now();
+        let error = finder
+            .wait_for_playing(Some(Duration::from_millis(300)), |name| {
+                name == fake.bus_name()
+            })
+            .unwrap_err();
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert!(
+            error.to_string().contains("No player started playing"),
+            "{}",
+            error
+        );
+    }
+}
@@ -24,6 +24,9 @@
 //!
 //! 1. Look at the examples under `examples/`.
 //! 2. Look at the [`PlayerFinder`] struct.
+//! 3. Import the most common types with `use mpris::prelude::*;`. See [`prelude`].
+//!
+//! Every public type is exported from the crate root; the modules that define them are private.
 //!
 //! [spec]: https://specifications.freedesktop.org/mpris-spec/latest/
 
@@ -34,22 +37,76 @@ mod extensions;
 #[allow(unreachable_pub)]
 mod generated;
 
+mod capability_report;
+mod category;
+mod controls;
+mod decode;
 mod event;
+#[cfg(any(test, feature = "test-support"))]
+mod fake_player;
 mod find;
 mod metadata;
+mod now_playing;
+mod open_uri;
 mod player;
 mod pooled_connection;
 mod progress;
+mod property_cache;
+mod retry;
+mod seek;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod trace;
 mod track_list;
+mod volume;
+
+pub mod prelude;
 
-pub use crate::event::{Event, EventError, PlayerEvents};
+/// The version of [`dbus`] that is used in public signatures, like
+/// [`PlayerFinder::for_connection`].
+pub use dbus;
+
+pub use crate::capability_report::CapabilityReport;
+pub use crate::category::{PlayerCategory, PlayerClassifier};
+pub use crate::controls::PlayerControls;
+pub use crate::decode::DecodeWarning;
+pub use crate::event::{
+    Event, EventError, PlayerEvents, PlayerEventsBuilder, StopHandle, TrackChanges,
+};
+#[cfg(any(test, feature = "test-support"))]
+pub use crate::fake_player::FakePlayer;
 pub use crate::find::{FindingError, PlayerFinder, PlayerIter};
 pub use crate::metadata::Metadata;
 pub use crate::metadata::Value as MetadataValue;
 pub use crate::metadata::ValueKind as MetadataValueKind;
+pub use crate::metadata::{
+    DefaultNormalizer, MediaLocation, MetadataIssue, MetadataIssueKind, MetadataNormalizer,
+    MetadataSortKey, Rating, RatingSource, Timestamp,
+};
+pub use crate::now_playing::NowPlaying;
+pub use crate::open_uri::OpenUriOutcome;
 pub use crate::player::Player;
-pub use crate::progress::{Progress, ProgressError, ProgressTick, ProgressTracker};
-pub use crate::track_list::{TrackID, TrackList, TrackListError};
+pub use crate::progress::{
+    Capabilities, Position, PositionSource, Progress, ProgressError, ProgressTick, ProgressTracker,
+    RootCapabilities,
+};
+pub use crate::property_cache::CachePolicy;
+pub use crate::retry::RetryPolicy;
+pub use crate::seek::{SeekBounds, SeekMethod, SetPositionOutcome};
+pub use crate::track_list::{
+    InvalidTrackID, InvalidTrackIDReason, MetadataIter, TrackID, TrackList, TrackListError,
+};
+pub use crate::volume::Volume;
+
+/// How much two volumes or playback rates may differ while still being considered equal. Players
+/// often round the values they are given, so an exact comparison would see changes that did not
+/// happen.
+pub(crate) const FLOAT_TOLERANCE: f64 = 1e-4;
+
+/// Compares two volumes or playback rates within [`FLOAT_TOLERANCE`].
+pub(crate) fn floats_equal(a: f64, b: f64) -> bool {
+    (a - b).abs() < FLOAT_TOLERANCE
+}
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 #[allow(missing_docs)]
@@ -84,62 +141,138 @@ pub enum LoopStatus {
     Playlist,
 }
 
+impl PlaybackStatus {
+    /// Every status, in the order of the specification. Useful to build pickers.
+    pub const ALL: [PlaybackStatus; 3] = [
+        PlaybackStatus::Playing,
+        PlaybackStatus::Paused,
+        PlaybackStatus::Stopped,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            PlaybackStatus::Playing => "Playing",
+            PlaybackStatus::Paused => "Paused",
+            PlaybackStatus::Stopped => "Stopped",
+        }
+    }
+}
+
 /// [`PlaybackStatus`] had an invalid string value.
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
 #[error("PlaybackStatus must be one of Playing, Paused, Stopped, but was {0}")]
 pub struct InvalidPlaybackStatus(String);
 
+impl InvalidPlaybackStatus {
+    /// The string that could not be parsed.
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Parses the status names of the specification, ignoring case: `"Playing"` and `"playing"` are
+/// both [`PlaybackStatus::Playing`].
 impl ::std::str::FromStr for PlaybackStatus {
     type Err = InvalidPlaybackStatus;
 
     fn from_str(string: &str) -> Result<Self, Self::Err> {
-        use crate::PlaybackStatus::*;
+        PlaybackStatus::ALL
+            .iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(string))
+            .copied()
+            .ok_or_else(|| InvalidPlaybackStatus(string.to_string()))
+    }
+}
 
-        match string {
-            "Playing" => Ok(Playing),
-            "Paused" => Ok(Paused),
-            "Stopped" => Ok(Stopped),
-            other => Err(InvalidPlaybackStatus(other.to_string())),
-        }
+/// Writes the status name of the specification, like `Playing`.
+impl ::std::fmt::Display for PlaybackStatus {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
 /// [`LoopStatus`] had an invalid string value.
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
 #[error("LoopStatus must be one of None, Track, Playlist, but was {0}")]
 pub struct InvalidLoopStatus(String);
 
+impl InvalidLoopStatus {
+    /// The string that could not be parsed.
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Parses the status names of the specification, ignoring case: `"Playlist"` and `"playlist"`
+/// are both [`LoopStatus::Playlist`].
 impl ::std::str::FromStr for LoopStatus {
     type Err = InvalidLoopStatus;
 
     fn from_str(string: &str) -> Result<Self, Self::Err> {
-        match string {
-            "None" => Ok(LoopStatus::None),
-            "Track" => Ok(LoopStatus::Track),
-            "Playlist" => Ok(LoopStatus::Playlist),
-            other => Err(InvalidLoopStatus(other.to_string())),
-        }
+        LoopStatus::ALL
+            .iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(string))
+            .copied()
+            .ok_or_else(|| InvalidLoopStatus(string.to_string()))
+    }
+}
+
+/// Writes the status name of the specification, like `Playlist`.
+impl ::std::fmt::Display for LoopStatus {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
 impl LoopStatus {
-    fn dbus_value(self) -> String {
-        String::from(match self {
+    /// Every status, in the order of the specification. Useful to build pickers.
+    pub const ALL: [LoopStatus; 3] = [LoopStatus::None, LoopStatus::Track, LoopStatus::Playlist];
+
+    fn as_str(self) -> &'static str {
+        match self {
             LoopStatus::None => "None",
             LoopStatus::Track => "Track",
             LoopStatus::Playlist => "Playlist",
-        })
+        }
+    }
+
+    fn dbus_value(self) -> String {
+        String::from(self.as_str())
     }
 }
 
 /// Something went wrong when communicating with the D-Bus. This could either be an underlying
 /// D-Bus library problem, or that the other side did not conform to the expected protocols.
+///
+/// The error is `Send + Sync + 'static`, and [`source`](std::error::Error::source) returns the
+/// underlying error of the [`dbus`] crate when there is one, so error reporting libraries can show
+/// the whole chain.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum DBusError {
     /// An error occurred while talking to the D-Bus.
     #[error("D-Bus call failed: {0}")]
     TransportError(#[from] dbus::Error),
 
+    /// A call to a [`Player`] failed on the D-Bus. This is like
+    /// [`TransportError`](Self::TransportError), but also says which player and which method or
+    /// property the call was about, like `failed to get Volume from
+    /// org.mpris.MediaPlayer2.spotify: Did not receive a reply`.
+    #[error("failed to {operation} {member} {} {bus_name}: {source}", operation.preposition())]
+    CallFailed {
+        /// What the call tried to do.
+        operation: CallOperation,
+
+        /// The name of the method, or of the property that was read or written.
+        member: String,
+
+        /// The bus name of the [`Player`].
+        bus_name: String,
+
+        /// The error that the call failed with.
+        source: dbus::Error,
+    },
+
     /// Failed to parse an enum from a string value received from the [`Player`]. This means that the
     /// [`Player`] replied with unexpected data.
     #[error("Failed to parse enum value: {0}")]
@@ -150,11 +283,214 @@ pub enum DBusError {
     #[error("D-Bus call failed: {0}")]
     TypeMismatchError(#[from] dbus::arg::TypeMismatchError),
 
+    /// The [`Player`] rejected a control call (like seeking or skipping to the next track) while
+    /// it was stopped.
+    ///
+    /// Some players do not allow these calls without a current track, and reply with an error
+    /// that does not say why.
+    #[error("Can not {action} while the player is stopped: {source}")]
+    NotAllowedWhileStopped {
+        /// What the call tried to do, like `"seek"`.
+        action: String,
+
+        /// The error that the [`Player`] replied with.
+        source: dbus::Error,
+    },
+
+    /// The current track can not be seeked in by position, because its track ID or its length
+    /// is unknown, or because the player does not allow seeking. This is common for live streams,
+    /// so user interfaces can disable seeking when they see this.
+    #[error("Can not seek in the current track, as {0}")]
+    TrackNotSeekable(NotSeekableReason),
+
+    /// The [`Player`] reports that it does not allow a call, so it was not sent. Contains the
+    /// name of the capability property that is `false`, like `"CanSetFullscreen"`.
+    #[error("The player does not allow this, as {0} is false")]
+    NotAllowed(String),
+
     /// Some other unexpected error occurred.
     #[error("Unexpected error: {0}")]
     Miscellaneous(String),
 }
 
+/// What kind of failure a [`DBusError`] is, to decide how to react to it.
+///
+/// See [`DBusError::kind`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[non_exhaustive]
+pub enum DBusErrorKind {
+    /// The [`Player`] is not on the bus, usually because it exited. Look for the player again
+    /// instead of retrying.
+    ///
+    /// D-Bus errors: `ServiceUnknown`, `NameHasNoOwner`.
+    ServiceUnknown,
+
+    /// The [`Player`] did not reply in time, likely because it is busy. Retrying might work.
+    ///
+    /// D-Bus errors: `NoReply`, `Timeout`, `TimedOut`.
+    Timeout,
+
+    /// The bus or the [`Player`] did not allow the call.
+    ///
+    /// D-Bus errors: `AccessDenied`, `AuthFailed`.
+    AccessDenied,
+
+    /// The [`Player`] does not implement the method, property or interface.
+    ///
+    /// D-Bus errors: `UnknownMethod`, `UnknownProperty`, `UnknownInterface`, `UnknownObject`.
+    UnknownMethod,
+
+    /// The [`Player`] rejected the arguments of the call, or the property can not be set.
+    ///
+    /// D-Bus errors: `InvalidArgs`, `InvalidSignature`, `PropertyReadOnly`.
+    InvalidArguments,
+
+    /// The connection to the bus was lost or could not be made.
+    ///
+    /// D-Bus errors: `Disconnected`, `NoServer`, `NoNetwork`.
+    Disconnected,
+
+    /// The [`Player`] rejected a control call because it is stopped.
+    ///
+    /// This is [`DBusError::NotAllowedWhileStopped`].
+    NotAllowedWhileStopped,
+
+    /// The current track can not be seeked in by position.
+    ///
+    /// This is [`DBusError::TrackNotSeekable`].
+    TrackNotSeekable,
+
+    /// The [`Player`] reports that it does not allow the call, so it was not sent.
+    ///
+    /// This is [`DBusError::NotAllowed`].
+    NotAllowed,
+
+    /// The [`Player`] replied with data of an unexpected type or with an unknown enum value.
+    ///
+    /// These are [`DBusError::EnumParseError`] and [`DBusError::TypeMismatchError`].
+    InvalidReply,
+
+    /// Any other error.
+    Other,
+}
+
+impl DBusError {
+    /// Returns what kind of failure this is, based on the name of the underlying D-Bus error.
+    pub fn kind(&self) -> DBusErrorKind {
+        match self {
+            DBusError::TransportError(error) | DBusError::CallFailed { source: error, .. } => {
+                error.name().map_or(DBusErrorKind::Other, dbus_error_kind)
+            }
+            DBusError::EnumParseError(_) | DBusError::TypeMismatchError(_) => {
+                DBusErrorKind::InvalidReply
+            }
+            DBusError::NotAllowedWhileStopped { .. } => DBusErrorKind::NotAllowedWhileStopped,
+            DBusError::TrackNotSeekable(_) => DBusErrorKind::TrackNotSeekable,
+            DBusError::NotAllowed(_) => DBusErrorKind::NotAllowed,
+            DBusError::Miscellaneous(_) => DBusErrorKind::Other,
+        }
+    }
+
+    /// The error of the [`dbus`] crate that caused this error, if any.
+    pub(crate) fn dbus_error(&self) -> Option<&dbus::Error> {
+        match self {
+            DBusError::TransportError(error)
+            | DBusError::CallFailed { source: error, .. }
+            | DBusError::NotAllowedWhileStopped { source: error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Says which player and member a failed call was about, if the error came from the D-Bus.
+    /// Other errors are returned unchanged.
+    pub(crate) fn in_call(self, operation: CallOperation, member: &str, bus_name: &str) -> Self {
+        match self {
+            DBusError::TransportError(source) => DBusError::CallFailed {
+                operation,
+                member: String::from(member),
+                bus_name: String::from(bus_name),
+                source,
+            },
+            other => other,
+        }
+    }
+}
+
+/// What a call to a [`Player`] tried to do. See [`DBusError::CallFailed`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[non_exhaustive]
+pub enum CallOperation {
+    /// Read a property.
+    Get,
+
+    /// Write a property.
+    Set,
+
+    /// Call a method.
+    Call,
+}
+
+impl CallOperation {
+    fn preposition(self) -> &'static str {
+        match self {
+            CallOperation::Get => "from",
+            CallOperation::Set | CallOperation::Call => "on",
+        }
+    }
+}
+
+/// Writes the operation as a verb, like `get`.
+impl ::std::fmt::Display for CallOperation {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match self {
+            CallOperation::Get => "get",
+            CallOperation::Set => "set",
+            CallOperation::Call => "call",
+        })
+    }
+}
+
+/// Why the current track can not be seeked in by position. See [`DBusError::TrackNotSeekable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[non_exhaustive]
+pub enum NotSeekableReason {
+    /// The track has no `mpris:trackid`, which `SetPosition` needs.
+    #[error("it has no track ID")]
+    NoTrackID,
+
+    /// The track's `mpris:trackid` is not a valid object path, so it can not be sent back to the
+    /// player. See [`Metadata::checked_track_id`] for details.
+    #[error("its track ID is not valid")]
+    InvalidTrackID,
+
+    /// The track has no `mpris:length`, or a length of zero.
+    #[error("its length is unknown")]
+    UnknownLength,
+
+    /// The player reports `CanSeek` as `false`.
+    #[error("the player does not allow seeking")]
+    PlayerCanNotSeek,
+}
+
+pub(crate) fn dbus_error_kind(name: &str) -> DBusErrorKind {
+    let name = match name.strip_prefix("org.freedesktop.DBus.Error.") {
+        Some(name) => name,
+        None => return DBusErrorKind::Other,
+    };
+
+    match name {
+        "ServiceUnknown" | "NameHasNoOwner" => DBusErrorKind::ServiceUnknown,
+        "NoReply" | "Timeout" | "TimedOut" => DBusErrorKind::Timeout,
+        "AccessDenied" | "AuthFailed" => DBusErrorKind::AccessDenied,
+        "UnknownMethod" | "UnknownProperty" | "UnknownInterface" | "UnknownObject" => {
+            DBusErrorKind::UnknownMethod
+        }
+        "InvalidArgs" | "InvalidSignature" | "PropertyReadOnly" => DBusErrorKind::InvalidArguments,
+        "Disconnected" | "NoServer" | "NoNetwork" => DBusErrorKind::Disconnected,
+        _ => DBusErrorKind::Other,
+    }
+}
+
 impl From<InvalidPlaybackStatus> for DBusError {
     fn from(error: InvalidPlaybackStatus) -> Self {
         DBusError::EnumParseError(error.to_string())
@@ -166,3 +502,120 @@ impl From<InvalidLoopStatus> for DBusError {
         DBusError::EnumParseError(error.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_statuses_ignoring_case() {
+        for status in PlaybackStatus::ALL.iter() {
+            let name = status.to_string();
+            assert_eq!(name.parse::<PlaybackStatus>().unwrap(), *status);
+            assert_eq!(
+                name.to_lowercase().parse::<PlaybackStatus>().unwrap(),
+                *status
+            );
+        }
+        for status in LoopStatus::ALL.iter() {
+            let name = status.to_string();
+            assert_eq!(name.parse::<LoopStatus>().unwrap(), *status);
+            assert_eq!(name.to_uppercase().parse::<LoopStatus>().unwrap(), *status);
+        }
+
+        assert_eq!(PlaybackStatus::Paused.to_string(), "Paused");
+        assert_eq!(LoopStatus::Playlist.to_string(), "Playlist");
+        assert_eq!(
+            "Buffering".parse::<PlaybackStatus>().unwrap_err().value(),
+            "Buffering"
+        );
+        assert_eq!(
+            "repeat".parse::<LoopStatus>().unwrap_err().value(),
+            "repeat"
+        );
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn it_serializes_statuses_as_strings() {
+        assert_eq!(
+            serde_json::to_string(&PlaybackStatus::Playing).unwrap(),
+            r#""Playing""#
+        );
+        assert_eq!(
+            serde_json::from_str::<LoopStatus>(r#""playlist""#).unwrap(),
+            LoopStatus::Playlist
+        );
+        assert!(serde_json::from_str::<LoopStatus>(r#""repeat""#).is_err());
+    }
+
+    #[test]
+    fn it_round_trips_loop_statuses_through_dbus_strings() {
+        for (status, dbus_string) in [
+            (LoopStatus::None, "None"),
+            (LoopStatus::Track, "Track"),
+            (LoopStatus::Playlist, "Playlist"),
+        ]
+        .iter()
+        {
+            assert_eq!(status.dbus_value(), *dbus_string);
+            assert_eq!(dbus_string.parse::<LoopStatus>().unwrap(), *status);
+        }
+    }
+
+    #[test]
+    fn it_categorizes_dbus_errors_by_name() {
+        fn kind(name: &str) -> DBusErrorKind {
+            DBusError::TransportError(dbus::Error::new_custom(name, "message")).kind()
+        }
+
+        assert_eq!(
+            kind("org.freedesktop.DBus.Error.ServiceUnknown"),
+            DBusErrorKind::ServiceUnknown
+        );
+        assert_eq!(
+            kind("org.freedesktop.DBus.Error.NameHasNoOwner"),
+            DBusErrorKind::ServiceUnknown
+        );
+        assert_eq!(
+            kind("org.freedesktop.DBus.Error.NoReply"),
+            DBusErrorKind::Timeout
+        );
+        assert_eq!(
+            kind("org.freedesktop.DBus.Error.AccessDenied"),
+            DBusErrorKind::AccessDenied
+        );
+        assert_eq!(
+            kind("org.freedesktop.DBus.Error.UnknownProperty"),
+            DBusErrorKind::UnknownMethod
+        );
+        assert_eq!(
+            kind("org.freedesktop.DBus.Error.InvalidArgs"),
+            DBusErrorKind::InvalidArguments
+        );
+        assert_eq!(
+            kind("org.freedesktop.DBus.Error.Disconnected"),
+            DBusErrorKind::Disconnected
+        );
+        assert_eq!(
+            kind("org.freedesktop.DBus.Error.Failed"),
+            DBusErrorKind::Other
+        );
+        assert_eq!(kind("com.example.Error.NoReply"), DBusErrorKind::Other);
+
+        assert_eq!(
+            DBusError::EnumParseError(String::from("Buffering")).kind(),
+            DBusErrorKind::InvalidReply
+        );
+        assert_eq!(
+            DBusError::Miscellaneous(String::from("oops")).kind(),
+            DBusErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn it_rejects_unknown_loop_statuses() {
+        assert!("off".parse::<LoopStatus>().is_err());
+        assert!("".parse::<LoopStatus>().is_err());
+    }
+}
@@ -1,8 +1,26 @@
+#[cfg(feature = "serde_json")]
+mod json;
+mod location;
+mod normalize;
+mod rating;
+mod sort_key;
+mod timestamp;
+mod validation;
 mod value;
+pub use self::location::MediaLocation;
+pub(crate) use self::normalize::NormalizerSlot;
+pub use self::normalize::{DefaultNormalizer, MetadataNormalizer};
+pub use self::rating::{Rating, RatingSource};
+pub use self::sort_key::MetadataSortKey;
+pub use self::timestamp::Timestamp;
+pub use self::validation::{MetadataIssue, MetadataIssueKind};
 pub use self::value::{Value, ValueKind};
-use super::TrackID;
+use super::{InvalidTrackID, TrackID};
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// A structured representation of the [`Player`](crate::player::Player) metadata.
@@ -10,11 +28,14 @@ use std::time::Duration;
 /// * [Read more about the MPRIS2 `Metadata_Map` type.][metadata_map]
 /// * [Read MPRIS v2 metadata guidelines][metadata_guidelines]
 ///
+/// The values are stored in shared, immutable storage, so cloning a [`Metadata`] is cheap even
+/// when it contains large values (like embedded lyrics).
+///
 /// [metadata_map]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Mapping:Metadata_Map
 /// [metadata_guidelines]: https://www.freedesktop.org/wiki/Specifications/mpris-spec/metadata/
 #[derive(Debug, Default, Clone)]
 pub struct Metadata {
-    values: HashMap<String, Value>,
+    values: Arc<HashMap<String, Value>>,
 }
 
 impl Metadata {
@@ -32,7 +53,9 @@ impl Metadata {
             Value::String(track_id.into()),
         );
 
-        Metadata { values }
+        Metadata {
+            values: Arc::new(values),
+        }
     }
 
     /// Get a value from the metadata by key name.
@@ -51,6 +74,27 @@ impl Metadata {
         self.values.get(key)
     }
 
+    /// Insert a value, replacing any previous value for the key. Returns the previous value.
+    ///
+    /// If this [`Metadata`] shares its values with clones of it, the values are copied first so
+    /// that the clones are not changed.
+    pub fn insert<S>(&mut self, key: S, value: Value) -> Option<Value>
+    where
+        S: Into<String>,
+    {
+        Arc::make_mut(&mut self.values).insert(key.into(), value)
+    }
+
+    /// Remove a value by key name. Returns the removed value.
+    ///
+    /// Like [`insert`](Self::insert), this does not change any clones of this [`Metadata`].
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        if !self.values.contains_key(key) {
+            return None;
+        }
+        Arc::make_mut(&mut self.values).remove(key)
+    }
+
     /// The track ID.
     ///
     /// If the [`TrackID`] could not be parsed as a proper [`TrackID`], [`None`] will be returned.
@@ -59,17 +103,35 @@ impl Metadata {
     /// > A unique identity for this track within the context of an MPRIS object.
     ///
     pub fn track_id(&self) -> Option<TrackID> {
+        self.checked_track_id().and_then(Result::ok)
+    }
+
+    /// The track ID, or the reason it is invalid.
+    ///
+    /// Players sometimes send an ID that is not a D-Bus object path, like `spotify:track:xyz`.
+    /// Such metadata is still kept as-is, but the ID can not be sent back to the player (for
+    /// example to seek in the track). Returns [`None`] if there is no `mpris:trackid` string.
+    pub fn checked_track_id(&self) -> Option<Result<TrackID, InvalidTrackID>> {
         self.get("mpris:trackid")
             .and_then(Value::as_str)
-            .and_then(|v| TrackID::new(v).ok())
+            .map(str::parse)
     }
 
     /// A list of artists of the album the track appears on.
     ///
     /// Based on `xesam:albumArtist`
     /// > The album artist(s).
+    ///
+    /// A single string is treated as a list with one artist. Returns [`None`] if the list is
+    /// empty.
     pub fn album_artists(&self) -> Option<Vec<&str>> {
-        self.get("xesam:albumArtist").and_then(Value::as_str_array)
+        self.non_empty_str_array("xesam:albumArtist")
+    }
+
+    /// All of the [`album_artists`](Self::album_artists), joined by `separator`.
+    pub fn album_artists_joined(&self, separator: &str) -> Option<String> {
+        self.album_artists()
+            .map(|album_artists| album_artists.join(separator))
     }
 
     /// The name of the album the track appears on.
@@ -89,38 +151,94 @@ impl Metadata {
         self.get("mpris:artUrl").and_then(Value::as_str)
     }
 
+    /// The [`art_url`](Self::art_url) as a local file path, if it points to a local file.
+    ///
+    /// Returns [`None`] for remote URLs and for art embedded as a `data:` URI. See
+    /// [`embedded_art`](Self::embedded_art) for the latter.
+    pub fn art_path(&self) -> Option<PathBuf> {
+        match self.art_url().and_then(MediaLocation::parse) {
+            Some(MediaLocation::File(path)) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The raw bytes of album art that is embedded in the [`art_url`](Self::art_url) as a base64
+    /// `data:` URI.
+    ///
+    /// Returns [`None`] if the art is not embedded this way, or if the `data:` URI is malformed.
+    pub fn embedded_art(&self) -> Option<Vec<u8>> {
+        self.art_url().and_then(location::decode_base64_data_uri)
+    }
+
     /// A list of artists of the track.
     ///
     /// Based on `xesam:artist`
     /// > The track artist(s).
+    ///
+    /// A single string is treated as a list with one artist. Returns [`None`] if the list is
+    /// empty, like [`album_artists`](Self::album_artists).
     pub fn artists(&self) -> Option<Vec<&str>> {
-        self.get("xesam:artist").and_then(Value::as_str_array)
+        self.non_empty_str_array("xesam:artist")
+    }
+
+    /// The first of the track's [`artists`](Self::artists).
+    ///
+    /// Useful when only a single artist can be displayed.
+    pub fn first_artist(&self) -> Option<&str> {
+        self.artists()
+            .and_then(|artists| artists.into_iter().next())
+    }
+
+    /// All of the track's [`artists`](Self::artists), joined by `separator`.
+    ///
+    /// Returns [`None`] when there are no artists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::Metadata;
+    /// # let metadata = Metadata::new("/1");
+    /// let artist = metadata.artist_string(", ").unwrap_or_else(|| String::from("Unknown"));
+    /// ```
+    pub fn artist_string(&self, separator: &str) -> Option<String> {
+        self.artists().map(|artists| artists.join(separator))
     }
 
     /// Based on `xesam:autoRating`
     /// > An automatically-generated rating, based on things such as how often it has been played.
     /// > This should be in the range 0.0 to 1.0.
     pub fn auto_rating(&self) -> Option<f64> {
-        self.get("xesam:autoRating").and_then(Value::as_f64)
+        self.get("xesam:autoRating").and_then(Value::coerce_f64)
+    }
+
+    /// Free-form comments about the track.
+    ///
+    /// Based on `xesam:comment`
+    /// > A (list of) freeform comment(s).
+    ///
+    /// A single string is treated as a list with one comment. Returns [`None`] if the list is
+    /// empty.
+    pub fn comments(&self) -> Option<Vec<&str>> {
+        self.non_empty_str_array("xesam:comment")
     }
 
     /// Based on `xesam:discNumber`
     /// > The disc number on the album that this track is from.
+    ///
+    /// Players that send the number as an unsigned integer or as a string (like `"02"`) are also
+    /// supported.
     pub fn disc_number(&self) -> Option<i32> {
-        self.get("xesam:discNumber").and_then(Value::as_i32)
+        self.get("xesam:discNumber").and_then(lenient_i32)
     }
 
     /// The duration of the track, in microseconds
     ///
     /// Based on `mpris:length`
     /// > The duration of the track in microseconds.
+    ///
+    /// Any numeric type is accepted, as long as it is a non-negative integer.
     pub fn length_in_microseconds(&self) -> Option<u64> {
-        match self.get("mpris:length") {
-            Some(Value::I64(len)) => Some(*len as u64),
-            Some(Value::U64(len)) => Some(*len),
-            Some(_) => None,
-            None => None,
-        }
+        self.get("mpris:length").and_then(Value::coerce_integer)
     }
 
     /// The duration of the track, as a [`Duration`]
@@ -143,8 +261,11 @@ impl Metadata {
     ///
     /// Based on `xesam:trackNumber`
     /// > The track number on the album disc.
+    ///
+    /// Players that send the number as an unsigned integer or as a string (like `"07"`) are also
+    /// supported.
     pub fn track_number(&self) -> Option<i32> {
-        self.get("xesam:trackNumber").and_then(Value::as_i32)
+        self.get("xesam:trackNumber").and_then(lenient_i32)
     }
 
     /// A URL to the media being played.
@@ -155,6 +276,126 @@ impl Metadata {
         self.get("xesam:url").and_then(Value::as_str)
     }
 
+    /// The [`url`](Self::url) parsed as a [`MediaLocation`].
+    ///
+    /// Local files are returned as paths, with the percent-encoding of `file://` URLs decoded, so
+    /// they can be compared and opened directly.
+    pub fn url_parsed(&self) -> Option<MediaLocation<'_>> {
+        self.url().and_then(MediaLocation::parse)
+    }
+
+    /// A title that is always suitable for display, even when the player does not send one.
+    ///
+    /// The first of these that is available and not blank is used:
+    ///
+    /// 1. The [`title`](Self::title).
+    /// 2. The file name of the [`url`](Self::url), percent-decoded and without its extension.
+    /// 3. The last segment of the `mpris:trackid` path (unless it is the special "NoTrack" path).
+    /// 4. `"Unknown"`.
+    pub fn display_title(&self) -> Cow<'_, str> {
+        if let Some(title) = self.title().filter(|title| !title.trim().is_empty()) {
+            return Cow::Borrowed(title);
+        }
+
+        if let Some(stem) = self.url().and_then(url_file_stem) {
+            return Cow::Owned(stem);
+        }
+
+        self.get("mpris:trackid")
+            .and_then(Value::as_str)
+            .filter(|track_id| *track_id != crate::track_list::NO_TRACK)
+            .and_then(|track_id| track_id.rsplit('/').next())
+            .filter(|segment| !segment.trim().is_empty())
+            .map_or(Cow::Borrowed("Unknown"), Cow::Borrowed)
+    }
+
+    /// The track's [`artists`](Self::artists) joined by `", "`, falling back to the
+    /// [`album_artists`](Self::album_artists) when there are no artists.
+    ///
+    /// Blank names are skipped. Returns [`None`] if no names are left.
+    pub fn display_artists(&self) -> Option<String> {
+        fn join(names: Option<Vec<&str>>) -> Option<String> {
+            let names: Vec<&str> = names?
+                .into_iter()
+                .filter(|name| !name.trim().is_empty())
+                .collect();
+            if names.is_empty() {
+                None
+            } else {
+                Some(names.join(", "))
+            }
+        }
+
+        join(self.artists()).or_else(|| join(self.album_artists()))
+    }
+
+    /// A single line describing the track, like `"Artist — Title"`, for status bars and
+    /// notifications.
+    ///
+    /// Uses the [`display_artists`](Self::display_artists) and the [`title`](Self::title). When
+    /// only one of them is available, only that one is returned. When neither is, this is the same
+    /// as [`display_title`](Self::display_title), which ends up as `"Unknown"` if nothing else is
+    /// known about the track.
+    pub fn now_playing(&self) -> String {
+        let title = self.title().filter(|title| !title.trim().is_empty());
+        match (self.display_artists(), title) {
+            (Some(artists), Some(title)) => format!("{} — {}", artists, title),
+            (None, Some(title)) => String::from(title),
+            (Some(artists), None) => artists,
+            (None, None) => self.display_title().into_owned(),
+        }
+    }
+
+    /// Formats the track with a template, replacing the placeholders `{artist}`, `{title}` and
+    /// `{album}`.
+    ///
+    /// `{title}` is the [`display_title`](Self::display_title) and `{artist}` the
+    /// [`display_artists`](Self::display_artists). `{artist}` and `{album}` are replaced with
+    /// nothing when they are missing. Any other text in braces is kept as it is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::{Metadata, MetadataValue};
+    /// let mut metadata = Metadata::new("/org/example/Track/1");
+    /// metadata.insert("xesam:title", MetadataValue::from("Riverside"));
+    /// metadata.insert("xesam:album", MetadataValue::from("Philharmonics"));
+    ///
+    /// assert_eq!(metadata.display_with("{title} ({album})"), "Riverside (Philharmonics)");
+    /// ```
+    pub fn display_with(&self, template: &str) -> String {
+        let mut output = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            output.push_str(&rest[..start]);
+            let placeholder = &rest[start..];
+            let replacement = placeholder.find('}').and_then(|end| {
+                let value = match &placeholder[1..end] {
+                    "artist" => Cow::Owned(self.display_artists().unwrap_or_default()),
+                    "title" => self.display_title(),
+                    "album" => Cow::Borrowed(self.album_name().unwrap_or("")),
+                    _ => return None,
+                };
+                Some((value, end))
+            });
+
+            match replacement {
+                Some((value, end)) => {
+                    output.push_str(&value);
+                    rest = &placeholder[end + 1..];
+                }
+                None => {
+                    output.push('{');
+                    rest = &placeholder[1..];
+                }
+            }
+        }
+
+        output.push_str(rest);
+        output
+    }
+
     /// Returns an owned [`HashMap`] of borrowed values from this [`Metadata`]. Useful if you need a
     /// mutable hash but don't have ownership of [`Metadata`] or want to consume it.
     ///
@@ -173,10 +414,114 @@ impl Metadata {
         self.values.keys().map(String::as_str)
     }
 
+    /// Iterate all metadata keys and values where the key starts with `prefix`, sorted by key.
+    ///
+    /// Useful to find player-specific extensions to the metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::Metadata;
+    /// # let metadata = Metadata::new("/1");
+    /// for (key, value) in metadata.keys_with_prefix("mpv:") {
+    ///     println!("{}: {:?}", key, value);
+    /// }
+    /// ```
+    pub fn keys_with_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a Value)> + 'a {
+        let mut matching: Vec<_> = self
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .collect();
+        matching.sort_by_key(|(key, _)| *key);
+        matching.into_iter()
+    }
+
+    /// Returns all values in a namespace, keyed by the part of the key after the namespace.
+    ///
+    /// The namespace is the part of the key before the first colon, so `namespace("xesam")` will
+    /// have `title` for the `xesam:title` key. A [`BTreeMap`] is used so iteration order is stable.
+    pub fn namespace(&self, ns: &str) -> BTreeMap<&str, &Value> {
+        self.iter()
+            .filter_map(|(key, value)| match key.split_once(':') {
+                Some((key_ns, name)) if key_ns == ns => Some((name, value)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns a copy with every key from the MPRIS2 metadata guidelines, but only the named
+    /// `keys` from outside of them.
+    ///
+    /// Useful to keep a few extension values (like lyrics or a rating) while dropping bulky
+    /// player-specific data before passing the metadata on.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::{Metadata, MetadataValue};
+    /// let mut metadata = Metadata::new("/1");
+    /// metadata.insert("xesam:title", MetadataValue::from("Song"));
+    /// metadata.insert("mpv:lyrics", MetadataValue::from("La la la"));
+    /// metadata.insert("mpv:cover-data", MetadataValue::from("..."));
+    ///
+    /// let slim = metadata.clone_with_rest_keys(&["mpv:lyrics"]);
+    /// assert_eq!(slim.title(), Some("Song"));
+    /// assert!(slim.get("mpv:lyrics").is_some());
+    /// assert!(slim.get("mpv:cover-data").is_none());
+    /// ```
+    pub fn clone_with_rest_keys(&self, keys: &[&str]) -> Metadata {
+        self.iter()
+            .filter(|(key, _)| validation::KNOWN_KEYS.contains(key) || keys.contains(key))
+            .map(|(key, value)| (key.to_owned(), value.clone()))
+            .collect::<HashMap<_, _>>()
+            .into()
+    }
+
     /// Returns [`true`] if there is no metadata
     pub fn is_empty(&self) -> bool {
         self.values.is_empty()
     }
+
+    fn non_empty_str_array(&self, key: &str) -> Option<Vec<&str>> {
+        self.get(key)
+            .and_then(Value::as_str_array)
+            .filter(|values| !values.is_empty())
+    }
+}
+
+/// Read a number that should be an `i32`, but that some players send as another numeric type or
+/// as a string.
+fn lenient_i32(value: &Value) -> Option<i32> {
+    match value {
+        Value::String(val) => val.trim().parse().ok(),
+        other => other.coerce_integer(),
+    }
+}
+
+/// The file name of a URL's path, percent-decoded and without its extension.
+fn url_file_stem(url: &str) -> Option<String> {
+    let url = url.split(|c| c == '?' || c == '#').next().unwrap_or(url);
+    let path = match url.find("://") {
+        // Skip the host, which is not a file name even if there is no path after it.
+        Some(index) => &url[index + 3..][url[index + 3..].find('/')?..],
+        None => url,
+    };
+
+    let name = path.rsplit('/').next()?;
+    let name = location::percent_decode(name).unwrap_or_else(|| name.to_owned());
+    let stem = match name.rfind('.') {
+        Some(index) if index > 0 => &name[..index],
+        _ => name.as_str(),
+    };
+
+    if stem.trim().is_empty() {
+        None
+    } else {
+        Some(stem.to_owned())
+    }
 }
 
 impl IntoIterator for Metadata {
@@ -184,7 +529,7 @@ impl IntoIterator for Metadata {
     type IntoIter = std::collections::hash_map::IntoIter<String, Value>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.values.into_iter()
+        HashMap::from(self).into_iter()
     }
 }
 
@@ -193,13 +538,16 @@ impl IntoIterator for Metadata {
 #[allow(clippy::implicit_hasher)]
 impl From<Metadata> for HashMap<String, Value> {
     fn from(metadata: Metadata) -> Self {
-        metadata.values
+        // Only copy the values if some other clone is still sharing them.
+        Arc::try_unwrap(metadata.values).unwrap_or_else(|shared| (*shared).clone())
     }
 }
 
 impl From<HashMap<String, Value>> for Metadata {
     fn from(values: HashMap<String, Value>) -> Self {
-        Metadata { values }
+        Metadata {
+            values: Arc::new(values),
+        }
     }
 }
 
@@ -213,6 +561,24 @@ mod tests {
         assert_eq!(metadata.track_id(), Some(TrackID::new("/foo").unwrap()));
     }
 
+    #[test]
+    fn it_keeps_invalid_track_ids() {
+        let metadata = Metadata::new("spotify:track:xyz");
+        assert_eq!(metadata.track_id(), None);
+        assert_eq!(
+            metadata.get("mpris:trackid").and_then(Value::as_str),
+            Some("spotify:track:xyz")
+        );
+
+        let error = metadata.checked_track_id().unwrap().unwrap_err();
+        assert_eq!(error.value, "spotify:track:xyz");
+        assert_eq!(
+            Metadata::new("/1").checked_track_id(),
+            Some(Ok(TrackID::new("/1").unwrap()))
+        );
+        assert_eq!(Metadata::default().checked_track_id(), None);
+    }
+
     #[test]
     fn it_supports_blank_metadata() {
         let metadata = Metadata::from(HashMap::new());
@@ -249,6 +615,165 @@ mod tests {
         }
     }
 
+    fn metadata_with(values: Vec<(&str, Value)>) -> Metadata {
+        Metadata::from(
+            values
+                .into_iter()
+                .map(|(key, value)| (String::from(key), value))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn it_reads_track_and_disc_numbers_sent_as_strings() {
+        let metadata = metadata_with(vec![
+            ("xesam:trackNumber", Value::from("07")),
+            ("xesam:discNumber", Value::from(" 2 ")),
+        ]);
+
+        assert_eq!(metadata.track_number(), Some(7));
+        assert_eq!(metadata.disc_number(), Some(2));
+    }
+
+    #[test]
+    fn it_reads_track_and_disc_numbers_sent_as_unsigned() {
+        let metadata = metadata_with(vec![
+            ("xesam:trackNumber", Value::U32(12)),
+            ("xesam:discNumber", Value::U8(1)),
+        ]);
+
+        assert_eq!(metadata.track_number(), Some(12));
+        assert_eq!(metadata.disc_number(), Some(1));
+    }
+
+    #[test]
+    fn it_rejects_unparseable_track_numbers() {
+        let metadata = metadata_with(vec![
+            ("xesam:trackNumber", Value::from("7/12")),
+            ("xesam:discNumber", Value::U64(u64::MAX)),
+        ]);
+
+        assert_eq!(metadata.track_number(), None);
+        assert_eq!(metadata.disc_number(), None);
+    }
+
+    #[test]
+    fn it_reads_lengths_of_any_numeric_type() {
+        for value in &[
+            Value::I64(1_000),
+            Value::U64(1_000),
+            Value::I32(1_000),
+            Value::F64(1_000.0),
+        ] {
+            let metadata = metadata_with(vec![("mpris:length", value.clone())]);
+            assert_eq!(metadata.length_in_microseconds(), Some(1_000));
+        }
+
+        let metadata = metadata_with(vec![("mpris:length", Value::I64(-1))]);
+        assert_eq!(metadata.length_in_microseconds(), None);
+    }
+
+    #[test]
+    fn it_finds_keys_by_prefix_in_order() {
+        let metadata = metadata_with(vec![
+            ("mpv:b", Value::from(2)),
+            ("xesam:title", Value::from("Title")),
+            ("mpv:a", Value::from(1)),
+        ]);
+
+        let keys: Vec<&str> = metadata.keys_with_prefix("mpv:").map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["mpv:a", "mpv:b"]);
+    }
+
+    #[test]
+    fn it_groups_keys_by_namespace() {
+        let metadata = metadata_with(vec![
+            ("xesam:title", Value::from("Title")),
+            ("xesam:album", Value::from("Album")),
+            ("mpris:trackid", Value::from("/1")),
+            ("nonamespace", Value::from("value")),
+        ]);
+
+        let xesam: Vec<(&str, &Value)> = metadata.namespace("xesam").into_iter().collect();
+        assert_eq!(
+            xesam,
+            vec![
+                ("album", &Value::from("Album")),
+                ("title", &Value::from("Title"))
+            ]
+        );
+        assert!(metadata.namespace("nonamespace").is_empty());
+    }
+
+    #[test]
+    fn it_clones_with_selected_extension_keys() {
+        let metadata = metadata_with(vec![
+            ("mpris:trackid", Value::from("/1")),
+            ("xesam:title", Value::from("Title")),
+            ("xesam:asText", Value::from("Lyrics")),
+            ("mpv:rating", Value::from(5)),
+            ("mpv:cover-data", Value::from("...")),
+        ]);
+
+        let clone = metadata.clone_with_rest_keys(&["mpv:rating", "mpv:missing"]);
+        let mut keys: Vec<&str> = clone.keys().collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            vec!["mpris:trackid", "mpv:rating", "xesam:asText", "xesam:title"]
+        );
+
+        assert_eq!(metadata.clone_with_rest_keys(&[]).keys().count(), 3);
+    }
+
+    #[test]
+    fn it_returns_first_artist_and_joined_artists() {
+        let metadata = metadata_with(vec![(
+            "xesam:artist",
+            Value::from(vec![Value::from("Simon"), Value::from("Garfunkel")]),
+        )]);
+
+        assert_eq!(metadata.first_artist(), Some("Simon"));
+        assert_eq!(
+            metadata.artist_string(" & "),
+            Some(String::from("Simon & Garfunkel"))
+        );
+    }
+
+    #[test]
+    fn it_has_no_artist_string_for_empty_artists() {
+        let metadata = metadata_with(vec![("xesam:artist", Value::from(Vec::<Value>::new()))]);
+
+        assert_eq!(metadata.artists(), None);
+        assert_eq!(metadata.first_artist(), None);
+        assert_eq!(metadata.artist_string(", "), None);
+    }
+
+    #[test]
+    fn it_parses_urls() {
+        let metadata = metadata_with(vec![(
+            "xesam:url",
+            Value::from("file:///home/user/Music/01%20Intro.ogg"),
+        )]);
+
+        assert_eq!(
+            metadata.url_parsed(),
+            Some(MediaLocation::File("/home/user/Music/01 Intro.ogg".into()))
+        );
+    }
+
+    #[test]
+    fn it_shares_values_between_clones() {
+        let metadata = metadata_with(vec![("xesam:asText", Value::from("la la la"))]);
+        let clone = metadata.clone();
+
+        assert!(Arc::ptr_eq(&metadata.values, &clone.values));
+
+        // Converting one of them back into a map must not affect the other.
+        let map: HashMap<String, Value> = clone.into();
+        assert_eq!(map.get("xesam:asText"), metadata.get("xesam:asText"));
+    }
+
     #[test]
     fn from_hashmap_artist_string() {
         use std::iter::FromIterator;
@@ -274,4 +799,138 @@ mod tests {
 
         assert_eq!(metadata.artists(), Some(vec!["Agnes Obel"]));
     }
+
+    #[test]
+    fn it_falls_back_through_display_titles() {
+        let metadata = metadata_with(vec![
+            ("xesam:title", Value::from("Title")),
+            ("xesam:url", Value::from("file:///music/Other.flac")),
+        ]);
+        assert_eq!(metadata.display_title(), "Title");
+
+        let metadata = metadata_with(vec![
+            ("xesam:title", Value::from("  ")),
+            (
+                "xesam:url",
+                Value::from("file:///music/My%20Song.remix.flac?x=1"),
+            ),
+            ("mpris:trackid", Value::from("/org/example/Track/5")),
+        ]);
+        assert_eq!(metadata.display_title(), "My Song.remix");
+
+        let metadata = metadata_with(vec![
+            ("xesam:url", Value::from("https://radio.example.com")),
+            ("mpris:trackid", Value::from("/org/example/Track/5")),
+        ]);
+        assert_eq!(metadata.display_title(), "5");
+
+        let metadata = Metadata::new("/org/mpris/MediaPlayer2/TrackList/NoTrack");
+        assert_eq!(metadata.display_title(), "Unknown");
+        assert_eq!(Metadata::default().display_title(), "Unknown");
+    }
+
+    #[test]
+    fn it_finds_url_file_stems() {
+        assert_eq!(url_file_stem("/music/a.mp3"), Some(String::from("a")));
+        assert_eq!(url_file_stem("https://example.com/"), None);
+        assert_eq!(url_file_stem("https://example.com"), None);
+        assert_eq!(
+            url_file_stem("https://x.org/live#a.b"),
+            Some(String::from("live"))
+        );
+        assert_eq!(
+            url_file_stem("file:///.hidden"),
+            Some(String::from(".hidden"))
+        );
+    }
+
+    #[test]
+    fn it_falls_back_to_album_artists_for_display() {
+        let metadata = metadata_with(vec![
+            (
+                "xesam:artist",
+                Value::from(vec![Value::from("A"), Value::from("B")]),
+            ),
+            ("xesam:albumArtist", Value::from(vec![Value::from("C")])),
+        ]);
+        assert_eq!(metadata.display_artists(), Some(String::from("A, B")));
+
+        let metadata = metadata_with(vec![
+            ("xesam:artist", Value::from(vec![Value::from("")])),
+            ("xesam:albumArtist", Value::from(vec![Value::from("C")])),
+        ]);
+        assert_eq!(metadata.display_artists(), Some(String::from("C")));
+
+        assert_eq!(Metadata::default().display_artists(), None);
+    }
+
+    #[test]
+    fn it_describes_what_is_playing() {
+        let artist = ("xesam:artist", Value::from(vec![Value::from("Agnes Obel")]));
+        let title = ("xesam:title", Value::from("Riverside"));
+
+        let both = metadata_with(vec![artist.clone(), title.clone()]);
+        assert_eq!(both.now_playing(), "Agnes Obel — Riverside");
+        assert_eq!(metadata_with(vec![title]).now_playing(), "Riverside");
+        assert_eq!(metadata_with(vec![artist]).now_playing(), "Agnes Obel");
+        assert_eq!(Metadata::default().now_playing(), "Unknown");
+
+        assert_eq!(
+            both.display_with("{artist}: {title} [{album}] {year} {title"),
+            "Agnes Obel: Riverside [] {year} {title"
+        );
+    }
+
+    #[test]
+    fn it_reads_comments_and_album_artists() {
+        let metadata = metadata_with(vec![
+            ("xesam:comment", Value::from("Live")),
+            (
+                "xesam:albumArtist",
+                Value::from(vec![Value::from("A"), Value::from("B")]),
+            ),
+        ]);
+        assert_eq!(metadata.comments(), Some(vec!["Live"]));
+        assert_eq!(metadata.album_artists(), Some(vec!["A", "B"]));
+        assert_eq!(
+            metadata.album_artists_joined(" & "),
+            Some(String::from("A & B"))
+        );
+
+        let metadata = metadata_with(vec![
+            ("xesam:comment", Value::from(Vec::<Value>::new())),
+            ("xesam:albumArtist", Value::from(Vec::<Value>::new())),
+        ]);
+        assert_eq!(metadata.comments(), None);
+        assert_eq!(metadata.album_artists(), None);
+        assert_eq!(metadata.album_artists_joined(", "), None);
+    }
+
+    #[test]
+    fn it_separates_art_files_from_embedded_art() {
+        let metadata = metadata_with(vec![("mpris:artUrl", Value::from("file:///tmp/cover.jpg"))]);
+        assert_eq!(metadata.art_path(), Some(PathBuf::from("/tmp/cover.jpg")));
+        assert_eq!(metadata.embedded_art(), None);
+
+        let metadata = metadata_with(vec![(
+            "mpris:artUrl",
+            Value::from("data:image/png;base64,AAE="),
+        )]);
+        assert_eq!(metadata.art_path(), None);
+        assert_eq!(metadata.embedded_art(), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn it_copies_values_on_write() {
+        let mut metadata = Metadata::new("/1");
+        let clone = metadata.clone();
+
+        assert_eq!(metadata.insert("xesam:title", Value::from("Title")), None);
+        assert_eq!(metadata.title(), Some("Title"));
+        assert_eq!(clone.title(), None);
+
+        assert_eq!(metadata.remove("mpris:trackid"), Some(Value::from("/1")));
+        assert_eq!(metadata.remove("mpris:trackid"), None);
+        assert_eq!(clone.get("mpris:trackid"), Some(&Value::from("/1")));
+    }
 }
@@ -0,0 +1,140 @@
+use serde_json::{Map, Number, Value as Json};
+use std::collections::HashMap;
+
+use super::{Metadata, Value};
+
+/// Conversion between [`Metadata`] and JSON.
+///
+/// The mapping is stable:
+///
+/// * Keys are kept exactly as the player sent them, like `xesam:title`.
+/// * Strings, booleans, arrays and maps are mapped to their JSON counterparts.
+/// * Integers (like the `mpris:length` in microseconds) are mapped to JSON integers.
+/// * Floats are mapped to JSON numbers, except for NaN and infinite values which become `null`.
+/// * Unsupported values become `null`.
+impl Metadata {
+    /// Convert the metadata to a JSON object.
+    ///
+    /// See the list above for how values are mapped.
+    pub fn to_json(&self) -> Json {
+        Json::Object(
+            self.iter()
+                .map(|(key, value)| (key.to_owned(), value_to_json(value)))
+                .collect(),
+        )
+    }
+
+    /// Build metadata from a JSON object, like one produced by [`to_json`](Self::to_json).
+    ///
+    /// Fields are all optional. Keys with `null` values are skipped, and input that is not a JSON
+    /// object results in empty metadata.
+    ///
+    /// Integers that fit in an `i64` become [`Value::I64`], larger ones become [`Value::U64`],
+    /// and everything else becomes [`Value::F64`].
+    pub fn from_json(json: Json) -> Self {
+        match json {
+            Json::Object(object) => Metadata::from(object_to_values(object)),
+            _ => Metadata::default(),
+        }
+    }
+}
+
+fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::String(val) => Json::String(val.clone()),
+        Value::I16(val) => Json::from(*val),
+        Value::I32(val) => Json::from(*val),
+        Value::I64(val) => Json::from(*val),
+        Value::U8(val) => Json::from(*val),
+        Value::U16(val) => Json::from(*val),
+        Value::U32(val) => Json::from(*val),
+        Value::U64(val) => Json::from(*val),
+        Value::F64(val) => Number::from_f64(*val).map_or(Json::Null, Json::Number),
+        Value::Bool(val) => Json::Bool(*val),
+        Value::Array(values) => Json::Array(values.iter().map(value_to_json).collect()),
+        Value::Map(values) => Json::Object(
+            values
+                .iter()
+                .map(|(key, value)| (key.clone(), value_to_json(value)))
+                .collect(),
+        ),
+        Value::Unsupported => Json::Null,
+    }
+}
+
+fn object_to_values(object: Map<String, Json>) -> HashMap<String, Value> {
+    object
+        .into_iter()
+        .filter_map(|(key, json)| json_to_value(json).map(|value| (key, value)))
+        .collect()
+}
+
+fn json_to_value(json: Json) -> Option<Value> {
+    match json {
+        Json::Null => None,
+        Json::Bool(val) => Some(Value::Bool(val)),
+        Json::Number(number) => number
+            .as_i64()
+            .map(Value::I64)
+            .or_else(|| number.as_u64().map(Value::U64))
+            .or_else(|| number.as_f64().map(Value::F64)),
+        Json::String(val) => Some(Value::String(val)),
+        Json::Array(values) => Some(Value::Array(
+            values.into_iter().filter_map(json_to_value).collect(),
+        )),
+        Json::Object(object) => Some(Value::Map(object_to_values(object))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_converts_metadata_to_json() {
+        let mut values = HashMap::new();
+        values.insert(String::from("mpris:trackid"), Value::from("/1"));
+        values.insert(String::from("mpris:length"), Value::I64(215_000_000));
+        values.insert(
+            String::from("xesam:artist"),
+            Value::from(vec![Value::from("Artist")]),
+        );
+        values.insert(String::from("xesam:userRating"), Value::F64(0.5));
+        values.insert(String::from("x:unsupported"), Value::Unsupported);
+
+        assert_eq!(
+            Metadata::from(values).to_json(),
+            json!({
+                "mpris:trackid": "/1",
+                "mpris:length": 215_000_000,
+                "xesam:artist": ["Artist"],
+                "xesam:userRating": 0.5,
+                "x:unsupported": null,
+            })
+        );
+    }
+
+    #[test]
+    fn it_round_trips_through_json() {
+        let json = json!({
+            "mpris:trackid": "/1",
+            "mpris:length": 215_000_000u64,
+            "xesam:title": "Title",
+            "x:nested": {"a": [1, 2.5, true]},
+        });
+
+        let metadata = Metadata::from_json(json.clone());
+        assert_eq!(metadata.length_in_microseconds(), Some(215_000_000));
+        assert_eq!(metadata.title(), Some("Title"));
+        assert_eq!(metadata.to_json(), json);
+    }
+
+    #[test]
+    fn it_tolerates_missing_and_invalid_fields() {
+        let metadata = Metadata::from_json(json!({"xesam:title": null}));
+        assert!(metadata.is_empty());
+
+        assert!(Metadata::from_json(json!([1, 2, 3])).is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+use std::path::PathBuf;
+
+/// A parsed media location, as given in `xesam:url` or `mpris:artUrl`.
+///
+/// Local files are normalized to a [`PathBuf`]. For `file://` URIs the prefix is removed and
+/// percent-encoding is decoded; bare paths are kept as they are. Anything else is kept as the
+/// original URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaLocation<'a> {
+    /// The location is a file on the local machine.
+    File(PathBuf),
+
+    /// The location is some other URI, like `https://…`.
+    Uri(&'a str),
+}
+
+impl<'a> MediaLocation<'a> {
+    /// Parse a location string from the metadata.
+    ///
+    /// Strings without a scheme that start with `/` are treated as local paths, and are not
+    /// percent-decoded as they are not URIs. The empty string and `file://` URIs pointing at
+    /// another host result in [`None`].
+    pub(crate) fn parse(location: &'a str) -> Option<Self> {
+        if location.is_empty() {
+            return None;
+        }
+
+        if location.starts_with('/') {
+            return Some(MediaLocation::File(location.into()));
+        }
+
+        match strip_prefix_ignore_case(location, "file://") {
+            Some(rest) => {
+                // The host part is usually empty, but "localhost" is also allowed.
+                let path = strip_prefix_ignore_case(rest, "localhost").unwrap_or(rest);
+                if path.starts_with('/') {
+                    percent_decode(path).map(|path| MediaLocation::File(path.into()))
+                } else {
+                    None
+                }
+            }
+            None => Some(MediaLocation::Uri(location)),
+        }
+    }
+
+    /// Returns the local file path, if this is a [`File`](Self::File) location.
+    pub fn as_path(&self) -> Option<&std::path::Path> {
+        match self {
+            MediaLocation::File(path) => Some(path),
+            MediaLocation::Uri(_) => None,
+        }
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(string: &'a str, prefix: &str) -> Option<&'a str> {
+    if string.len() >= prefix.len()
+        && string.is_char_boundary(prefix.len())
+        && string[..prefix.len()].eq_ignore_ascii_case(prefix)
+    {
+        Some(&string[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// Decode `%XX` escapes in a string. Returns [`None`] if the escapes are malformed or if the
+/// decoded bytes are not valid UTF-8.
+pub(crate) fn percent_decode(string: &str) -> Option<String> {
+    if !string.contains('%') {
+        return Some(string.to_owned());
+    }
+
+    let bytes = string.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = bytes.get(index + 1..index + 3)?;
+            // from_str_radix accepts a leading sign, so "%+1" would decode without this check.
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            let hex = std::str::from_utf8(hex).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+/// Decode the payload of a base64 `data:` URI, like `data:image/png;base64,iVBO...`. Returns
+/// [`None`] for other URIs, for `data:` URIs that are not base64-encoded and for malformed
+/// base64.
+pub(crate) fn decode_base64_data_uri(uri: &str) -> Option<Vec<u8>> {
+    let rest = strip_prefix_ignore_case(uri, "data:")?;
+    let (parameters, data) = rest.split_once(',')?;
+    if !parameters
+        .rsplit(';')
+        .next()
+        .map_or(false, |encoding| encoding.eq_ignore_ascii_case("base64"))
+    {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(data.len() / 4 * 3);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for byte in data.bytes().filter(|byte| !byte.is_ascii_whitespace()) {
+        if byte == b'=' {
+            break;
+        }
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    // A single leftover character can not encode a full byte.
+    if bits >= 6 {
+        return None;
+    }
+
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_file_uris() {
+        assert_eq!(
+            MediaLocation::parse("file:///home/user/Music/My%20Song.flac"),
+            Some(MediaLocation::File("/home/user/Music/My Song.flac".into()))
+        );
+        assert_eq!(
+            MediaLocation::parse("file://localhost/tmp/a.mp3"),
+            Some(MediaLocation::File("/tmp/a.mp3".into()))
+        );
+        assert_eq!(
+            MediaLocation::parse("FILE:///tmp/a.mp3"),
+            Some(MediaLocation::File("/tmp/a.mp3".into()))
+        );
+    }
+
+    #[test]
+    fn it_treats_absolute_paths_as_files() {
+        assert_eq!(
+            MediaLocation::parse("/tmp/a%2Fb.mp3"),
+            Some(MediaLocation::File("/tmp/a%2Fb.mp3".into()))
+        );
+        assert_eq!(
+            MediaLocation::parse("/music/100%.mp3"),
+            Some(MediaLocation::File("/music/100%.mp3".into()))
+        );
+    }
+
+    #[test]
+    fn it_keeps_other_uris() {
+        assert_eq!(
+            MediaLocation::parse("https://example.com/a.mp3"),
+            Some(MediaLocation::Uri("https://example.com/a.mp3"))
+        );
+    }
+
+    #[test]
+    fn it_rejects_remote_and_malformed_files() {
+        assert_eq!(MediaLocation::parse(""), None);
+        assert_eq!(MediaLocation::parse("file://otherhost/a.mp3"), None);
+        assert_eq!(MediaLocation::parse("file:///bad%zzescape"), None);
+        assert_eq!(MediaLocation::parse("file:///truncated%2"), None);
+        assert_eq!(MediaLocation::parse("file:///signed%+1escape"), None);
+        assert_eq!(percent_decode("%+1"), None);
+        assert_eq!(percent_decode("%-1"), None);
+    }
+
+    #[test]
+    fn it_parses_locations_sent_by_real_players() {
+        // Spotify
+        assert_eq!(
+            MediaLocation::parse(
+                "https://i.scdn.co/image/ab67616d0000b273e8b066f70c206551210d902b"
+            ),
+            Some(MediaLocation::Uri(
+                "https://i.scdn.co/image/ab67616d0000b273e8b066f70c206551210d902b"
+            ))
+        );
+        // VLC
+        assert_eq!(
+            MediaLocation::parse(
+                "file:///home/user/Music/Artist%20Name/01%20-%20Song%20%28Live%29.flac"
+            ),
+            Some(MediaLocation::File(
+                "/home/user/Music/Artist Name/01 - Song (Live).flac".into()
+            ))
+        );
+        // Firefox and Chromium write the art of web pages to temporary files
+        assert_eq!(
+            MediaLocation::parse(
+                "file:///tmp/firefox-mpris/cbf6e0c0f2a3a4d5e6f7a8b9c0d1e2f3_1234.png"
+            ),
+            Some(MediaLocation::File(
+                "/tmp/firefox-mpris/cbf6e0c0f2a3a4d5e6f7a8b9c0d1e2f3_1234.png".into()
+            ))
+        );
+        // mpv and Rhythmbox encode non-ASCII characters as UTF-8
+        assert_eq!(
+            MediaLocation::parse("file:///home/user/Music/Beyonc%C3%A9/Caf%C3%A9.mp3"),
+            Some(MediaLocation::File(
+                "/home/user/Music/Beyonc\u{e9}/Caf\u{e9}.mp3".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn it_decodes_base64_data_uris() {
+        assert_eq!(
+            decode_base64_data_uri("data:image/png;base64,aGVsbG8="),
+            Some(b"hello".to_vec())
+        );
+        assert_eq!(
+            decode_base64_data_uri("DATA:;BASE64,aGV sbG8"),
+            Some(b"hello".to_vec())
+        );
+        assert_eq!(decode_base64_data_uri("data:;base64,"), Some(Vec::new()));
+    }
+
+    #[test]
+    fn it_rejects_other_data_uris() {
+        assert_eq!(decode_base64_data_uri("https://example.com/a.png"), None);
+        assert_eq!(decode_base64_data_uri("data:text/plain,hello"), None);
+        assert_eq!(decode_base64_data_uri("data:image/png;base64"), None);
+        assert_eq!(decode_base64_data_uri("data:;base64,a*bc"), None);
+        assert_eq!(decode_base64_data_uri("data:;base64,abcde"), None);
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+
+use super::validation::LIST_KEYS;
+use super::{Metadata, Value};
+
+/// Fixes up [`Metadata`] from a player before it is handed out.
+///
+/// Register a normalizer on a [`Player`](crate::Player) with
+/// [`Player::set_metadata_normalizer`](crate::Player::set_metadata_normalizer). It is then
+/// applied to all metadata read from that player, including the metadata in
+/// [`Event::TrackChanged`](crate::Event::TrackChanged), [`Progress`](crate::Progress) and the
+/// [`TrackList`](crate::TrackList).
+///
+/// Closures taking the player's identity and the metadata also implement this trait.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mpris::{Metadata, MetadataNormalizer, MetadataValue};
+/// // Load album art through a local proxy.
+/// let normalizer = |_identity: &str, metadata: &mut Metadata| {
+///     if let Some(url) = metadata.art_url().map(String::from) {
+///         metadata.insert("mpris:artUrl", MetadataValue::from(format!("http://localhost:8080/?{}", url)));
+///     }
+/// };
+///
+/// let mut metadata = Metadata::new("/1");
+/// metadata.insert("mpris:artUrl", MetadataValue::from("https://example.com/a.png"));
+/// normalizer.normalize("Example Player", &mut metadata);
+/// assert_eq!(metadata.art_url(), Some("http://localhost:8080/?https://example.com/a.png"));
+/// ```
+pub trait MetadataNormalizer {
+    /// Change `metadata` in place. `player_identity` is the player's
+    /// [`identity`](crate::Player::identity), for normalizers that only apply to some players.
+    fn normalize(&self, player_identity: &str, metadata: &mut Metadata);
+}
+
+impl<F> MetadataNormalizer for F
+where
+    F: Fn(&str, &mut Metadata),
+{
+    fn normalize(&self, player_identity: &str, metadata: &mut Metadata) {
+        self(player_identity, metadata)
+    }
+}
+
+/// A [`MetadataNormalizer`] with fixups for known problems in popular players.
+///
+/// * Values that should be lists of strings (like `xesam:artist`) but were sent as a single
+///   string are turned into lists.
+/// * Spotify's `open.spotify.com/image/` album art URLs, which do not load, are rewritten to
+///   `i.scdn.co/image/`.
+///
+/// This is not applied unless registered with
+/// [`Player::set_metadata_normalizer`](crate::Player::set_metadata_normalizer).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultNormalizer;
+
+const SPOTIFY_BROKEN_ART_PREFIX: &str = "https://open.spotify.com/image/";
+const SPOTIFY_ART_PREFIX: &str = "https://i.scdn.co/image/";
+
+impl MetadataNormalizer for DefaultNormalizer {
+    fn normalize(&self, player_identity: &str, metadata: &mut Metadata) {
+        for key in LIST_KEYS {
+            if let Some(Value::String(value)) = metadata.get(key) {
+                let list = Value::Array(vec![Value::String(value.clone())]);
+                metadata.insert(*key, list);
+            }
+        }
+
+        if player_identity.eq_ignore_ascii_case("spotify") {
+            let fixed_url = metadata.art_url().and_then(|url| {
+                url.strip_prefix(SPOTIFY_BROKEN_ART_PREFIX)
+                    .map(|id| format!("{}{}", SPOTIFY_ART_PREFIX, id))
+            });
+            if let Some(url) = fixed_url {
+                metadata.insert("mpris:artUrl", Value::String(url));
+            }
+        }
+    }
+}
+
+/// An optional, registered [`MetadataNormalizer`]. Normalizers don't have to implement
+/// [`Debug`](fmt::Debug), so this only shows if one is registered.
+#[derive(Default)]
+pub(crate) struct NormalizerSlot(Option<Box<dyn MetadataNormalizer>>);
+
+impl NormalizerSlot {
+    pub(crate) fn set(&mut self, normalizer: Option<Box<dyn MetadataNormalizer>>) {
+        self.0 = normalizer;
+    }
+
+    pub(crate) fn apply(&self, player_identity: &str, metadata: &mut Metadata) {
+        if let Some(ref normalizer) = self.0 {
+            normalizer.normalize(player_identity, metadata);
+        }
+    }
+}
+
+impl fmt::Debug for NormalizerSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Some(MetadataNormalizer)"),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_turns_bare_strings_into_lists() {
+        let mut metadata = Metadata::new("/1");
+        metadata.insert("xesam:artist", Value::from("Artist"));
+
+        DefaultNormalizer.normalize("Some Player", &mut metadata);
+        assert_eq!(
+            metadata.get("xesam:artist"),
+            Some(&Value::Array(vec![Value::from("Artist")]))
+        );
+    }
+
+    #[test]
+    fn it_fixes_spotify_art_urls() {
+        let mut metadata = Metadata::new("/1");
+        metadata.insert(
+            "mpris:artUrl",
+            Value::from("https://open.spotify.com/image/ab67616d"),
+        );
+
+        let mut other = metadata.clone();
+        DefaultNormalizer.normalize("Other", &mut other);
+        assert_eq!(
+            other.art_url(),
+            Some("https://open.spotify.com/image/ab67616d")
+        );
+
+        DefaultNormalizer.normalize("Spotify", &mut metadata);
+        assert_eq!(metadata.art_url(), Some("https://i.scdn.co/image/ab67616d"));
+    }
+
+    #[test]
+    fn it_applies_registered_normalizers() {
+        let mut slot = NormalizerSlot::default();
+        let mut metadata = Metadata::new("/1");
+        slot.apply("Player", &mut metadata);
+        assert_eq!(metadata.title(), None);
+
+        slot.set(Some(Box::new(|identity: &str, metadata: &mut Metadata| {
+            metadata.insert("xesam:title", Value::from(identity));
+        })));
+        slot.apply("Player", &mut metadata);
+        assert_eq!(metadata.title(), Some("Player"));
+    }
+}
@@ -0,0 +1,175 @@
+use super::{Metadata, Value};
+
+/// A track rating, normalized to the `0.0` to `1.0` range. See [`Metadata::rating`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    value: f64,
+    source: RatingSource,
+    raw: f64,
+}
+
+/// The metadata key that a [`Rating`] was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RatingSource {
+    /// `xesam:userRating`, a rating given by the user.
+    User,
+
+    /// `xesam:autoRating`, a rating calculated by the player.
+    Auto,
+}
+
+impl Rating {
+    /// The rating, in the range `0.0` to `1.0`.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The key that the rating was read from.
+    pub fn source(&self) -> RatingSource {
+        self.source
+    }
+
+    /// The value as it was sent by the player, before it was normalized.
+    pub fn raw(&self) -> f64 {
+        self.raw
+    }
+
+    /// Normalizes a raw value, guessing its scale unless `strict` is set.
+    fn from_raw(raw: f64, source: RatingSource, strict: bool) -> Option<Self> {
+        let value = if (0.0..=1.0).contains(&raw) {
+            raw
+        } else if strict {
+            return None;
+        } else if raw > 1.0 && raw <= 5.0 {
+            raw / 5.0
+        } else if raw > 5.0 && raw <= 100.0 {
+            raw / 100.0
+        } else {
+            return None;
+        };
+
+        Some(Rating { value, source, raw })
+    }
+}
+
+impl RatingSource {
+    fn key(self) -> &'static str {
+        match self {
+            RatingSource::User => "xesam:userRating",
+            RatingSource::Auto => "xesam:autoRating",
+        }
+    }
+}
+
+impl Metadata {
+    /// The rating of the track, normalized to the `0.0` to `1.0` range.
+    ///
+    /// `xesam:userRating` is used if it is present and usable, otherwise `xesam:autoRating`. The
+    /// specification says that both should be in the `0.0` to `1.0` range, but some players use
+    /// other scales. Those are normalized with these rules:
+    ///
+    /// * Values from `0.0` to `1.0` are used as they are.
+    /// * Values above `1.0`, up to `5.0`, are treated as a number of stars out of 5.
+    /// * Values above `5.0`, up to `100.0`, are treated as a percentage.
+    /// * Other values (like negative numbers) are ignored.
+    ///
+    /// Use [`rating_strict`](Self::rating_strict) to ignore everything outside of the
+    /// specification instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::{Metadata, MetadataValue};
+    /// # use std::collections::HashMap;
+    /// let mut values = HashMap::new();
+    /// values.insert(String::from("xesam:userRating"), MetadataValue::I32(4));
+    /// let metadata = Metadata::from(values);
+    ///
+    /// assert_eq!(metadata.rating().map(|rating| rating.value()), Some(0.8));
+    /// assert_eq!(metadata.rating_strict(), None);
+    /// ```
+    pub fn rating(&self) -> Option<Rating> {
+        self.find_rating(false)
+    }
+
+    /// Like [`rating`](Self::rating), but only accepts values in the `0.0` to `1.0` range, as the
+    /// specification says.
+    pub fn rating_strict(&self) -> Option<Rating> {
+        self.find_rating(true)
+    }
+
+    fn find_rating(&self, strict: bool) -> Option<Rating> {
+        [RatingSource::User, RatingSource::Auto]
+            .iter()
+            .find_map(|&source| {
+                self.get(source.key())
+                    .and_then(Value::coerce_f64)
+                    .and_then(|raw| Rating::from_raw(raw, source, strict))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn metadata_with(values: Vec<(&str, Value)>) -> Metadata {
+        Metadata::from(
+            values
+                .into_iter()
+                .map(|(key, value)| (String::from(key), value))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn it_normalizes_other_scales() {
+        let cases = [
+            (Value::F64(0.5), Some(0.5)),
+            (Value::I32(1), Some(1.0)),
+            (Value::F64(2.5), Some(0.5)),
+            (Value::U8(80), Some(0.8)),
+            (Value::F64(101.0), None),
+            (Value::F64(-0.5), None),
+        ];
+        for (raw, expected) in cases.iter() {
+            let metadata = metadata_with(vec![("xesam:userRating", raw.clone())]);
+            assert_eq!(
+                metadata.rating().map(|rating| rating.value()),
+                *expected,
+                "for {:?}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn it_refuses_to_guess_in_strict_mode() {
+        let metadata = metadata_with(vec![("xesam:userRating", Value::F64(3.0))]);
+        assert_eq!(metadata.rating_strict(), None);
+
+        let metadata = metadata_with(vec![("xesam:userRating", Value::F64(0.3))]);
+        assert_eq!(
+            metadata.rating_strict().map(|rating| rating.value()),
+            Some(0.3)
+        );
+    }
+
+    #[test]
+    fn it_prefers_user_ratings() {
+        let metadata = metadata_with(vec![
+            ("xesam:userRating", Value::F64(3.0)),
+            ("xesam:autoRating", Value::F64(0.2)),
+        ]);
+
+        let rating = metadata.rating().unwrap();
+        assert_eq!(rating.source(), RatingSource::User);
+        assert_eq!(rating.raw(), 3.0);
+        assert_eq!(rating.value(), 0.6);
+
+        let rating = metadata.rating_strict().unwrap();
+        assert_eq!(rating.source(), RatingSource::Auto);
+        assert_eq!(rating.value(), 0.2);
+    }
+}
@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+
+use super::Metadata;
+
+/// A key to sort tracks like a music library does, by album artist, album, disc and track. See
+/// [`Metadata::sort_key`].
+///
+/// Keys are compared field by field, in this order:
+///
+/// 1. The album artist, without regard to case.
+/// 2. The album name, without regard to case.
+/// 3. The disc number.
+/// 4. The track number.
+/// 5. The [display title](Metadata::display_title), without regard to case, so that tracks
+///    without any of the above still have a stable order.
+///
+/// Missing names and track numbers sort after present ones, so that tracks with incomplete
+/// metadata end up at the end of each group.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MetadataSortKey {
+    album_artist: MissingLast<String>,
+    album: MissingLast<String>,
+    disc_number: i32,
+    track_number: MissingLast<i32>,
+    title: String,
+}
+
+/// An optional value that sorts after all present values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct MissingLast<T>(Option<T>);
+
+impl<T: Ord> PartialOrd for MissingLast<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for MissingLast<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.0, &other.0) {
+            (Some(this), Some(other)) => this.cmp(other),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl Metadata {
+    /// A key to sort tracks in a canonical order, like a music library shows them.
+    ///
+    /// When fields are missing:
+    ///
+    /// * Without [`album_artists`](Self::album_artists), the track's [`artists`](Self::artists)
+    ///   are used instead.
+    /// * Without a [`disc_number`](Self::disc_number), the track is treated as being on disc 1.
+    /// * Missing album artists, albums and track numbers sort last.
+    ///
+    /// See [`MetadataSortKey`] for the full order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::Metadata;
+    /// # fn history() -> Vec<Metadata> { Vec::new() }
+    /// let mut tracks: Vec<Metadata> = history();
+    /// tracks.sort_by_cached_key(Metadata::sort_key);
+    /// ```
+    pub fn sort_key(&self) -> MetadataSortKey {
+        MetadataSortKey {
+            album_artist: MissingLast(
+                sort_name(self.album_artists()).or_else(|| sort_name(self.artists())),
+            ),
+            album: MissingLast(sort_name(self.album_name().map(|album| vec![album]))),
+            disc_number: self.disc_number().unwrap_or(1),
+            track_number: MissingLast(self.track_number()),
+            title: self.display_title().to_lowercase(),
+        }
+    }
+}
+
+/// Joins the names that are not blank, in lowercase. Returns [`None`] if there are none.
+fn sort_name(names: Option<Vec<&str>>) -> Option<String> {
+    let names: Vec<&str> = names?
+        .into_iter()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    if names.is_empty() {
+        None
+    } else {
+        Some(names.join(", ").to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataValue;
+    use std::collections::HashMap;
+
+    fn track(values: Vec<(&str, MetadataValue)>) -> Metadata {
+        values
+            .into_iter()
+            .map(|(key, value)| (String::from(key), value))
+            .collect::<HashMap<_, _>>()
+            .into()
+    }
+
+    fn album_track(artist: &str, album: &str, disc: i32, number: i32) -> Metadata {
+        track(vec![
+            ("xesam:albumArtist", MetadataValue::from(artist)),
+            ("xesam:album", MetadataValue::from(album)),
+            ("xesam:discNumber", MetadataValue::from(disc)),
+            ("xesam:trackNumber", MetadataValue::from(number)),
+        ])
+    }
+
+    #[test]
+    fn it_sorts_by_album_artist_album_disc_and_track() {
+        let mut tracks = [
+            album_track("Beta", "First", 1, 1),
+            album_track("alpha", "Second", 1, 1),
+            album_track("Alpha", "First", 2, 1),
+            album_track("Alpha", "First", 1, 10),
+            album_track("Alpha", "First", 1, 2),
+        ];
+        tracks.sort_by_cached_key(Metadata::sort_key);
+
+        let order: Vec<_> = tracks
+            .iter()
+            .map(|track| {
+                (
+                    track.album_name().unwrap(),
+                    track.disc_number().unwrap(),
+                    track.track_number().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("First", 1, 2),
+                ("First", 1, 10),
+                ("First", 2, 1),
+                ("Second", 1, 1),
+                ("First", 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_falls_back_when_fields_are_missing() {
+        let with_artist = track(vec![
+            ("xesam:artist", MetadataValue::from("Alpha")),
+            ("xesam:album", MetadataValue::from("First")),
+            ("xesam:trackNumber", MetadataValue::from(3)),
+        ]);
+        assert!(with_artist.sort_key() > album_track("Alpha", "First", 1, 2).sort_key());
+        assert!(with_artist.sort_key() < album_track("Alpha", "First", 2, 1).sort_key());
+
+        let without_track_number = track(vec![
+            ("xesam:albumArtist", MetadataValue::from("Alpha")),
+            ("xesam:album", MetadataValue::from("First")),
+        ]);
+        assert!(without_track_number.sort_key() > with_artist.sort_key());
+
+        let untagged = track(vec![("xesam:title", MetadataValue::from("Anything"))]);
+        assert!(untagged.sort_key() > album_track("Zeta", "Last", 9, 99).sort_key());
+    }
+}
@@ -0,0 +1,455 @@
+use super::{Metadata, Value};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A point in time read from an ISO 8601 date string in [`Metadata`].
+///
+/// Players are free to send dates with as much or as little precision as they like, so only the
+/// year is always present. A date-only string like `2007-04-29` has no time of day, and a string
+/// without a `Z` or `+02:00` suffix has no UTC offset.
+///
+/// See [`Timestamp::parse`] for the accepted formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timestamp {
+    year: i32,
+    month: Option<u8>,
+    day: Option<u8>,
+    time: Option<TimeOfDay>,
+    utc_offset_minutes: Option<i16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TimeOfDay {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+}
+
+impl Timestamp {
+    /// Parses an ISO 8601 date, or date and time.
+    ///
+    /// These forms are accepted, where the time can be separated by either `T` or a space:
+    ///
+    /// * `2007`, `2007-04` and `2007-04-29`
+    /// * `2007-04-29T14:35`, `2007-04-29T14:35:51` and `2007-04-29T14:35:51.123`
+    /// * Any of the times above followed by `Z`, `+02`, `+0200` or `+02:00`
+    ///
+    /// Returns [`None`] for anything else, including dates that do not exist (like `2007-02-30`).
+    pub fn parse(string: &str) -> Option<Self> {
+        let string = string.trim();
+        let (date, time) = match string.find(|c| c == 'T' || c == ' ') {
+            Some(index) => (&string[..index], Some(&string[index + 1..])),
+            None => (string, None),
+        };
+
+        let mut parts = date.split('-');
+        let year = parse_digits(parts.next()?, 4)? as i32;
+        let month = match parts.next() {
+            Some(part) => Some(parse_digits(part, 2)?),
+            None => None,
+        };
+        let day = match parts.next() {
+            Some(part) => Some(parse_digits(part, 2)?),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+
+        if let Some(month) = month {
+            if !(1..=12).contains(&month) {
+                return None;
+            }
+            if let Some(day) = day {
+                if day < 1 || day > days_in_month(year, month) {
+                    return None;
+                }
+            }
+        }
+
+        let (time, utc_offset_minutes) = match time {
+            Some(time) => {
+                // A time of day without a full date is not meaningful.
+                day?;
+                let (time, offset) = parse_time(time)?;
+                (Some(time), offset)
+            }
+            None => (None, None),
+        };
+
+        Some(Timestamp {
+            year,
+            month: month.map(|month| month as u8),
+            day: day.map(|day| day as u8),
+            time,
+            utc_offset_minutes,
+        })
+    }
+
+    /// The year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// The month, from 1 to 12, if the string had one.
+    pub fn month(&self) -> Option<u8> {
+        self.month
+    }
+
+    /// The day of the month, starting at 1, if the string had one.
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+
+    /// Returns `true` if the string had a time of day and not only a date.
+    pub fn has_time(&self) -> bool {
+        self.time.is_some()
+    }
+
+    /// The hour, from 0 to 23, if the string had a time of day.
+    pub fn hour(&self) -> Option<u8> {
+        self.time.map(|time| time.hour)
+    }
+
+    /// The minute, from 0 to 59, if the string had a time of day.
+    pub fn minute(&self) -> Option<u8> {
+        self.time.map(|time| time.minute)
+    }
+
+    /// The second, from 0 to 59, if the string had a time of day. Times without seconds use 0.
+    pub fn second(&self) -> Option<u8> {
+        self.time.map(|time| time.second)
+    }
+
+    /// The fraction of the second, in nanoseconds, if the string had a time of day.
+    pub fn nanosecond(&self) -> Option<u32> {
+        self.time.map(|time| time.nanosecond)
+    }
+
+    /// The offset from UTC in minutes, if the string had one. `Z` is an offset of 0.
+    pub fn utc_offset_minutes(&self) -> Option<i16> {
+        self.utc_offset_minutes
+    }
+
+    /// Converts the timestamp to a [`SystemTime`].
+    ///
+    /// Missing parts are filled in with their earliest value: a missing month or day is the first
+    /// one, a missing time of day is midnight and a missing UTC offset is treated as UTC.
+    pub fn to_system_time(&self) -> SystemTime {
+        let days = days_from_civil(self.year, self.month.unwrap_or(1), self.day.unwrap_or(1));
+        let time = self.time.unwrap_or(TimeOfDay {
+            hour: 0,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+        });
+        let seconds = days * 86_400
+            + i64::from(time.hour) * 3600
+            + i64::from(time.minute) * 60
+            + i64::from(time.second)
+            - i64::from(self.utc_offset_minutes.unwrap_or(0)) * 60;
+
+        if seconds >= 0 {
+            UNIX_EPOCH + Duration::new(seconds as u64, time.nanosecond)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+                + Duration::new(0, time.nanosecond)
+        }
+    }
+}
+
+/// Formats the timestamp as ISO 8601, with the same precision it was parsed with.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "-{:02}", day)?;
+        }
+        if let Some(time) = self.time {
+            write!(f, "T{:02}:{:02}:{:02}", time.hour, time.minute, time.second)?;
+            if time.nanosecond > 0 {
+                let fraction = format!("{:09}", time.nanosecond);
+                write!(f, ".{}", fraction.trim_end_matches('0'))?;
+            }
+        }
+        match self.utc_offset_minutes {
+            Some(0) => write!(f, "Z"),
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let offset = offset.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, offset / 60, offset % 60)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Metadata {
+    /// When the track was created, usually the recording date.
+    ///
+    /// Based on `xesam:contentCreated`
+    /// > When the track was created. Usually only the year component will be useful.
+    ///
+    /// Returns [`None`] if the value is missing or can not be parsed. See [`Timestamp::parse`]
+    /// for the accepted formats.
+    pub fn content_created(&self) -> Option<Timestamp> {
+        self.timestamp("xesam:contentCreated")
+    }
+
+    /// When the track was first played.
+    ///
+    /// Based on `xesam:firstUsed`
+    /// > When the track was first played.
+    pub fn first_used(&self) -> Option<Timestamp> {
+        self.timestamp("xesam:firstUsed")
+    }
+
+    /// When the track was last played.
+    ///
+    /// Based on `xesam:lastUsed`
+    /// > When the track was last played.
+    pub fn last_used(&self) -> Option<Timestamp> {
+        self.timestamp("xesam:lastUsed")
+    }
+
+    /// Parses the value of any key as a [`Timestamp`], for date keys that are not part of the
+    /// specification (like `xesam:lastPlayed`).
+    pub fn timestamp(&self, key: &str) -> Option<Timestamp> {
+        self.get(key)
+            .and_then(Value::as_str)
+            .and_then(Timestamp::parse)
+    }
+}
+
+/// Parses a string of exactly `length` ASCII digits.
+fn parse_digits(string: &str, length: usize) -> Option<u32> {
+    if string.len() == length && string.bytes().all(|b| b.is_ascii_digit()) {
+        string.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn parse_time(string: &str) -> Option<(TimeOfDay, Option<i16>)> {
+    let (time, offset) = match string.find(|c| c == 'Z' || c == '+' || c == '-') {
+        Some(index) => (&string[..index], Some(&string[index..])),
+        None => (string, None),
+    };
+
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) => (time, Some(fraction)),
+        None => (time, None),
+    };
+
+    let mut parts = time.split(':');
+    let hour = parse_digits(parts.next()?, 2)?;
+    let minute = parse_digits(parts.next()?, 2)?;
+    let second = match parts.next() {
+        Some(part) => Some(parse_digits(part, 2)?),
+        None => None,
+    };
+    if parts.next().is_some() || hour > 23 || minute > 59 || second.map_or(false, |s| s > 59) {
+        return None;
+    }
+    // Fractions only make sense after seconds.
+    if fraction.is_some() && second.is_none() {
+        return None;
+    }
+
+    let nanosecond = match fraction {
+        Some(fraction) => parse_fraction(fraction)?,
+        None => 0,
+    };
+
+    let offset = match offset {
+        Some(offset) => Some(parse_offset(offset)?),
+        None => None,
+    };
+
+    Some((
+        TimeOfDay {
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second.unwrap_or(0) as u8,
+            nanosecond,
+        },
+        offset,
+    ))
+}
+
+fn parse_fraction(fraction: &str) -> Option<u32> {
+    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // Digits after nanosecond precision are dropped.
+    let digits = &fraction[..fraction.len().min(9)];
+    let value: u32 = digits.parse().ok()?;
+    Some(value * 10u32.pow(9 - digits.len() as u32))
+}
+
+fn parse_offset(offset: &str) -> Option<i16> {
+    if offset == "Z" {
+        return Some(0);
+    }
+
+    let sign = match offset.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let offset = &offset[1..];
+    let (hours, minutes) = match offset.len() {
+        2 => (offset, "00"),
+        4 => (&offset[..2], &offset[2..]),
+        5 if offset.as_bytes()[2] == b':' => (&offset[..2], &offset[3..]),
+        _ => return None,
+    };
+    let hours = parse_digits(hours, 2)?;
+    let minutes = parse_digits(minutes, 2)?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    Some(sign * (hours * 60 + minutes) as i16)
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar.
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - if month <= 2 { 1 } else { 0 };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds_since_epoch(timestamp: &Timestamp) -> u64 {
+        timestamp
+            .to_system_time()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn it_parses_full_timestamps() {
+        let timestamp = Timestamp::parse("2007-04-29T14:35:51.5+02:00").unwrap();
+        assert_eq!(timestamp.year(), 2007);
+        assert_eq!(timestamp.month(), Some(4));
+        assert_eq!(timestamp.day(), Some(29));
+        assert_eq!(timestamp.hour(), Some(14));
+        assert_eq!(timestamp.minute(), Some(35));
+        assert_eq!(timestamp.second(), Some(51));
+        assert_eq!(timestamp.nanosecond(), Some(500_000_000));
+        assert_eq!(timestamp.utc_offset_minutes(), Some(120));
+        assert_eq!(seconds_since_epoch(&timestamp), 1_177_850_151);
+    }
+
+    #[test]
+    fn it_parses_partial_timestamps() {
+        let year = Timestamp::parse("2007").unwrap();
+        assert_eq!(
+            (year.month(), year.day(), year.has_time()),
+            (None, None, false)
+        );
+        assert_eq!(seconds_since_epoch(&year), 1_167_609_600);
+
+        let date = Timestamp::parse("2007-04-29").unwrap();
+        assert_eq!(date.day(), Some(29));
+        assert!(!date.has_time());
+        assert_eq!(date.utc_offset_minutes(), None);
+
+        let local = Timestamp::parse("2007-04-29 14:35").unwrap();
+        assert_eq!(local.second(), Some(0));
+        assert_eq!(local.utc_offset_minutes(), None);
+
+        let utc = Timestamp::parse("2007-04-29T14:35:51Z").unwrap();
+        assert_eq!(utc.utc_offset_minutes(), Some(0));
+
+        let negative = Timestamp::parse("2007-04-29T14:35:51-0330").unwrap();
+        assert_eq!(negative.utc_offset_minutes(), Some(-210));
+    }
+
+    #[test]
+    fn it_rejects_invalid_timestamps() {
+        for string in &[
+            "",
+            "yesterday",
+            "07-04-29",
+            "2007-13-01",
+            "2007-02-29",
+            "2007-04-29T25:00",
+            "2007-04T14:35",
+            "2007-04-29T14:35.5",
+            "2007-04-29T14:35:51+2",
+            "2007-04-29-01",
+        ] {
+            assert_eq!(Timestamp::parse(string), None, "{:?}", string);
+        }
+        assert!(Timestamp::parse("2008-02-29").is_some());
+    }
+
+    #[test]
+    fn it_handles_dates_before_the_epoch() {
+        let timestamp = Timestamp::parse("1969-12-31T23:59:59Z").unwrap();
+        assert_eq!(
+            UNIX_EPOCH
+                .duration_since(timestamp.to_system_time())
+                .unwrap(),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn it_formats_with_the_parsed_precision() {
+        for string in &[
+            "2007",
+            "2007-04",
+            "2007-04-29",
+            "2007-04-29T14:35:51",
+            "2007-04-29T14:35:51.25Z",
+            "2007-04-29T14:35:51-03:30",
+        ] {
+            assert_eq!(Timestamp::parse(string).unwrap().to_string(), *string);
+        }
+    }
+
+    #[test]
+    fn it_reads_timestamps_from_metadata() {
+        let mut metadata = Metadata::new("/track");
+        metadata.insert("xesam:contentCreated", Value::from("2007-04-29"));
+        metadata.insert("xesam:lastUsed", Value::from("not a date"));
+        metadata.insert("xesam:lastPlayed", Value::from("2020-01-02T03:04:05Z"));
+
+        assert_eq!(metadata.content_created().unwrap().year(), 2007);
+        assert_eq!(metadata.last_used(), None);
+        assert_eq!(metadata.first_used(), None);
+        assert_eq!(
+            metadata.timestamp("xesam:lastPlayed").unwrap().hour(),
+            Some(3)
+        );
+    }
+}
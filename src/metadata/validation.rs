@@ -0,0 +1,335 @@
+use super::{Metadata, Value};
+use crate::TrackID;
+
+/// Metadata keys defined by the MPRIS2 metadata guidelines.
+pub(crate) const KNOWN_KEYS: &[&str] = &[
+    "mpris:trackid",
+    "mpris:length",
+    "mpris:artUrl",
+    "xesam:album",
+    "xesam:albumArtist",
+    "xesam:artist",
+    "xesam:asText",
+    "xesam:audioBPM",
+    "xesam:autoRating",
+    "xesam:comment",
+    "xesam:composer",
+    "xesam:contentCreated",
+    "xesam:discNumber",
+    "xesam:firstUsed",
+    "xesam:genre",
+    "xesam:lastUsed",
+    "xesam:lyricist",
+    "xesam:title",
+    "xesam:trackNumber",
+    "xesam:url",
+    "xesam:useCount",
+    "xesam:userRating",
+];
+
+/// Metadata keys that should contain a list of strings.
+pub(super) const LIST_KEYS: &[&str] = &[
+    "xesam:albumArtist",
+    "xesam:artist",
+    "xesam:comment",
+    "xesam:composer",
+    "xesam:genre",
+    "xesam:lyricist",
+];
+
+/// Metadata keys that should contain an `i32`.
+const INTEGER_KEYS: &[&str] = &[
+    "xesam:audioBPM",
+    "xesam:discNumber",
+    "xesam:trackNumber",
+    "xesam:useCount",
+];
+
+/// A problem found by [`Metadata::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataIssue {
+    /// The metadata key that the issue is about.
+    pub key: String,
+
+    /// What kind of issue this is.
+    pub kind: MetadataIssueKind,
+
+    /// A human-readable description of the issue.
+    pub message: String,
+}
+
+/// The kind of a [`MetadataIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetadataIssueKind {
+    /// `mpris:trackid` is missing. The specification requires it.
+    MissingTrackId,
+
+    /// `mpris:trackid` is not a valid D-Bus object path.
+    InvalidTrackId,
+
+    /// A value that should be a list of strings was sent as a single string. This crate treats it
+    /// as a list with one item.
+    BareStringInsteadOfList,
+
+    /// A value has a different type than the specification says. This crate still reads it if it
+    /// can be converted without loss.
+    UnexpectedType,
+
+    /// `mpris:length` is zero or negative.
+    NonPositiveLength,
+
+    /// `mpris:artUrl` or `xesam:url` is not a valid URI.
+    InvalidUri,
+
+    /// `xesam:userRating` or `xesam:autoRating` is outside the `0.0` to `1.0` range.
+    RatingOutOfRange,
+
+    /// The key differs from a known key only by case, like `xesam:Title`. It is most likely a
+    /// typo, and will not be read by the typed accessors.
+    MisspelledKey,
+}
+
+impl MetadataIssue {
+    fn new<S: Into<String>>(key: &str, kind: MetadataIssueKind, message: S) -> Self {
+        MetadataIssue {
+            key: key.to_owned(),
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl Metadata {
+    /// Checks the metadata against the MPRIS2 specification and metadata guidelines, and returns
+    /// everything that the player got wrong.
+    ///
+    /// Many of these issues are handled leniently by this crate (for example, a bare string is
+    /// accepted where a list of strings is expected), but they could still break other clients.
+    /// Issues are sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::Metadata;
+    /// let metadata = Metadata::new("/org/example/Track/1");
+    /// for issue in metadata.validate() {
+    ///     println!("{}: {}", issue.key, issue.message);
+    /// }
+    /// ```
+    pub fn validate(&self) -> Vec<MetadataIssue> {
+        use self::MetadataIssueKind::*;
+
+        let mut issues = Vec::new();
+
+        match self.get("mpris:trackid") {
+            None => issues.push(MetadataIssue::new(
+                "mpris:trackid",
+                MissingTrackId,
+                "mpris:trackid is missing",
+            )),
+            Some(Value::String(id)) => {
+                if let Err(error) = id.parse::<TrackID>() {
+                    issues.push(MetadataIssue::new(
+                        "mpris:trackid",
+                        InvalidTrackId,
+                        format!(
+                            "{:?} is not a valid D-Bus object path: {}",
+                            id, error.reason
+                        ),
+                    ));
+                }
+            }
+            Some(other) => issues.push(MetadataIssue::new(
+                "mpris:trackid",
+                InvalidTrackId,
+                format!("expected an object path, but got {:?}", other),
+            )),
+        }
+
+        for (key, value) in self.iter() {
+            if LIST_KEYS.contains(&key) {
+                match value {
+                    Value::String(_) => issues.push(MetadataIssue::new(
+                        key,
+                        BareStringInsteadOfList,
+                        format!("{} should be a list of strings, not a single string", key),
+                    )),
+                    Value::Array(items) if items.iter().all(Value::is_string) => {}
+                    other => issues.push(MetadataIssue::new(
+                        key,
+                        UnexpectedType,
+                        format!("{} should be a list of strings, but got {:?}", key, other),
+                    )),
+                }
+            } else if INTEGER_KEYS.contains(&key) && !value.is_i32() {
+                issues.push(MetadataIssue::new(
+                    key,
+                    UnexpectedType,
+                    format!("{} should be a 32-bit integer, but got {:?}", key, value),
+                ));
+            } else if !KNOWN_KEYS.contains(&key) {
+                if let Some(known) = KNOWN_KEYS
+                    .iter()
+                    .find(|known| known.eq_ignore_ascii_case(key))
+                {
+                    issues.push(MetadataIssue::new(
+                        key,
+                        MisspelledKey,
+                        format!("{} looks like a misspelling of {}", key, known),
+                    ));
+                }
+            }
+        }
+
+        if let Some(value) = self.get("mpris:length") {
+            match value.coerce_integer::<i128>() {
+                Some(length) if length > 0 => {}
+                Some(length) => issues.push(MetadataIssue::new(
+                    "mpris:length",
+                    NonPositiveLength,
+                    format!("mpris:length should be positive, but was {}", length),
+                )),
+                None => issues.push(MetadataIssue::new(
+                    "mpris:length",
+                    UnexpectedType,
+                    format!("mpris:length should be an integer, but got {:?}", value),
+                )),
+            }
+        }
+
+        for key in &["mpris:artUrl", "xesam:url"] {
+            match self.get(key) {
+                Some(Value::String(uri)) if is_valid_uri(uri) => {}
+                Some(other) => issues.push(MetadataIssue::new(
+                    key,
+                    InvalidUri,
+                    format!("{} should be a valid URI, but got {:?}", key, other),
+                )),
+                None => {}
+            }
+        }
+
+        for key in &["xesam:userRating", "xesam:autoRating"] {
+            if let Some(value) = self.get(key) {
+                match value.coerce_f64() {
+                    Some(rating) if (0.0..=1.0).contains(&rating) => {}
+                    Some(rating) => issues.push(MetadataIssue::new(
+                        key,
+                        RatingOutOfRange,
+                        format!("{} should be between 0.0 and 1.0, but was {}", key, rating),
+                    )),
+                    None => issues.push(MetadataIssue::new(
+                        key,
+                        UnexpectedType,
+                        format!("{} should be a float, but got {:?}", key, value),
+                    )),
+                }
+            }
+        }
+
+        issues.sort_by(|a, b| a.key.cmp(&b.key));
+        issues
+    }
+}
+
+/// Checks that the string starts with a URI scheme and does not contain whitespace.
+fn is_valid_uri(uri: &str) -> bool {
+    let scheme = match uri.split_once(':') {
+        Some((scheme, _)) => scheme,
+        None => return false,
+    };
+
+    let mut scheme_chars = scheme.chars();
+    let valid_scheme = scheme_chars
+        .next()
+        .map_or(false, |first| first.is_ascii_alphabetic())
+        && scheme_chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+
+    valid_scheme && !uri.chars().any(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn kinds(values: Vec<(&str, Value)>) -> Vec<(String, MetadataIssueKind)> {
+        let metadata = Metadata::from(
+            values
+                .into_iter()
+                .map(|(key, value)| (String::from(key), value))
+                .collect::<HashMap<_, _>>(),
+        );
+        metadata
+            .validate()
+            .into_iter()
+            .map(|issue| (issue.key, issue.kind))
+            .collect()
+    }
+
+    #[test]
+    fn it_accepts_valid_metadata() {
+        let issues = kinds(vec![
+            ("mpris:trackid", Value::from("/org/example/1")),
+            ("mpris:length", Value::I64(1_000)),
+            ("mpris:artUrl", Value::from("https://example.com/art.png")),
+            ("xesam:artist", Value::from(vec![Value::from("Artist")])),
+            ("xesam:trackNumber", Value::I32(1)),
+            ("xesam:userRating", Value::F64(1.0)),
+        ]);
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn it_reports_track_id_problems() {
+        assert_eq!(
+            kinds(vec![]),
+            vec![(
+                String::from("mpris:trackid"),
+                MetadataIssueKind::MissingTrackId
+            )]
+        );
+        assert_eq!(
+            kinds(vec![("mpris:trackid", Value::from("spotify:track:xyz"))]),
+            vec![(
+                String::from("mpris:trackid"),
+                MetadataIssueKind::InvalidTrackId
+            )]
+        );
+    }
+
+    #[test]
+    fn it_reports_sloppy_values() {
+        use self::MetadataIssueKind::*;
+
+        let issues = kinds(vec![
+            ("mpris:trackid", Value::from("/1")),
+            ("mpris:length", Value::I64(0)),
+            ("mpris:artUrl", Value::from("not a uri")),
+            ("xesam:artist", Value::from("Artist")),
+            ("xesam:trackNumber", Value::from("07")),
+            ("xesam:userRating", Value::F64(4.0)),
+            ("xesam:Title", Value::from("Title")),
+        ]);
+        assert_eq!(
+            issues,
+            vec![
+                (String::from("mpris:artUrl"), InvalidUri),
+                (String::from("mpris:length"), NonPositiveLength),
+                (String::from("xesam:Title"), MisspelledKey),
+                (String::from("xesam:artist"), BareStringInsteadOfList),
+                (String::from("xesam:trackNumber"), UnexpectedType),
+                (String::from("xesam:userRating"), RatingOutOfRange),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_ignores_unknown_keys() {
+        let issues = kinds(vec![
+            ("mpris:trackid", Value::from("/1")),
+            ("mpv:extension", Value::from("value")),
+        ]);
+        assert_eq!(issues, vec![]);
+    }
+}
@@ -1,7 +1,8 @@
-use dbus::arg::ArgType;
+use dbus::arg::{ArgType, RefArg, Variant};
 use enum_kinds::EnumKind;
 use from_variants::FromVariants;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
 /// Holds a dynamically-typed metadata value.
 ///
@@ -330,6 +331,61 @@ impl Value {
     }
 }
 
+impl Value {
+    /// Returns any numeric value as an integer of type `T`, as long as the conversion does not
+    /// lose information.
+    ///
+    /// * All integer values are converted when they fit in `T`.
+    /// * Floats are only converted when they have no fractional part.
+    /// * Values that are out of range for `T` result in `None` instead of wrapping.
+    ///
+    /// Different players send the same fields using different numeric types, so all numeric
+    /// accessors on [`Metadata`](crate::Metadata) use this to read values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::MetadataValue;
+    /// assert_eq!(MetadataValue::I32(42).coerce_integer::<u64>(), Some(42));
+    /// assert_eq!(MetadataValue::F64(42.0).coerce_integer::<u8>(), Some(42));
+    /// assert_eq!(MetadataValue::F64(4.2).coerce_integer::<u8>(), None);
+    /// assert_eq!(MetadataValue::I32(-1).coerce_integer::<u64>(), None);
+    /// ```
+    pub fn coerce_integer<T: TryFrom<i128>>(&self) -> Option<T> {
+        let integer = match *self {
+            Value::I16(val) => i128::from(val),
+            Value::I32(val) => i128::from(val),
+            Value::I64(val) => i128::from(val),
+            Value::U8(val) => i128::from(val),
+            Value::U16(val) => i128::from(val),
+            Value::U32(val) => i128::from(val),
+            Value::U64(val) => i128::from(val),
+            // Largest float magnitude that certainly fits in an i128. Every float this large is
+            // integral, so nothing is lost for values below it.
+            Value::F64(val) if val.fract() == 0.0 && val.abs() < 1e38 => val as i128,
+            _ => return None,
+        };
+        T::try_from(integer).ok()
+    }
+
+    /// Returns any numeric value as an `f64`, as long as the conversion does not lose
+    /// information.
+    ///
+    /// Integers are only converted when they can be represented exactly as an `f64` (that is, when
+    /// their magnitude is at most 2<sup>53</sup>).
+    pub fn coerce_f64(&self) -> Option<f64> {
+        const MAX_EXACT: i128 = 1 << 53;
+
+        match *self {
+            Value::F64(val) => Some(val),
+            _ => self
+                .coerce_integer::<i128>()
+                .filter(|val| val.abs() <= MAX_EXACT)
+                .map(|val| val as f64),
+        }
+    }
+}
+
 impl Value {
     /// Returns true if `self` is a String
     pub fn is_string(&self) -> bool {
@@ -397,6 +453,38 @@ impl Value {
     }
 }
 
+impl Value {
+    /// Converts the value so it can be appended to a D-Bus message as its own type. Arrays are
+    /// converted to arrays of variants and maps to maps of variants.
+    ///
+    /// Returns [`None`] for [`Value::Unsupported`], which can not be sent.
+    pub(crate) fn to_ref_arg(&self) -> Option<Box<dyn RefArg>> {
+        let variant = |value: &Value| value.to_ref_arg().map(Variant);
+
+        Some(match self {
+            Value::String(value) => Box::new(value.clone()),
+            Value::I16(value) => Box::new(*value),
+            Value::I32(value) => Box::new(*value),
+            Value::I64(value) => Box::new(*value),
+            Value::U8(value) => Box::new(*value),
+            Value::U16(value) => Box::new(*value),
+            Value::U32(value) => Box::new(*value),
+            Value::U64(value) => Box::new(*value),
+            Value::F64(value) => Box::new(*value),
+            Value::Bool(value) => Box::new(*value),
+            Value::Array(values) => {
+                Box::new(values.iter().map(variant).collect::<Option<Vec<_>>>()?)
+            }
+            Value::Map(map) => Box::new(
+                map.iter()
+                    .map(|(key, value)| variant(value).map(|value| (key.clone(), value)))
+                    .collect::<Option<HashMap<_, _>>>()?,
+            ),
+            Value::Unsupported => return None,
+        })
+    }
+}
+
 impl<'a> From<&'a str> for Value {
     fn from(string: &'a str) -> Value {
         Value::String(String::from(string))
@@ -450,7 +538,7 @@ impl<'a> dbus::arg::Get<'a> for Value {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dbus::arg::{Append, RefArg, Variant};
+    use dbus::arg::Append;
     use dbus::ffidisp::{BusType, Connection, ConnectionItem};
     use dbus::Message;
 
@@ -493,6 +581,32 @@ mod tests {
         send_values_over_dbus(|message| message.append1(value))
     }
 
+    #[test]
+    fn it_coerces_integers_losslessly() {
+        assert_eq!(Value::U64(7).coerce_integer::<i32>(), Some(7));
+        assert_eq!(Value::I16(-7).coerce_integer::<i64>(), Some(-7));
+        assert_eq!(Value::F64(120.0).coerce_integer::<u64>(), Some(120));
+
+        assert_eq!(Value::U64(u64::MAX).coerce_integer::<i64>(), None);
+        assert_eq!(Value::I64(-1).coerce_integer::<u32>(), None);
+        assert_eq!(Value::F64(120.5).coerce_integer::<u64>(), None);
+        assert_eq!(Value::F64(f64::NAN).coerce_integer::<u64>(), None);
+        assert_eq!(Value::F64(f64::INFINITY).coerce_integer::<u64>(), None);
+        assert_eq!(Value::from("120").coerce_integer::<u64>(), None);
+    }
+
+    #[test]
+    fn it_coerces_floats_losslessly() {
+        assert_eq!(Value::F64(0.5).coerce_f64(), Some(0.5));
+        assert_eq!(Value::I32(1).coerce_f64(), Some(1.0));
+        assert_eq!(
+            Value::U64(1 << 53).coerce_f64(),
+            Some(9_007_199_254_740_992.0)
+        );
+        assert_eq!(Value::U64((1 << 53) + 1).coerce_f64(), None);
+        assert_eq!(Value::Bool(true).coerce_f64(), None);
+    }
+
     #[test]
     fn it_supports_strings() {
         let message = send_value_over_dbus("Hello world!");
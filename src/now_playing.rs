@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use crate::player::{activity_score, Player};
+use crate::progress::Progress;
+use crate::{DBusError, Metadata, PlaybackStatus};
+
+/// What a [`Player`] is playing, as returned by
+/// [`PlayerFinder::now_playing`](crate::PlayerFinder::now_playing) and
+/// [`PlayerFinder::now_playing_list`](crate::PlayerFinder::now_playing_list).
+///
+/// This is a snapshot taken with a single D-Bus call, with the [`Player`] to control it or to
+/// read more. The position keeps moving like [`Progress::position`] does, for as long as the
+/// snapshot is kept. As the snapshot is never refreshed, it does not notice seeks or pauses; read
+/// a new one (or use [`Player::track_progress`]) to follow the player over a longer time.
+#[derive(Debug)]
+pub struct NowPlaying {
+    player: Player,
+    progress: Progress,
+}
+
+impl NowPlaying {
+    /// Reads the state of the player.
+    pub(crate) fn read(player: Player) -> Result<Self, DBusError> {
+        let progress = Progress::from_player(&player)?;
+        Ok(NowPlaying { player, progress })
+    }
+
+    /// The player that is playing.
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    /// Returns the player, to keep it after the snapshot is no longer needed.
+    pub fn into_player(self) -> Player {
+        self.player
+    }
+
+    /// The whole state of the player, as it was read.
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    /// The current track. It is empty if the player has no track.
+    pub fn metadata(&self) -> &Metadata {
+        self.progress.metadata()
+    }
+
+    /// The playback status, as it was read.
+    pub fn playback_status(&self) -> PlaybackStatus {
+        self.progress.playback_status()
+    }
+
+    /// The position in the current track, calculated for the current instant. See
+    /// [`Progress::position`]. It keeps moving however old the snapshot is.
+    pub fn position(&self) -> Duration {
+        self.progress.position()
+    }
+
+    /// The [`Player::activity_score`] of the player, calculated from the snapshot.
+    pub(crate) fn activity_score(&self) -> i64 {
+        let has_track = !self.metadata().is_empty();
+        let can_control = self.progress.capabilities().can_control();
+        activity_score(self.playback_status(), has_track, can_control)
+    }
+}
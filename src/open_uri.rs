@@ -0,0 +1,97 @@
+/// The result of [`Player::open_uri_checked`](crate::Player::open_uri_checked).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenUriOutcome {
+    /// The URI was sent to the player.
+    Sent,
+
+    /// The player does not support the scheme of the URI, so it was not sent. Contains the
+    /// schemes that the player supports, as the player reported them.
+    SchemeUnsupported(Vec<String>),
+
+    /// The URI was sent to the player, but its file extension suggests a MIME type (which is
+    /// included) that the player does not list as supported. The player might still be able to
+    /// play it, as the type is only guessed.
+    MimeProbablyUnsupported(String),
+}
+
+/// Returns the scheme of a URI, like `https`, or `file` for URIs without a scheme, which are
+/// treated as file paths.
+pub(crate) fn scheme(uri: &str) -> &str {
+    // RFC 3986: ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ) ":"
+    let end = match uri.find(':') {
+        Some(end) => end,
+        None => return "file",
+    };
+    let candidate = &uri[..end];
+    let mut chars = candidate.chars();
+    let valid = chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+
+    if valid {
+        candidate
+    } else {
+        "file"
+    }
+}
+
+/// Guesses the MIME types of the media at `uri` from its file extension, with the most common
+/// name for the type first. Returns [`None`] for unknown extensions.
+pub(crate) fn probable_mime_types(uri: &str) -> Option<&'static [&'static str]> {
+    let path = uri.split(|c| c == '?' || c == '#').next().unwrap_or(uri);
+    let name = path.rsplit('/').next()?;
+    let extension = match name.rfind('.') {
+        Some(index) if index > 0 => name[index + 1..].to_ascii_lowercase(),
+        _ => return None,
+    };
+
+    let types: &'static [&'static str] = match extension.as_str() {
+        "mp3" => &["audio/mpeg", "audio/mp3", "audio/x-mp3"],
+        "ogg" | "oga" => &["audio/ogg", "audio/x-vorbis+ogg", "application/ogg"],
+        "opus" => &["audio/ogg", "audio/opus", "audio/x-opus+ogg"],
+        "flac" => &["audio/flac", "audio/x-flac"],
+        "wav" => &["audio/x-wav", "audio/wav", "audio/vnd.wave"],
+        "m4a" | "aac" => &["audio/mp4", "audio/aac", "audio/x-m4a"],
+        "wma" => &["audio/x-ms-wma"],
+        "mp4" | "m4v" => &["video/mp4"],
+        "mkv" => &["video/x-matroska"],
+        "webm" => &["video/webm", "audio/webm"],
+        "avi" => &["video/x-msvideo"],
+        "mov" => &["video/quicktime"],
+        "m3u" | "m3u8" => &["audio/x-mpegurl", "application/vnd.apple.mpegurl"],
+        "pls" => &["audio/x-scpls"],
+        _ => return None,
+    };
+    Some(types)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_schemes_and_treats_paths_as_files() {
+        assert_eq!(scheme("https://example.com/stream"), "https");
+        assert_eq!(scheme("HTTP://example.com"), "HTTP");
+        assert_eq!(scheme("spotify:track:123"), "spotify");
+        assert_eq!(scheme("svn+ssh://host/repo"), "svn+ssh");
+        assert_eq!(scheme("/music/song.ogg"), "file");
+        assert_eq!(scheme("music/a:b.ogg"), "file");
+        assert_eq!(scheme("1http://example.com"), "file");
+        assert_eq!(scheme(""), "file");
+    }
+
+    #[test]
+    fn it_guesses_mime_types_from_extensions() {
+        assert_eq!(
+            probable_mime_types("file:///music/Song.FLAC").map(|types| types[0]),
+            Some("audio/flac")
+        );
+        assert_eq!(
+            probable_mime_types("https://example.com/a.mp3?token=x.y").map(|types| types[0]),
+            Some("audio/mpeg")
+        );
+        assert_eq!(probable_mime_types("https://example.com/stream"), None);
+        assert_eq!(probable_mime_types("/music/.hidden"), None);
+        assert_eq!(probable_mime_types("/music/notes.txt"), None);
+    }
+}
@@ -1,19 +1,34 @@
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
 use std::ops::Range;
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use dbus::ffidisp::{ConnPath, Connection};
 use dbus::strings::{BusName, Path};
 
-use super::{DBusError, LoopStatus, MetadataValue, PlaybackStatus, TrackID, TrackList};
-use crate::event::PlayerEvents;
+use super::{
+    dbus_error_kind, DBusError, DBusErrorKind, LoopStatus, MetadataValue, NotSeekableReason,
+    PlaybackStatus, TrackID, TrackList,
+};
+use crate::capability_report::{CapabilityReport, PROBE_TIMEOUT_MS};
+use crate::category::{PlayerCategory, PlayerClassifier};
+use crate::controls::PlayerControls;
+use crate::decode::{self, DecodeWarning, Decoding, PropertyDecoder};
+use crate::event::{Event, EventError, EventState, PlayerEvents, TrackChanges};
 use crate::extensions::DurationExtensions;
 use crate::generated::OrgMprisMediaPlayer2;
 use crate::generated::OrgMprisMediaPlayer2Player;
-use crate::metadata::Metadata;
-use crate::pooled_connection::{MprisEvent, PooledConnection};
-use crate::progress::ProgressTracker;
+use crate::metadata::{Metadata, MetadataNormalizer, NormalizerSlot};
+use crate::open_uri::{self, OpenUriOutcome};
+use crate::pooled_connection::{PooledConnection, Subscription};
+use crate::progress::{Capabilities, ProgressTick, ProgressTracker, RootCapabilities};
+use crate::property_cache::{CachePolicy, PropertyCache};
+use crate::retry::{Idempotent, Member, RetryPolicy, RetryableError};
+use crate::seek::{self, SeekBounds, SeekMethod, SetPositionOutcome};
+use crate::trace::CallSpan;
+use crate::volume::Volume;
 
 pub(crate) const MPRIS2_PREFIX: &str = "org.mpris.MediaPlayer2.";
 pub(crate) const MPRIS2_PATH: &str = "/org/mpris/MediaPlayer2";
@@ -36,6 +51,12 @@ pub struct Player {
     identity: String,
     timeout_ms: i32,
     has_tracklist_interface: bool,
+    position_support: Cell<Option<bool>>,
+    dispatch_state: Option<(EventState, Subscription)>,
+    metadata_normalizer: NormalizerSlot,
+    property_cache: RefCell<PropertyCache>,
+    retry_policy: RetryPolicy,
+    decoding: Decoding,
 }
 
 impl Player {
@@ -50,6 +71,27 @@ impl Player {
         Player::for_pooled_connection(Rc::new(connection.into()), bus_name, timeout_ms)
     }
 
+    /// Create a new [`Player`] for a known bus name, like `org.mpris.MediaPlayer2.spotify`,
+    /// without looking through all players on the bus like [`PlayerFinder`](crate::PlayerFinder)
+    /// does.
+    ///
+    /// Useful to reconnect to a player whose bus name was saved earlier. Instance suffixes (like
+    /// `org.mpris.MediaPlayer2.vlc.instance1234`) are part of the bus name, so they have to be
+    /// included. The player uses the default D-Bus timeout; see
+    /// [`set_dbus_timeout_ms`](Self::set_dbus_timeout_ms).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player could not be queried. When no player owns `bus_name`, the
+    /// error has the [`DBusErrorKind::ServiceUnknown`] kind.
+    pub fn from_bus_name(connection: Connection, bus_name: &str) -> Result<Player, DBusError> {
+        Player::for_pooled_connection(
+            Rc::new(connection.into()),
+            bus_name.to_string(),
+            DEFAULT_TIMEOUT_MS,
+        )
+    }
+
     pub(crate) fn for_pooled_connection(
         pooled_connection: Rc<PooledConnection>,
         bus_name: String,
@@ -83,6 +125,12 @@ impl Player {
             identity,
             timeout_ms,
             has_tracklist_interface,
+            position_support: Cell::new(None),
+            dispatch_state: None,
+            metadata_normalizer: NormalizerSlot::default(),
+            property_cache: RefCell::new(PropertyCache::default()),
+            retry_policy: RetryPolicy::default(),
+            decoding: Decoding::default(),
         })
     }
 
@@ -101,6 +149,96 @@ impl Player {
         self.timeout_ms = timeout_ms;
     }
 
+    /// Returns how properties of this player are cached. See
+    /// [`set_cache_policy`](Self::set_cache_policy).
+    pub fn cache_policy(&self) -> CachePolicy {
+        self.property_cache.borrow().policy()
+    }
+
+    /// Choose how properties of the player are cached. This also forgets all cached values.
+    ///
+    /// With a cache, the capability getters (like [`can_seek`](Self::can_seek)) and the status
+    /// getters ([`get_playback_status`](Self::get_playback_status),
+    /// [`get_loop_status`](Self::get_loop_status), [`get_shuffle`](Self::get_shuffle),
+    /// [`get_volume`](Self::get_volume) and [`get_playback_rate`](Self::get_playback_rate)) only
+    /// read from the bus when the cached value is no longer valid. The position is never cached.
+    /// A cached value can be out of date for a moment after the player changes, until the
+    /// `PropertiesChanged` signal arrives or the value expires.
+    ///
+    /// The default is [`CachePolicy::Off`].
+    pub fn set_cache_policy(&mut self, policy: CachePolicy) {
+        let subscription = match policy {
+            CachePolicy::SignalInvalidated => Some(Subscription::watch_changes(
+                &self.connection,
+                &self.unique_name,
+            )),
+            _ => None,
+        };
+        self.property_cache = RefCell::new(PropertyCache::new(policy, subscription));
+    }
+
+    /// Returns how failed calls to the player are retried. See
+    /// [`set_retry_policy`](Self::set_retry_policy).
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Choose how calls to the player are repeated when they fail, for players that are briefly
+    /// too busy to reply.
+    ///
+    /// This applies to all property reads and method calls of this player, but not to waiting
+    /// for signals (like in [`PlayerEvents`] or [`ProgressTracker`]) or to
+    /// [`PlayerControls`]. Calls that do something different when made twice, like
+    /// [`next`](Self::next), [`seek`](Self::seek) and [`play_pause`](Self::play_pause), are only
+    /// repeated if [`RetryPolicy::retry_non_idempotent`] is set.
+    ///
+    /// All attempts of a call together take at most the D-Bus timeout (see
+    /// [`set_dbus_timeout_ms`](Self::set_dbus_timeout_ms)), including the waits between them. A
+    /// call that timed out therefore leaves no time to retry it; raise the timeout to give
+    /// retries room.
+    ///
+    /// The default is [`RetryPolicy::default`], which never retries.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Returns [`true`] if property values that do not follow the specification are errors. See
+    /// [`set_strict`](Self::set_strict).
+    pub fn is_strict(&self) -> bool {
+        self.decoding.strict
+    }
+
+    /// Choose whether property values that do not follow the specification are errors.
+    ///
+    /// By default, properties are decoded leniently, so that a single mistake of a player does
+    /// not make reading its whole state (like in [`PlayerEvents`] and [`ProgressTracker`]) fail:
+    ///
+    /// * `PlaybackStatus` and `LoopStatus` ignore case, and accept common aliases like `"play"`
+    ///   or `"off"`.
+    /// * `Volume`, `Rate` and `Position` accept any numeric type, and numbers sent as strings.
+    /// * `Shuffle` and the capabilities (like `CanSeek`) also accept numbers and the strings
+    ///   `"true"` and `"false"`.
+    ///
+    /// A value that still can not be decoded is replaced with a default, and recorded in
+    /// [`decode_warnings`](Self::decode_warnings). The defaults are `Stopped` for
+    /// `PlaybackStatus`, `None` for `LoopStatus`, `1.0` for `Volume` and `Rate`, `0` for
+    /// `Position`, and [`false`] for `Shuffle` and the capabilities.
+    ///
+    /// In strict mode these values are errors instead, which is useful to validate player
+    /// implementations. Status names are still read without regard to case, and numbers of other
+    /// numeric types without loss are still accepted.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.decoding.strict = strict;
+    }
+
+    /// Returns the property values that could not be decoded and were replaced with defaults,
+    /// oldest first. Each value of a property is only recorded once. Always empty in strict mode.
+    ///
+    /// See [`set_strict`](Self::set_strict).
+    pub fn decode_warnings(&self) -> Vec<DecodeWarning> {
+        self.decoding.warnings()
+    }
+
     /// Returns the player's D-Bus bus name.
     pub fn bus_name(&self) -> &str {
         &self.bus_name
@@ -134,6 +272,20 @@ impl Player {
         &self.identity
     }
 
+    /// Checks if the player is a web browser, or a proxy for a browser's media keys.
+    ///
+    /// This only looks at the player's identity, desktop entry and bus name. See
+    /// [`PlayerClassifier`] for the heuristics.
+    pub fn is_browser(&self) -> bool {
+        PlayerClassifier::new().lookup_player(self) == Some(PlayerCategory::Browser)
+    }
+
+    /// Guesses what kind of media the player plays, using the built-in table of well-known
+    /// players. Use a [`PlayerClassifier`] to extend the table.
+    pub fn category(&self) -> PlayerCategory {
+        PlayerClassifier::new().classify(self)
+    }
+
     /// Checks if the Player implements the `org.mpris.MediaPlayer2.TrackList` interface.
     pub fn supports_track_lists(&self) -> bool {
         self.has_tracklist_interface
@@ -145,7 +297,11 @@ impl Player {
     ///
     /// [desktop_entry]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:DesktopEntry
     pub fn get_desktop_entry(&self) -> Result<Option<String>, DBusError> {
-        handle_optional_property(self.connection_path().desktop_entry())
+        handle_optional_property(self.retrying(
+            Idempotent::Yes,
+            Member::get("DesktopEntry"),
+            |path| path.desktop_entry(),
+        ))
     }
 
     /// Returns the player's `SupportedMimeTypes` property.
@@ -154,9 +310,9 @@ impl Player {
     ///
     /// [mime_types]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:SupportedMimeTypes
     pub fn get_supported_mime_types(&self) -> Result<Vec<String>, DBusError> {
-        self.connection_path()
-            .supported_mime_types()
-            .map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::get("SupportedMimeTypes"), |path| {
+            path.supported_mime_types()
+        })
     }
 
     /// Returns the player's `SupportedUriSchemes` property.
@@ -165,9 +321,11 @@ impl Player {
     ///
     /// [schemes]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:SupportedUriSchemes
     pub fn get_supported_uri_schemes(&self) -> Result<Vec<String>, DBusError> {
-        self.connection_path()
-            .supported_uri_schemes()
-            .map_err(|e| e.into())
+        self.retrying(
+            Idempotent::Yes,
+            Member::get("SupportedUriSchemes"),
+            |path| path.supported_uri_schemes(),
+        )
     }
 
     /// Returns the player's `HasTrackList` property.
@@ -176,9 +334,9 @@ impl Player {
     ///
     /// [track_list]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:HasTrackList
     pub fn get_has_track_list(&self) -> Result<bool, DBusError> {
-        self.connection_path()
-            .has_track_list()
-            .map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::get("HasTrackList"), |path| {
+            path.has_track_list()
+        })
     }
 
     /// Returns the player's MPRIS `position` as a [`Duration`] since the start of the media.
@@ -201,11 +359,10 @@ impl Player {
 
     /// Returns the player's MPRIS `position` as a count of microseconds since the start of the
     /// media.
+    ///
+    /// Any numeric type sent by the player is accepted. Negative positions are treated as `0`.
     pub fn get_position_in_microseconds(&self) -> Result<u64, DBusError> {
-        self.connection_path()
-            .position()
-            .map(|p| p as u64)
-            .map_err(|e| e.into())
+        self.get_cached_property(&decode::POSITION)
     }
 
     /// Sets the position of the current track to the given position (as a [`Duration`]).
@@ -213,8 +370,8 @@ impl Player {
     /// Current [`TrackID`] must be provided to avoid race conditions with the player, in case it
     /// changes tracks while the signal is being sent.
     ///
-    /// **Note:** There is currently no good way to retrieve the current [`TrackID`] through the
-    /// `mpris` library. You will have to manually retrieve it through D-Bus until implemented.
+    /// Use [`set_current_position`](Self::set_current_position) to use the current track without
+    /// looking up its [`TrackID`] first.
     ///
     /// See: [MPRIS2 specification about `SetPosition`][set_position].
     ///
@@ -223,6 +380,46 @@ impl Player {
         self.set_position_in_microseconds(track_id, DurationExtensions::as_micros(position))
     }
 
+    /// Sets the position of the current track, reading its [`TrackID`] from the player's metadata.
+    ///
+    /// Returns [`DBusError::TrackNotSeekable`] if the current track has no valid track ID.
+    ///
+    /// If the player changes tracks between reading the ID and the `SetPosition` call, the call
+    /// is ignored by the player, as the specification requires. Use
+    /// [`set_current_position_verified`](Self::set_current_position_verified) to find out when
+    /// that happened.
+    ///
+    /// See: [MPRIS2 specification about `SetPosition`][set_position].
+    ///
+    /// [set_position]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:SetPosition
+    pub fn set_current_position(&self, position: Duration) -> Result<(), DBusError> {
+        let track_id = track_id_for_seeking(&self.get_metadata()?)?;
+        self.set_position(track_id, &position)
+    }
+
+    /// Like [`set_current_position`](Self::set_current_position), but reads the current track
+    /// again after the call to check that the position was set on the track it was meant for.
+    ///
+    /// Returns [`SetPositionOutcome::StalePosition`] if the track changed in between, in which
+    /// case the player ignored the call. This costs one more D-Bus call.
+    pub fn set_current_position_verified(
+        &self,
+        position: Duration,
+    ) -> Result<SetPositionOutcome, DBusError> {
+        let track_id = track_id_for_seeking(&self.get_metadata()?)?;
+        self.set_position(track_id.clone(), &position)?;
+
+        let current = self.get_metadata()?.checked_track_id().and_then(Result::ok);
+        if current.as_ref() == Some(&track_id) {
+            Ok(SetPositionOutcome::Sent)
+        } else {
+            Ok(SetPositionOutcome::StalePosition {
+                requested_track: track_id,
+                current_track: current,
+            })
+        }
+    }
+
     /// Set the "Position" setting of the player, if the player indicates that it supports the
     /// "Position" setting and can be controlled.
     ///
@@ -250,8 +447,8 @@ impl Player {
     /// Current [`TrackID`] must be provided to avoid race conditions with the player, in case it
     /// changes tracks while the signal is being sent.
     ///
-    /// **Note:** There is currently no good way to retrieve the current [`TrackID`] through the
-    /// `mpris` library. You will have to manually retrieve it through D-Bus until implemented.
+    /// Use [`set_current_position`](Self::set_current_position) to use the current track without
+    /// looking up its [`TrackID`] first.
     ///
     /// See: [MPRIS2 specification about `SetPosition`][set_position].
     ///
@@ -261,16 +458,117 @@ impl Player {
         track_id: TrackID,
         position_in_us: u64,
     ) -> Result<(), DBusError> {
-        self.connection_path()
-            .set_position(track_id.as_path(), position_in_us as i64)
-            .map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::call("SetPosition"), |path| {
+            path.set_position(track_id.as_path(), position_in_us as i64)
+        })
+        .map_err(|e| self.explain_rejection("seek", e))
+    }
+
+    /// Sets the position of the current track to a fraction of its length, like `0.3` to jump to
+    /// 30% of the track. This is what a click on a progress bar needs.
+    ///
+    /// `fraction` is clamped to `0.0..=1.0`. The current [`TrackID`] and length are read from the
+    /// player's metadata. Returns [`DBusError::TrackNotSeekable`] if either of them is unknown,
+    /// like for live streams, and an [`Err`] if `fraction` is not a number.
+    ///
+    /// See: [MPRIS2 specification about `SetPosition`][set_position].
+    ///
+    /// [set_position]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:SetPosition
+    pub fn set_position_fraction(&self, fraction: f64) -> Result<(), DBusError> {
+        if fraction.is_nan() {
+            return Err(DBusError::Miscellaneous(String::from(
+                "Position fraction must be a number, but was NaN",
+            )));
+        }
+
+        let (track_id, length) = self.current_track_extent()?;
+        let fraction = fraction.clamp(0.0, 1.0);
+        self.set_position_in_microseconds(track_id, fraction_of_length(fraction, length))
+    }
+
+    /// Sets the position of the current track to a fraction of its length, if the player
+    /// indicates that it can seek.
+    ///
+    /// Returns a boolean to show if the signal was sent or not. See
+    /// [`set_position_fraction`](Self::set_position_fraction) for the other errors.
+    ///
+    /// See: [MPRIS2 specification about `CanSeek`][can_seek].
+    ///
+    /// [can_seek]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanSeek
+    pub fn checked_set_position_fraction(&self, fraction: f64) -> Result<bool, DBusError> {
+        if self.can_seek()? {
+            self.set_position_fraction(fraction).map(|_| true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Moves the position of the current track by a fraction of its length, like `0.05` to skip
+    /// ahead by 5% of the track or `-0.05` to go back by as much.
+    ///
+    /// The new position is clamped to the start and the end of the track. Unlike
+    /// [`set_position_fraction`](Self::set_position_fraction), which jumps to an absolute
+    /// position, this is meant for stepping through a track with keys.
+    ///
+    /// Returns an [`Err`] if `delta` is not a finite number. Returns
+    /// [`DBusError::TrackNotSeekable`] if the player can not seek, or if the track ID or length of
+    /// the current track is unknown.
+    pub fn seek_by_fraction(&self, delta: f64) -> Result<(), DBusError> {
+        if !delta.is_finite() {
+            return Err(DBusError::Miscellaneous(format!(
+                "Seek fraction must be a finite number, but was {}",
+                delta
+            )));
+        }
+
+        if !self.can_seek()? {
+            return Err(DBusError::TrackNotSeekable(
+                NotSeekableReason::PlayerCanNotSeek,
+            ));
+        }
+
+        let (track_id, length) = self.current_track_extent()?;
+        let position = self.get_position_in_microseconds()?;
+        self.set_position_in_microseconds(track_id, shift_by_fraction(position, delta, length))
+    }
+
+    /// Returns how much of the current track has been played, from `0.0` to `1.0`.
+    ///
+    /// Reads the position and the length of the current track from the player. Returns [`None`]
+    /// if the length is unknown or zero. Positions past the end of the track, which live streams
+    /// often report, give `1.0`.
+    ///
+    /// Use [`Progress::fraction`](crate::Progress::fraction) instead when a [`ProgressTracker`]
+    /// is already reading the player.
+    pub fn position_fraction(&self) -> Result<Option<f64>, DBusError> {
+        let length = match self.get_metadata()?.length_in_microseconds() {
+            Some(length) if length > 0 => length,
+            _ => return Ok(None),
+        };
+        let position = self.get_position_in_microseconds()?;
+        Ok(Some(played_fraction(position, length)))
+    }
+
+    /// Returns the ID and length (in microseconds) of the current track, or
+    /// [`DBusError::TrackNotSeekable`] if the track can not be seeked in with `SetPosition`.
+    fn current_track_extent(&self) -> Result<(TrackID, u64), DBusError> {
+        let metadata = self.get_metadata()?;
+        let track_id = track_id_for_seeking(&metadata)?;
+        let length = metadata
+            .length_in_microseconds()
+            .filter(|length| *length > 0)
+            .ok_or(DBusError::TrackNotSeekable(
+                NotSeekableReason::UnknownLength,
+            ))?;
+
+        Ok((track_id, length))
     }
 
     /// Returns the player's MPRIS (playback) `rate` as a factor.
     ///
     /// 1.0 would mean normal rate, while 2.0 would mean twice the playback speed.
     pub fn get_playback_rate(&self) -> Result<f64, DBusError> {
-        self.connection_path().rate().map_err(|e| e.into())
+        self.get_cached_property(&decode::RATE)
     }
 
     /// Gets the "Rate" setting, if the player indicates that it supports it.
@@ -299,7 +597,9 @@ impl Player {
     ///
     /// [rate]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Rate
     pub fn set_playback_rate(&self, rate: f64) -> Result<(), DBusError> {
-        self.connection_path().set_rate(rate).map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::set("Rate"), |path| {
+            path.set_rate(rate)
+        })
     }
 
     /// Set the playback rate of the player, if the player indicates that supports it and that it
@@ -326,7 +626,9 @@ impl Player {
     ///
     /// [min_rate]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:MinimumRate
     pub fn get_minimum_playback_rate(&self) -> Result<f64, DBusError> {
-        self.connection_path().minimum_rate().map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::get("MinimumRate"), |path| {
+            path.minimum_rate()
+        })
     }
 
     /// Gets the maximum allowed value for playback rate.
@@ -335,7 +637,9 @@ impl Player {
     ///
     /// [max_rate]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:MaximumRate
     pub fn get_maximum_playback_rate(&self) -> Result<f64, DBusError> {
-        self.connection_path().maximum_rate().map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::get("MaximumRate"), |path| {
+            path.maximum_rate()
+        })
     }
 
     /// Gets the minimum-maximum allowed value range for playback rate.
@@ -353,15 +657,50 @@ impl Player {
     pub fn get_metadata(&self) -> Result<Metadata, DBusError> {
         use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        let connection_path = self.connection_path();
+        let mut metadata = self
+            .retrying(Idempotent::Yes, Member::get("Metadata"), |path| {
+                Properties::get::<HashMap<String, MetadataValue>>(
+                    &path,
+                    "org.mpris.MediaPlayer2.Player",
+                    "Metadata",
+                )
+            })
+            .map(Metadata::from)?;
+        self.normalize_metadata(&mut metadata);
+        Ok(metadata)
+    }
 
-        Properties::get::<HashMap<String, MetadataValue>>(
-            &connection_path,
-            "org.mpris.MediaPlayer2.Player",
-            "Metadata",
-        )
-        .map(Metadata::from)
-        .map_err(DBusError::from)
+    /// Register a [`MetadataNormalizer`] that is applied to all metadata read from this player,
+    /// replacing any previously registered normalizer. Pass [`None`] to stop normalizing.
+    ///
+    /// See [`DefaultNormalizer`](crate::DefaultNormalizer) for fixups for known player problems.
+    pub fn set_metadata_normalizer(&mut self, normalizer: Option<Box<dyn MetadataNormalizer>>) {
+        self.metadata_normalizer.set(normalizer);
+    }
+
+    /// Apply the registered [`MetadataNormalizer`], if any.
+    pub(crate) fn normalize_metadata(&self, metadata: &mut Metadata) {
+        self.metadata_normalizer.apply(&self.identity, metadata);
+    }
+
+    /// Read any property on any interface of the player's MPRIS2 object, using the player's own
+    /// connection and bus name.
+    ///
+    /// This is an escape hatch for properties that this crate does not wrap, like the ones on
+    /// player-specific extension interfaces. The value is converted to `T`; use [`MetadataValue`]
+    /// if the type is not known in advance.
+    ///
+    /// Will return [`Err`] if the interface or property does not exist or if the value has a
+    /// different type than `T`.
+    pub fn get_property_on<T>(&self, interface: &str, name: &str) -> Result<T, DBusError>
+    where
+        T: for<'b> dbus::arg::Get<'b>,
+    {
+        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+
+        self.retrying(Idempotent::Yes, Member::get(name), |path| {
+            Properties::get::<T>(&path, interface, name)
+        })
     }
 
     /// Query the player for the current tracklist.
@@ -373,15 +712,10 @@ impl Player {
     pub fn get_track_list(&self) -> Result<TrackList, DBusError> {
         use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        let connection_path = self.connection_path();
-
-        Properties::get::<Vec<Path<'_>>>(
-            &connection_path,
-            "org.mpris.MediaPlayer2.TrackList",
-            "Tracks",
-        )
+        self.retrying(Idempotent::Yes, Member::get("Tracks"), |path| {
+            Properties::get::<Vec<Path<'_>>>(&path, "org.mpris.MediaPlayer2.TrackList", "Tracks")
+        })
         .map(TrackList::from)
-        .map_err(DBusError::from)
     }
 
     /// Query the player for the current tracklist.
@@ -410,14 +744,9 @@ impl Player {
     /// [can_edit]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Property:CanEditTracks
     pub fn can_edit_tracks(&self) -> Result<bool, DBusError> {
         use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
-        let connection_path = self.connection_path();
-
-        Properties::get::<bool>(
-            &connection_path,
-            "org.mpris.MediaPlayer2.TrackList",
-            "CanEditTracks",
-        )
-        .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::get("CanEditTracks"), |path| {
+            Properties::get::<bool>(&path, "org.mpris.MediaPlayer2.TrackList", "CanEditTracks")
+        })
     }
 
     /// Query the player to see if it allows changes to its TrackList.
@@ -447,22 +776,30 @@ impl Player {
     /// [get_meta]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Method:GetTracksMetadata
     pub fn get_tracks_metadata(&self, track_ids: &[TrackID]) -> Result<Vec<Metadata>, DBusError> {
         use dbus::arg::IterAppend;
-        let connection_path = self.connection_path();
-
-        let mut method = connection_path.method_call_with_args(
-            &"org.mpris.MediaPlayer2.TrackList".into(),
-            &"GetTracksMetadata".into(),
-            |msg| {
-                let mut i = IterAppend::new(msg);
-                i.append(track_ids.iter().map(|id| id.as_path()).collect::<Vec<_>>());
-            },
-        )?;
-        method.as_result()?;
+        let method = self.retrying(Idempotent::Yes, Member::call("GetTracksMetadata"), |path| {
+            let mut method = path.method_call_with_args(
+                &"org.mpris.MediaPlayer2.TrackList".into(),
+                &"GetTracksMetadata".into(),
+                |msg| {
+                    let mut i = IterAppend::new(msg);
+                    i.append(track_ids.iter().map(|id| id.as_path()).collect::<Vec<_>>());
+                },
+            )?;
+            method.as_result()?;
+            Ok::<_, dbus::Error>(method)
+        })?;
         let mut i = method.iter_init();
         let metadata: Vec<::std::collections::HashMap<String, MetadataValue>> = i.read()?;
 
         if metadata.len() == track_ids.len() {
-            Ok(metadata.into_iter().map(Metadata::from).collect())
+            Ok(metadata
+                .into_iter()
+                .map(|values| {
+                    let mut metadata = Metadata::from(values);
+                    self.normalize_metadata(&mut metadata);
+                    metadata
+                })
+                .collect())
         } else {
             Err(DBusError::Miscellaneous(format!(
                 "Expected {} tracks, but got {} tracks returned.",
@@ -506,6 +843,59 @@ impl Player {
         ProgressTracker::new(self, interval_ms)
     }
 
+    /// Runs a [`ProgressTracker`] loop, calling `on_tick` with each [`ProgressTick`].
+    ///
+    /// The loop stops when `on_tick` returns [`false`], or after the tick where the player quit
+    /// ([`ProgressTick::player_quit`]). When refreshing the player's state fails because it did not
+    /// reply in time, the tick has the error in [`ProgressTick::refresh_error`] and the loop keeps
+    /// going, so the refresh is tried again on later ticks. Any other refresh error stops the loop
+    /// after that tick.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tracker could not be created; see [`track_progress`](Self::track_progress).
+    /// Also returns the refresh error that stopped the loop, like when the connection to the bus
+    /// was lost.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use mpris::PlayerFinder;
+    /// # let player = PlayerFinder::new().unwrap().find_active().unwrap();
+    /// player
+    ///     .track_progress_with(100, |tick| {
+    ///         if tick.player_quit {
+    ///             println!("Player quit");
+    ///         } else {
+    ///             println!("{:?}", tick.progress.position());
+    ///         }
+    ///         // Keep going.
+    ///         true
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn track_progress_with<F>(&self, interval_ms: u32, mut on_tick: F) -> Result<(), DBusError>
+    where
+        F: FnMut(ProgressTick<'_>) -> bool,
+    {
+        let mut tracker = self.track_progress(interval_ms)?;
+        loop {
+            let tick = tracker.tick();
+            let player_quit = tick.player_quit;
+            let refresh_failed = tick
+                .refresh_error
+                .map_or(false, |error| error.kind() != DBusErrorKind::Timeout);
+            if !on_tick(tick) || player_quit {
+                return Ok(());
+            }
+            if refresh_failed {
+                if let Some(error) = tracker.take_refresh_error() {
+                    return Err(error);
+                }
+            }
+        }
+    }
+
     /// Returns a [`PlayerEvents`] iterator, or an [`DBusError`] if there was a problem with the D-Bus
     /// connection to the player.
     ///
@@ -523,28 +913,242 @@ impl Player {
         PlayerEvents::new(self)
     }
 
+    /// Returns an iterator over the tracks that the player changes to, for when only track
+    /// changes matter.
+    ///
+    /// This is [`events`](Self::events) without every other kind of [`Event`]: it yields the full
+    /// [`Metadata`] of each new track, and ends when the player shuts down.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use mpris::PlayerFinder;
+    /// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+    /// for metadata in player.track_change_stream().unwrap() {
+    ///     println!("Now playing: {:?}", metadata.unwrap().title());
+    /// }
+    /// ```
+    pub fn track_change_stream(&self) -> Result<TrackChanges<'_>, DBusError> {
+        TrackChanges::new(self)
+    }
+
+    /// Returns a [`PlayerControls`] handle that can control this player from other threads.
+    ///
+    /// The handle sends its commands over a connection of its own, so this player can keep
+    /// reading events on another thread at the same time.
+    pub fn controls(&self) -> PlayerControls {
+        PlayerControls::new(
+            self.connection.bus().clone(),
+            self.bus_name.clone(),
+            self.timeout_ms,
+        )
+    }
+
     /// Returns true if the bus of this player is still occupied in the connection, or put in
     /// another way: If there's a process still listening on messages on this bus.
     ///
     /// If the player that you are controlling / querying has shut down, then this would return
     /// false. You can use this to do graceful restarts, begin looking for another player, etc.
     pub fn is_running(&self) -> bool {
-        self.connection()
+        self.connection
             .name_has_owner(self.bus_name.to_string())
             .unwrap_or(false)
     }
 
-    pub(crate) fn connection(&self) -> &PooledConnection {
+    /// How likely it is that this is the player the user wants to control. Higher is more likely.
+    ///
+    /// [`PlayerFinder::find_active`](crate::PlayerFinder::find_active) returns the player with
+    /// the highest score, so you can rank players yourself the same way. The score is the sum of:
+    ///
+    /// * `30` if the player is [`Playing`](PlaybackStatus::Playing), `20` if it is
+    ///   [`Paused`](PlaybackStatus::Paused), or otherwise `10` if it has track metadata.
+    /// * `1` if the player can be controlled (see [`can_control`](Self::can_control)). Players
+    ///   that fail to report this are treated as not being controllable.
+    ///
+    /// MPRIS has no information about when a player was last used, so recency is not part of the
+    /// score.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the playback status or metadata failed.
+    pub fn activity_score(&self) -> Result<i64, DBusError> {
+        let status = self.get_playback_status()?;
+        let has_track = match status {
+            PlaybackStatus::Playing | PlaybackStatus::Paused => false,
+            PlaybackStatus::Stopped => !self.get_metadata()?.is_empty(),
+        };
+        let can_control = self.can_control().unwrap_or(false);
+        Ok(activity_score(status, has_track, can_control))
+    }
+
+    /// Blocks until the player reaches the given [`PlaybackStatus`].
+    ///
+    /// Useful after calling methods like [`play`](Self::play), as players apply them
+    /// asynchronously. Returns `Ok(true)` once the status is reached, or `Ok(false)` if `timeout`
+    /// runs out first. Without a timeout this waits forever, or until reading the status fails
+    /// (for example because the player quit).
+    ///
+    /// The status is read again each time the player emits a signal, and at least once every
+    /// second to support players that do not emit signals when changing status.
+    pub fn wait_until_status(
+        &self,
+        status: PlaybackStatus,
+        timeout: Option<Duration>,
+    ) -> Result<bool, DBusError> {
+        const MAX_WAIT: Duration = Duration::from_secs(1);
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if self.get_playback_status()? == status {
+                return Ok(true);
+            }
+
+            let wait = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(left) if left > Duration::from_millis(0) => left.min(MAX_WAIT),
+                    _ => return Ok(false),
+                },
+                None => MAX_WAIT,
+            };
+            self.connection.process_next_event_blocking_for(wait);
+        }
+    }
+
+    /// Returns the file descriptor of the player's D-Bus connection.
+    ///
+    /// Register it with an external event loop (like GLib or mio) and call
+    /// [`dispatch_pending`](Self::dispatch_pending) whenever it becomes readable. Returns [`None`]
+    /// if the connection has no socket to watch, which can happen after it was disconnected.
+    ///
+    /// **Note:** Players found by the same [`PlayerFinder`](crate::PlayerFinder) share their
+    /// connection, and so also the file descriptor.
+    #[cfg(unix)]
+    pub fn as_raw_fd(&self) -> Option<std::os::unix::io::RawFd> {
+        self.connection.raw_fd()
+    }
+
+    /// Processes D-Bus messages that have already arrived, without blocking, and returns the
+    /// [`Event`]s they caused.
+    ///
+    /// This is meant for integration with external event loops; see
+    /// [`as_raw_fd`](Self::as_raw_fd). The first call takes a snapshot of the player's state to
+    /// compare later changes against, so it will not return events for changes from before it was
+    /// called.
+    ///
+    /// This can be combined with [`events`](Self::events) and
+    /// [`track_progress`](Self::track_progress) for the same player; each of them gets every
+    /// signal exactly once.
+    pub fn dispatch_pending(&mut self) -> Result<Vec<Event>, EventError> {
+        let (mut state, subscription) = match self.dispatch_state.take() {
+            Some(dispatch_state) => dispatch_state,
+            None => {
+                let subscription = self.subscribe();
+                (EventState::new(self)?, subscription)
+            }
+        };
+
+        self.connection.process_events_nonblocking();
+        let mut events = Vec::new();
+        let result = state.process(self, subscription.pending_events(), &mut events);
+        self.dispatch_state = Some((state, subscription));
+
+        result.map(|_| events)
+    }
+
+    pub(crate) fn pooled_connection(&self) -> &PooledConnection {
         &self.connection
     }
 
+    /// Returns the D-Bus connection that this player talks to the player over.
+    ///
+    /// This is an escape hatch for interfaces that this crate does not wrap. The connection is
+    /// shared with this player's [`PlayerEvents`], [`ProgressTracker`]s and [`PlayerControls`],
+    /// and with every other [`Player`] found by the same [`PlayerFinder`](crate::PlayerFinder).
+    /// Sending method calls over it is fine, but reading messages from it (like through
+    /// [`Connection::incoming`]) or removing match rules takes signals away from them, and they
+    /// will miss events. Open a connection of your own to listen for other signals.
+    pub fn connection(&self) -> &Connection {
+        self.connection.underlying()
+    }
+
+    /// Calls a method on the player's bus name and object path, and returns the values in the
+    /// reply.
+    ///
+    /// This is an escape hatch for methods that this crate does not wrap, like the ones on
+    /// player-specific extension interfaces. Each argument is sent with the D-Bus type of its
+    /// [`MetadataValue`] variant, except that arrays are sent as arrays of variants (`av`) and maps
+    /// as `a{sv}`. Use [`connection`](Self::connection) to call methods that take other types.
+    ///
+    /// Will return [`Err`] if the player replied with an error, or if an argument is
+    /// [`MetadataValue::Unsupported`]. The call is only repeated after errors if the
+    /// [`RetryPolicy`] allows retrying calls that are not idempotent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use mpris::{MetadataValue, PlayerFinder};
+    /// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+    /// let reply = player
+    ///     .call_method(
+    ///         "org.freedesktop.DBus.Properties",
+    ///         "Get",
+    ///         &[
+    ///             MetadataValue::from("org.mpris.MediaPlayer2.Player"),
+    ///             MetadataValue::from("Volume"),
+    ///         ],
+    ///     )
+    ///     .unwrap();
+    /// println!("{:?}", reply);
+    /// ```
+    pub fn call_method(
+        &self,
+        interface: &str,
+        member: &str,
+        args: &[MetadataValue],
+    ) -> Result<Vec<MetadataValue>, DBusError> {
+        let args = args
+            .iter()
+            .map(|arg| {
+                arg.to_ref_arg().ok_or_else(|| {
+                    DBusError::Miscellaneous(String::from("Can not send an unsupported value"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let reply = self.retrying(Idempotent::No, Member::call(member), |path| {
+            let call = dbus::Message::new_method_call(
+                self.bus_name.as_str(),
+                MPRIS2_PATH,
+                interface,
+                member,
+            )
+            .map_err(DBusError::Miscellaneous)?
+            .append_ref(&args);
+
+            path.conn
+                .send_with_reply_and_block(call, path.timeout)
+                .map_err(DBusError::from)
+        })?;
+
+        let mut values = Vec::new();
+        let mut iter = reply.iter_init();
+        while let Some(value) = iter.get::<MetadataValue>() {
+            values.push(value);
+            if !iter.next() {
+                break;
+            }
+        }
+        Ok(values)
+    }
+
     /// Send a `PlayPause` signal to the player.
     ///
     /// See: [MPRIS2 specification about `PlayPause`][play_pause]
     ///
     /// [play_pause]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:PlayPause
     pub fn play_pause(&self) -> Result<(), DBusError> {
-        self.connection_path().play_pause().map_err(|e| e.into())
+        self.retrying(Idempotent::No, Member::call("PlayPause"), |path| {
+            path.play_pause()
+        })
     }
 
     /// Send a `Play` signal to the player.
@@ -553,7 +1157,7 @@ impl Player {
     ///
     /// [play]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Play
     pub fn play(&self) -> Result<(), DBusError> {
-        self.connection_path().play().map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::call("Play"), |path| path.play())
     }
 
     /// Send a `Pause` signal to the player.
@@ -562,7 +1166,7 @@ impl Player {
     ///
     /// [pause]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Pause
     pub fn pause(&self) -> Result<(), DBusError> {
-        self.connection_path().pause().map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::call("Pause"), |path| path.pause())
     }
 
     /// Send a `Stop` signal to the player.
@@ -571,7 +1175,7 @@ impl Player {
     ///
     /// [stop]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Stop
     pub fn stop(&self) -> Result<(), DBusError> {
-        self.connection_path().stop().map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::call("Stop"), |path| path.stop())
     }
 
     /// Send a `Next` signal to the player.
@@ -580,7 +1184,8 @@ impl Player {
     ///
     /// [next]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Next
     pub fn next(&self) -> Result<(), DBusError> {
-        self.connection_path().next().map_err(|e| e.into())
+        self.retrying(Idempotent::No, Member::call("Next"), |path| path.next())
+            .map_err(|e| self.explain_rejection("go to the next track", e))
     }
 
     /// Send a `Previous` signal to the player.
@@ -589,7 +1194,10 @@ impl Player {
     ///
     /// [prev]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Previous
     pub fn previous(&self) -> Result<(), DBusError> {
-        self.connection_path().previous().map_err(|e| e.into())
+        self.retrying(Idempotent::No, Member::call("Previous"), |path| {
+            path.previous()
+        })
+        .map_err(|e| self.explain_rejection("go to the previous track", e))
     }
 
     /// Send a `Seek` signal to the player.
@@ -598,9 +1206,95 @@ impl Player {
     ///
     /// [seek]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Seek
     pub fn seek(&self, offset_in_microseconds: i64) -> Result<(), DBusError> {
-        self.connection_path()
-            .seek(offset_in_microseconds)
-            .map_err(|e| e.into())
+        self.retrying(Idempotent::No, Member::call("Seek"), |path| {
+            path.seek(offset_in_microseconds)
+        })
+        .map_err(|e| self.explain_rejection("seek", e))
+    }
+
+    /// Send an `OpenUri` signal to the player, asking it to open and play the given URI.
+    ///
+    /// The URI is sent as it is. Use [`open_uri_checked`](Self::open_uri_checked) to check first
+    /// whether the player supports it.
+    ///
+    /// See: [MPRIS2 specification about `OpenUri`][open_uri].
+    ///
+    /// [open_uri]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:OpenUri
+    pub fn open_uri(&self, uri: &str) -> Result<(), DBusError> {
+        self.retrying(Idempotent::No, Member::call("OpenUri"), |path| {
+            path.open_uri(uri)
+        })
+    }
+
+    /// Sends an `OpenUri` signal to the player, after checking the URI against what the player
+    /// says it supports.
+    ///
+    /// * If the scheme of the URI (like `https`) is not in the player's `SupportedUriSchemes`, the
+    ///   URI is not sent and [`OpenUriOutcome::SchemeUnsupported`] is returned. Schemes are
+    ///   compared without regard to case, and URIs without a scheme (like `/music/song.ogg`) are
+    ///   treated as file paths, with the `file` scheme.
+    /// * If the file extension of the URI suggests a MIME type that is not in the player's
+    ///   `SupportedMimeTypes`, the URI is still sent, but
+    ///   [`OpenUriOutcome::MimeProbablyUnsupported`] is returned. Unknown extensions, and players
+    ///   that do not list any MIME types, are not checked.
+    ///
+    /// Returns an [`Err`] if the player's properties could not be read, or if the player rejected
+    /// the URI.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use mpris::{OpenUriOutcome, PlayerFinder};
+    /// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+    /// match player.open_uri_checked("https://example.com/stream.mp3").unwrap() {
+    ///     OpenUriOutcome::Sent => {}
+    ///     OpenUriOutcome::SchemeUnsupported(schemes) => {
+    ///         eprintln!("Only these schemes are supported: {}", schemes.join(", "))
+    ///     }
+    ///     OpenUriOutcome::MimeProbablyUnsupported(mime_type) => {
+    ///         eprintln!("The player might not be able to play {}", mime_type)
+    ///     }
+    /// }
+    /// ```
+    pub fn open_uri_checked(&self, uri: &str) -> Result<OpenUriOutcome, DBusError> {
+        let schemes = self.get_supported_uri_schemes()?;
+        let scheme = open_uri::scheme(uri);
+        if !schemes
+            .iter()
+            .any(|supported| supported.eq_ignore_ascii_case(scheme))
+        {
+            return Ok(OpenUriOutcome::SchemeUnsupported(schemes));
+        }
+
+        let unsupported_mime_type = match open_uri::probable_mime_types(uri) {
+            Some(mime_types) => {
+                let supported = self.get_supported_mime_types()?;
+                let known = supported.is_empty()
+                    || mime_types.iter().any(|mime_type| {
+                        supported
+                            .iter()
+                            .any(|supported| supported.eq_ignore_ascii_case(mime_type))
+                    });
+                if known {
+                    None
+                } else {
+                    Some(mime_types[0])
+                }
+            }
+            None => None,
+        };
+
+        self.open_uri(uri)?;
+        Ok(
+            unsupported_mime_type.map_or(OpenUriOutcome::Sent, |mime_type| {
+                OpenUriOutcome::MimeProbablyUnsupported(String::from(mime_type))
+            }),
+        )
+    }
+
+    /// See [`explain_rejection`].
+    fn explain_rejection(&self, action: &str, error: DBusError) -> DBusError {
+        explain_rejection(self.connection_path(), action, error)
     }
 
     /// Tell the player to seek forwards.
@@ -610,6 +1304,91 @@ impl Player {
         self.seek(DurationExtensions::as_micros(offset) as i64)
     }
 
+    /// Seek relative to the current position, clamped to the track.
+    ///
+    /// Unlike [`seek`](Self::seek) this reads the current position and length first and limits
+    /// the offset so the new position stays inside the track:
+    ///
+    /// * Seeking backwards never goes past the start of the track.
+    /// * Seeking forwards stops at the track length, if the length is known. When the length is
+    ///   unknown (live streams, for example), forward seeks are not clamped at all.
+    ///
+    /// Use [`track_length`](Self::track_length) if you want to make your own decisions about
+    /// clamping.
+    pub fn seek_relative(&self, offset_in_microseconds: i64) -> Result<(), DBusError> {
+        let position = self.get_position_in_microseconds()?;
+        let length = self.get_metadata()?.length_in_microseconds();
+        let target = clamp_seek_target(position, offset_in_microseconds, length);
+
+        self.seek(target as i64 - position as i64)
+    }
+
+    /// Jumps to an absolute position in the current track, like 1:23.
+    ///
+    /// Positions past the end of the track are clamped to its length, if the length is known.
+    /// Use [`seek_to_with`](Self::seek_to_with) to reject them instead.
+    ///
+    /// The position is set with `SetPosition`, using the current track ID. If the track has no
+    /// valid track ID, or the player rejects `SetPosition`, the position is read again and the
+    /// difference is sent with a relative `Seek`. The returned [`SeekMethod`] says which of them
+    /// was used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use mpris::PlayerFinder;
+    /// # use std::time::Duration;
+    /// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+    /// player.seek_to(Duration::from_secs(83)).unwrap();
+    /// ```
+    pub fn seek_to(&self, position: Duration) -> Result<SeekMethod, DBusError> {
+        self.seek_to_with(position, SeekBounds::Clamp)
+    }
+
+    /// Jumps to an absolute position in the current track, handling positions past the end of
+    /// the track as `bounds` says. See [`seek_to`](Self::seek_to).
+    pub fn seek_to_with(
+        &self,
+        position: Duration,
+        bounds: SeekBounds,
+    ) -> Result<SeekMethod, DBusError> {
+        let metadata = self.get_metadata()?;
+        let length = metadata
+            .length_in_microseconds()
+            .filter(|length| *length > 0);
+        let target = seek::bounded_target(position, length, bounds)?;
+
+        if let Some(Ok(track_id)) = metadata.checked_track_id() {
+            match self.set_position_in_microseconds(track_id, target) {
+                Ok(()) => return Ok(SeekMethod::SetPosition),
+                // A player that is gone or hanging will not answer a Seek either.
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        DBusErrorKind::ServiceUnknown
+                            | DBusErrorKind::Timeout
+                            | DBusErrorKind::Disconnected
+                    ) =>
+                {
+                    return Err(error)
+                }
+                Err(_) => {}
+            }
+        }
+
+        let offset_in_us = target as i64 - self.get_position_in_microseconds()? as i64;
+        self.seek(offset_in_us)?;
+        Ok(SeekMethod::RelativeSeek { offset_in_us })
+    }
+
+    /// Returns the length of the current track, if the player reports one.
+    ///
+    /// This reads the `mpris:length` value from the current [`Metadata`]. Tracks without a
+    /// length, like live streams, result in `Ok(None)`.
+    pub fn track_length(&self) -> Result<Option<Duration>, DBusError> {
+        self.get_metadata().map(|metadata| metadata.length())
+    }
+
     /// Send a `Raise` signal to the player.
     ///
     /// > Brings the media player's user interface to the front using any appropriate mechanism
@@ -623,7 +1402,7 @@ impl Player {
     ///
     /// [raise]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Method:Raise
     pub fn raise(&self) -> Result<(), DBusError> {
-        self.connection_path().raise().map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::call("Raise"), |path| path.raise())
     }
 
     /// Send a `Raise` signal to the player, if it supports it.
@@ -654,7 +1433,7 @@ impl Player {
     ///
     /// [quit]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Method:Quit
     pub fn quit(&self) -> Result<(), DBusError> {
-        self.connection_path().quit().map_err(|e| e.into())
+        self.retrying(Idempotent::Yes, Member::call("Quit"), |path| path.quit())
     }
 
     /// Send a `Quit` signal to the player, if it supports it.
@@ -687,9 +1466,9 @@ impl Player {
     pub fn go_to(&self, track_id: &TrackID) -> Result<(), DBusError> {
         use crate::generated::OrgMprisMediaPlayer2TrackList;
 
-        self.connection_path()
-            .go_to(track_id.into())
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::call("GoTo"), |path| {
+            path.go_to(track_id.into())
+        })
     }
 
     /// Add a URI to the TrackList and optionally set it as current.
@@ -709,9 +1488,9 @@ impl Player {
     ) -> Result<(), DBusError> {
         use crate::generated::OrgMprisMediaPlayer2TrackList;
 
-        self.connection_path()
-            .add_track(uri, after.into(), set_as_current)
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::No, Member::call("AddTrack"), |path| {
+            path.add_track(uri, after.into(), set_as_current)
+        })
     }
 
     /// Add a URI to the start of the TrackList and optionally set it as current.
@@ -724,9 +1503,9 @@ impl Player {
     pub fn add_track_at_start(&self, uri: &str, set_as_current: bool) -> Result<(), DBusError> {
         use crate::generated::OrgMprisMediaPlayer2TrackList;
 
-        self.connection_path()
-            .add_track(uri, crate::track_list::NO_TRACK.into(), set_as_current)
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::No, Member::call("AddTrack"), |path| {
+            path.add_track(uri, crate::track_list::NO_TRACK.into(), set_as_current)
+        })
     }
 
     /// Remove an item from the TrackList.
@@ -739,9 +1518,9 @@ impl Player {
     pub fn remove_track(&self, track_id: &TrackID) -> Result<(), DBusError> {
         use crate::generated::OrgMprisMediaPlayer2TrackList;
 
-        self.connection_path()
-            .remove_track(track_id.into())
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::call("RemoveTrack"), |path| {
+            path.remove_track(track_id.into())
+        })
     }
 
     /// Sends a `PlayPause` signal to the player, if the player indicates that it can pause.
@@ -887,7 +1666,7 @@ impl Player {
     ///
     /// [can_raise]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanRaise
     pub fn can_raise(&self) -> Result<bool, DBusError> {
-        self.connection_path().can_raise().map_err(|e| e.into())
+        self.get_cached_root_property(&decode::capability("CanRaise"))
     }
 
     /// Queries the player to see if it can be asked to quit.
@@ -896,7 +1675,7 @@ impl Player {
     ///
     /// [can_quit]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanQuit
     pub fn can_quit(&self) -> Result<bool, DBusError> {
-        self.connection_path().can_quit().map_err(|e| e.into())
+        self.get_cached_root_property(&decode::capability("CanQuit"))
     }
 
     /// Queries the player to see if it can be asked to entrer fullscreen.
@@ -910,8 +1689,97 @@ impl Player {
     ///
     /// [can_full]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanSetFullscreen
     pub fn can_set_fullscreen(&self) -> Result<bool, DBusError> {
-        handle_optional_property(self.connection_path().can_set_fullscreen())
-            .map(|o| o.unwrap_or(false))
+        handle_optional_property(
+            self.get_cached_root_property(&decode::capability("CanSetFullscreen")),
+        )
+        .map(|o| o.unwrap_or(false))
+    }
+
+    /// Queries what the player allows a client to do with the player itself, using a single
+    /// D-Bus call.
+    ///
+    /// The values are kept in the property cache, so with a [`CachePolicy`] other than
+    /// [`Off`](CachePolicy::Off) this can be called every time a menu is opened. See
+    /// [`RootCapabilities`] for how missing properties are handled.
+    pub fn root_capabilities(&self) -> Result<RootCapabilities, DBusError> {
+        let mut cache = self.property_cache.borrow_mut();
+        let cached: Option<HashMap<String, MetadataValue>> = RootCapabilities::PROPERTIES
+            .iter()
+            .map(|name| {
+                cache
+                    .get(name)
+                    .map(|value| (String::from(*name), value.clone()))
+            })
+            .collect();
+
+        let properties = match cached {
+            Some(properties) => properties,
+            None => {
+                let mut properties =
+                    self.retrying(Idempotent::Yes, Member::call("GetAll"), |path| {
+                        get_all_interface_properties(path, "org.mpris.MediaPlayer2")
+                    })?;
+                // Cache missing properties too, so that players without them are not asked again.
+                for name in &RootCapabilities::PROPERTIES {
+                    let value = properties
+                        .entry(String::from(*name))
+                        .or_insert(MetadataValue::Bool(false));
+                    cache.insert(name, value.clone());
+                }
+                properties
+            }
+        };
+
+        Ok(RootCapabilities::from_properties(&properties))
+    }
+
+    /// Finds out which parts of the MPRIS2 specification the player implements.
+    ///
+    /// The player's object is introspected, and the properties of the `org.mpris.MediaPlayer2` and
+    /// `org.mpris.MediaPlayer2.Player` interfaces are read. See [`CapabilityReport`] for what is
+    /// reported.
+    ///
+    /// Each of these probes has a short timeout (half a second, or the player's timeout if that
+    /// is shorter) and is not retried, so this finishes quickly even if the player hangs. Probes
+    /// that fail are listed in [`CapabilityReport::failed_probes`]. Returns an [`Err`] only if
+    /// the player is not on the bus anymore.
+    pub fn capability_report(&self) -> Result<CapabilityReport, DBusError> {
+        let timeout_ms = if self.timeout_ms < 0 {
+            PROBE_TIMEOUT_MS
+        } else {
+            self.timeout_ms.min(PROBE_TIMEOUT_MS)
+        };
+        let path = self.connection.with_path(
+            self.bus_name.as_str().into(),
+            MPRIS2_PATH.into(),
+            timeout_ms,
+        );
+
+        let span = CallSpan::enter(&self.bus_name, "CapabilityReport");
+        let result = CapabilityReport::probe(path);
+        span.finish(result.is_ok(), 1);
+        result
+    }
+
+    /// Reads every property of the `org.mpris.MediaPlayer2` and `org.mpris.MediaPlayer2.Player`
+    /// interfaces, exactly as the player reports them, sorted by name.
+    ///
+    /// This is meant for debugging: when a typed accessor fails or returns a default, the raw
+    /// value and its [`kind`](MetadataValue::kind) show what the player actually sent. The
+    /// specification gives the properties of both interfaces different names; should a player
+    /// still use a name on both, the value from `org.mpris.MediaPlayer2.Player` is kept.
+    ///
+    /// Values are not decoded or normalized, and the property cache is not used.
+    pub fn all_properties(&self) -> Result<BTreeMap<String, MetadataValue>, DBusError> {
+        let mut properties = BTreeMap::new();
+        for interface in &["org.mpris.MediaPlayer2", "org.mpris.MediaPlayer2.Player"] {
+            properties.extend(
+                self.retrying(Idempotent::Yes, Member::call("GetAll"), |path| {
+                    get_all_interface_properties(path, interface)
+                })?,
+            );
+        }
+        Ok(properties)
     }
 
     /// Queries the player to see if it can be controlled or not.
@@ -920,7 +1788,7 @@ impl Player {
     ///
     /// [can_control]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanControl
     pub fn can_control(&self) -> Result<bool, DBusError> {
-        self.connection_path().can_control().map_err(|e| e.into())
+        self.get_cached_property(&decode::capability("CanControl"))
     }
 
     /// Queries the player to see if it can go to next or not.
@@ -929,7 +1797,7 @@ impl Player {
     ///
     /// [can_next]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanGoNext
     pub fn can_go_next(&self) -> Result<bool, DBusError> {
-        self.connection_path().can_go_next().map_err(|e| e.into())
+        self.get_cached_property(&decode::capability("CanGoNext"))
     }
 
     /// Queries the player to see if it can go to previous or not.
@@ -938,9 +1806,7 @@ impl Player {
     ///
     /// [can_prev]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanGoPrevious
     pub fn can_go_previous(&self) -> Result<bool, DBusError> {
-        self.connection_path()
-            .can_go_previous()
-            .map_err(|e| e.into())
+        self.get_cached_property(&decode::capability("CanGoPrevious"))
     }
 
     /// Queries the player to see if it can pause.
@@ -949,7 +1815,7 @@ impl Player {
     ///
     /// [can_pause]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanPause
     pub fn can_pause(&self) -> Result<bool, DBusError> {
-        self.connection_path().can_pause().map_err(|e| e.into())
+        self.get_cached_property(&decode::capability("CanPause"))
     }
 
     /// Queries the player to see if it can play.
@@ -958,7 +1824,7 @@ impl Player {
     ///
     /// [can_play]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanPlay
     pub fn can_play(&self) -> Result<bool, DBusError> {
-        self.connection_path().can_play().map_err(|e| e.into())
+        self.get_cached_property(&decode::capability("CanPlay"))
     }
 
     /// Queries the player to see if it can seek within the media.
@@ -967,7 +1833,7 @@ impl Player {
     ///
     /// [can_seek]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanSeek
     pub fn can_seek(&self) -> Result<bool, DBusError> {
-        self.connection_path().can_seek().map_err(|e| e.into())
+        self.get_cached_property(&decode::capability("CanSeek"))
     }
 
     /// Queries the player to see if it can stop.
@@ -983,6 +1849,14 @@ impl Player {
         self.can_control()
     }
 
+    /// Queries everything the player allows a client to do at once, using a single D-Bus call.
+    ///
+    /// See [`Capabilities`] for how missing properties are handled.
+    pub fn get_capabilities(&self) -> Result<Capabilities, DBusError> {
+        self.get_all_properties()
+            .map(|properties| Capabilities::from_properties(&properties))
+    }
+
     /// Queries the player to see if it currently supports/allows changing playback rate.
     pub fn can_set_playback_rate(&self) -> Result<bool, DBusError> {
         self.get_valid_playback_rate_range()
@@ -993,50 +1867,75 @@ impl Player {
     pub fn can_shuffle(&self) -> Result<bool, DBusError> {
         use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("Shuffle"))
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::call("GetAll"), |path| {
+            path.get_all("org.mpris.MediaPlayer2.Player")
+        })
+        .map(|props| props.contains_key("Shuffle"))
     }
 
     /// Queries the player to see if it supports the "LoopStatus" setting
     pub fn can_loop(&self) -> Result<bool, DBusError> {
         use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("LoopStatus"))
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::call("GetAll"), |path| {
+            path.get_all("org.mpris.MediaPlayer2.Player")
+        })
+        .map(|props| props.contains_key("LoopStatus"))
     }
 
     /// Queries the player to see if it supports the "Rate" setting
     pub fn has_playback_rate(&self) -> Result<bool, DBusError> {
         use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("Rate"))
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::call("GetAll"), |path| {
+            path.get_all("org.mpris.MediaPlayer2.Player")
+        })
+        .map(|props| props.contains_key("Rate"))
     }
 
     /// Queries the player to see if it supports the "Position" setting
     pub fn has_position(&self) -> Result<bool, DBusError> {
         use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("Position"))
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::call("GetAll"), |path| {
+            path.get_all("org.mpris.MediaPlayer2.Player")
+        })
+        .map(|props| props.contains_key("Position"))
+    }
+
+    /// Checks if the player has a "Position" property that can actually be read.
+    ///
+    /// Unlike [`has_position`](Self::has_position), this also tries to read the position, since
+    /// some players list the property but fail to return a usable value. The answer is cached
+    /// after the first successful probe. Transient errors (like timeouts) are returned as
+    /// [`Err`] and are not cached.
+    pub fn supports_position(&self) -> Result<bool, DBusError> {
+        if let Some(supported) = self.position_support.get() {
+            return Ok(supported);
+        }
+
+        let supported = match self.has_position() {
+            Ok(true) => match self.get_position_in_microseconds() {
+                Ok(_) => true,
+                Err(error) if crate::event::is_transient_dbus_error(&error) => return Err(error),
+                Err(_) => false,
+            },
+            Ok(false) => false,
+            Err(error) => return Err(error),
+        };
+
+        self.position_support.set(Some(supported));
+        Ok(supported)
     }
 
     /// Queries the player to see if it supports the "Volume" setting
     pub fn has_volume(&self) -> Result<bool, DBusError> {
         use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("Volume"))
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::call("GetAll"), |path| {
+            path.get_all("org.mpris.MediaPlayer2.Player")
+        })
+        .map(|props| props.contains_key("Volume"))
     }
 
     /// Query the player for current fullscreen state.
@@ -1050,7 +1949,11 @@ impl Player {
     ///
     /// [full]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Fullscreen
     pub fn get_fullscreen(&self) -> Result<Option<bool>, DBusError> {
-        handle_optional_property(self.connection_path().fullscreen())
+        handle_optional_property(self.retrying(
+            Idempotent::Yes,
+            Member::get("Fullscreen"),
+            |path| path.fullscreen(),
+        ))
     }
 
     /// Asks the player to change fullscreen state.
@@ -1060,22 +1963,33 @@ impl Player {
     /// This property was added in MPRIS 2.2, and not all players will implement it. This method
     /// will try to detect this case and fall back to `Ok(false)`.
     ///
-    /// Other errors will be returned as [`Err`].
+    /// Returns [`DBusError::NotAllowed`] without asking the player if it has a fullscreen state but
+    /// does not report `CanSetFullscreen` as `true`, as many players can report their fullscreen
+    /// state but ignore requests to change it. Other errors will be returned as [`Err`] too.
     ///
     /// See: [MPRIS2 specification about `Fullscreen`][full] and the [`can_set_fullscreen`](Self::can_set_fullscreen) method.
     ///
     /// [full]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Fullscreen
     pub fn set_fullscreen(&self, new_state: bool) -> Result<bool, DBusError> {
-        handle_optional_property(self.connection_path().set_fullscreen(new_state))
-            .map(|o| o.is_some())
+        if !self.can_set_fullscreen()? {
+            // Players without a fullscreen state at all don't implement this part of MPRIS 2.2.
+            return match self.get_fullscreen()? {
+                Some(_) => Err(DBusError::NotAllowed(String::from("CanSetFullscreen"))),
+                None => Ok(false),
+            };
+        }
+
+        handle_optional_property(self.retrying(
+            Idempotent::Yes,
+            Member::set("Fullscreen"),
+            |path| path.set_fullscreen(new_state),
+        ))
+        .map(|o| o.is_some())
     }
 
     /// Query the player for current playback status.
     pub fn get_playback_status(&self) -> Result<PlaybackStatus, DBusError> {
-        self.connection_path()
-            .playback_status()?
-            .parse()
-            .map_err(DBusError::from)
+        self.get_cached_property(&decode::PLAYBACK_STATUS)
     }
 
     /// Query player for the state of the "Shuffle" setting.
@@ -1084,7 +1998,7 @@ impl Player {
     ///
     /// [shuffle]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Shuffle
     pub fn get_shuffle(&self) -> Result<bool, DBusError> {
-        self.connection_path().shuffle().map_err(DBusError::from)
+        self.get_cached_property(&decode::SHUFFLE)
     }
 
     /// Gets the "Shuffle" setting, if the player indicates that it supports it.
@@ -1105,9 +2019,9 @@ impl Player {
     ///
     /// [shuffle]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Shuffle
     pub fn set_shuffle(&self, state: bool) -> Result<(), DBusError> {
-        self.connection_path()
-            .set_shuffle(state)
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::set("Shuffle"), |path| {
+            path.set_shuffle(state)
+        })
     }
 
     /// Set the "Shuffle" setting of the player, if the player indicates that it supports the
@@ -1134,10 +2048,7 @@ impl Player {
     ///
     /// [loop_status]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:LoopStatus
     pub fn get_loop_status(&self) -> Result<LoopStatus, DBusError> {
-        self.connection_path()
-            .loop_status()?
-            .parse()
-            .map_err(DBusError::from)
+        self.get_cached_property(&decode::LOOP_STATUS)
     }
 
     /// Gets the "LoopStatus" setting, if the player indicates that it supports it.
@@ -1158,9 +2069,9 @@ impl Player {
     ///
     /// [loop_status]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:LoopStatus
     pub fn set_loop_status(&self, status: LoopStatus) -> Result<(), DBusError> {
-        self.connection_path()
-            .set_loop_status(status.dbus_value())
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::set("LoopStatus"), |path| {
+            path.set_loop_status(status.dbus_value())
+        })
     }
 
     /// Set the loop status of the player, if the player indicates that supports it and that it can
@@ -1190,7 +2101,7 @@ impl Player {
     ///
     /// [vol]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Volume
     pub fn get_volume(&self) -> Result<f64, DBusError> {
-        self.connection_path().volume().map_err(DBusError::from)
+        self.get_cached_property(&decode::VOLUME)
     }
 
     /// Gets the "Volume" setting, if the player indicates that it supports it.
@@ -1214,9 +2125,26 @@ impl Player {
     ///
     /// [vol]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Volume
     pub fn set_volume(&self, value: f64) -> Result<(), DBusError> {
-        self.connection_path()
-            .set_volume(value.max(0.0))
-            .map_err(DBusError::from)
+        self.retrying(Idempotent::Yes, Member::set("Volume"), |path| {
+            path.set_volume(value.max(0.0))
+        })
+    }
+
+    /// Returns the player's volume as a [`Volume`], clamped to full volume.
+    ///
+    /// See [`get_volume`](Self::get_volume) for the raw value, which can be above `1.0` for
+    /// players that amplify.
+    pub fn get_volume_level(&self) -> Result<Volume, DBusError> {
+        self.get_volume().map(Volume::from_fraction)
+    }
+
+    /// Set the volume of the player to a [`Volume`], like `Volume::from_percent(50)`.
+    ///
+    /// See: [MPRIS2 specification about `Volume`][vol].
+    ///
+    /// [vol]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Volume
+    pub fn set_volume_level(&self, volume: Volume) -> Result<(), DBusError> {
+        self.set_volume(volume.as_fraction())
     }
 
     /// Set the "Volume" setting of the player, if the player indicates that it supports the
@@ -1254,6 +2182,152 @@ impl Player {
         }
     }
 
+    /// Reads a property of the `org.mpris.MediaPlayer2.Player` interface, from the cache if the
+    /// [`CachePolicy`] allows it.
+    fn get_cached_property<T>(&self, decoder: &PropertyDecoder<T>) -> Result<T, DBusError>
+    where
+        T: Copy + std::fmt::Display,
+    {
+        self.get_cached_interface_property("org.mpris.MediaPlayer2.Player", decoder)
+    }
+
+    /// Like [`get_cached_property`](Self::get_cached_property), but for a property of the
+    /// `org.mpris.MediaPlayer2` interface.
+    fn get_cached_root_property<T>(&self, decoder: &PropertyDecoder<T>) -> Result<T, DBusError>
+    where
+        T: Copy + std::fmt::Display,
+    {
+        self.get_cached_interface_property("org.mpris.MediaPlayer2", decoder)
+    }
+
+    fn get_cached_interface_property<T>(
+        &self,
+        interface: &str,
+        decoder: &PropertyDecoder<T>,
+    ) -> Result<T, DBusError>
+    where
+        T: Copy + std::fmt::Display,
+    {
+        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+
+        let mut cache = self.property_cache.borrow_mut();
+        let value = match cache.get(decoder.name) {
+            Some(value) => value.clone(),
+            None => {
+                let value: MetadataValue =
+                    self.retrying(Idempotent::Yes, Member::get(decoder.name), |path| {
+                        Properties::get(&path, interface, decoder.name)
+                    })?;
+                cache.insert(decoder.name, value.clone());
+                value
+            }
+        };
+
+        self.decode_property(decoder, &value)
+    }
+
+    /// Decodes a property value, leniently unless the player is strict. See
+    /// [`set_strict`](Self::set_strict).
+    pub(crate) fn decode_property<T>(
+        &self,
+        decoder: &PropertyDecoder<T>,
+        value: &MetadataValue,
+    ) -> Result<T, DBusError>
+    where
+        T: Copy + std::fmt::Display,
+    {
+        self.decoding.decode(decoder, value)
+    }
+
+    /// Like [`decode_property`](Self::decode_property), but gives [`None`] instead of the fallback
+    /// when a lenient decoding fails.
+    pub(crate) fn decode_property_or_unknown<T>(
+        &self,
+        decoder: &PropertyDecoder<T>,
+        value: &MetadataValue,
+    ) -> Result<Option<T>, DBusError>
+    where
+        T: Copy + std::fmt::Display,
+    {
+        self.decoding.decode_or_unknown(decoder, value)
+    }
+
+    /// Reads every property of the `org.mpris.MediaPlayer2.Player` interface with a single
+    /// `GetAll` call.
+    pub(crate) fn get_all_properties(&self) -> Result<HashMap<String, MetadataValue>, DBusError> {
+        self.retrying(
+            Idempotent::Yes,
+            Member::call("GetAll"),
+            get_all_player_properties,
+        )
+    }
+
+    /// Makes a call to the player, and repeats it after errors as far as the [`RetryPolicy`]
+    /// allows.
+    ///
+    /// All attempts share the D-Bus timeout: later attempts only get the time that is left of
+    /// it, and no attempt is made after it ran out.
+    ///
+    /// `member` names the method, or the property that is read or written, for instrumentation
+    /// and for [`DBusError::CallFailed`] errors.
+    fn retrying<T, E, F>(
+        &self,
+        idempotent: Idempotent,
+        member: Member<'_>,
+        mut call: F,
+    ) -> Result<T, DBusError>
+    where
+        E: RetryableError + Into<DBusError>,
+        F: FnMut(ConnPath<'_, &Connection>) -> Result<T, E>,
+    {
+        // A negative timeout means the D-Bus default, which leaves no budget to keep track of.
+        let budget = u64::try_from(self.timeout_ms)
+            .ok()
+            .map(Duration::from_millis);
+        let started = Instant::now();
+        let mut attempt = 1;
+        let span = CallSpan::enter(&self.bus_name, member.name);
+
+        let result = loop {
+            let timeout_ms = match budget {
+                Some(budget) if attempt > 1 => {
+                    let left = budget.checked_sub(started.elapsed()).unwrap_or_default();
+                    i32::try_from(left.as_millis()).unwrap_or(i32::MAX).max(1)
+                }
+                _ => self.timeout_ms,
+            };
+            let path = self.connection.with_path(
+                self.bus_name.as_str().into(),
+                MPRIS2_PATH.into(),
+                timeout_ms,
+            );
+            let error = match call(path) {
+                Ok(value) => break Ok(value),
+                Err(error) => error,
+            };
+
+            let delay = match self
+                .retry_policy
+                .delay_before_retry(attempt, &error, idempotent)
+            {
+                Some(delay) => delay,
+                None => break Err(error),
+            };
+            if budget.map_or(false, |budget| started.elapsed() + delay >= budget) {
+                break Err(error);
+            }
+            std::thread::sleep(delay);
+            attempt += 1;
+        };
+
+        span.finish(result.is_ok(), attempt);
+        result.map_err(|error| {
+            error
+                .into()
+                .in_call(member.operation, member.name, &self.bus_name)
+        })
+    }
+
     fn connection_path(&self) -> ConnPath<'_, &Connection> {
         self.connection.with_path(
             self.bus_name.as_str().into(),
@@ -1262,26 +2336,28 @@ impl Player {
         )
     }
 
-    /// Blocks until player gets an event on the bus.
-    ///
-    /// Other player events will also be recorded, but will not cause this function to return. Note
-    /// that this will block forever if player is not running. Make sure to check that the player
-    /// is running before calling this method!
-    pub(crate) fn process_events_blocking_until_received(&self) {
-        while !self.connection.has_pending_events(&self.unique_name) {
-            self.connection.process_events_blocking_until_received();
-        }
+    /// Starts queueing up the events of this player for a new consumer. See [`Subscription`].
+    pub(crate) fn subscribe(&self) -> Subscription {
+        Subscription::new(&self.connection, &self.unique_name)
     }
+}
 
-    /// Return any events that are pending (for this player) on the connection.
-    pub(crate) fn pending_events(&self) -> Vec<MprisEvent> {
-        self.connection.pending_events(&self.unique_name)
-    }
+/// The highest possible [`Player::activity_score`].
+pub(crate) const MAX_ACTIVITY_SCORE: i64 = 31;
+
+pub(crate) fn activity_score(status: PlaybackStatus, has_track: bool, can_control: bool) -> i64 {
+    let status_score = match status {
+        PlaybackStatus::Playing => 30,
+        PlaybackStatus::Paused => 20,
+        PlaybackStatus::Stopped if has_track => 10,
+        PlaybackStatus::Stopped => 0,
+    };
+    status_score + if can_control { 1 } else { 0 }
 }
 
-fn handle_optional_property<T>(result: Result<T, dbus::Error>) -> Result<Option<T>, DBusError> {
+fn handle_optional_property<T>(result: Result<T, DBusError>) -> Result<Option<T>, DBusError> {
     if let Err(ref error) = result {
-        if let Some(error_name) = error.name() {
+        if let Some(error_name) = error.dbus_error().and_then(dbus::Error::name) {
             if error_name == "org.freedesktop.DBus.Error.InvalidArgs" {
                 // This property was likely just missing, which means that the player has not
                 // implemented it.
@@ -1290,7 +2366,111 @@ fn handle_optional_property<T>(result: Result<T, dbus::Error>) -> Result<Option<
         }
     }
 
-    result.map(Some).map_err(|e| e.into())
+    result.map(Some)
+}
+
+/// Returns the ID of the track in `metadata` to send with `SetPosition`, or
+/// [`DBusError::TrackNotSeekable`] if it has none that can be sent.
+fn track_id_for_seeking(metadata: &Metadata) -> Result<TrackID, DBusError> {
+    match metadata.checked_track_id() {
+        Some(Ok(track_id)) => Ok(track_id),
+        Some(Err(_)) => Err(DBusError::TrackNotSeekable(
+            NotSeekableReason::InvalidTrackID,
+        )),
+        None => Err(DBusError::TrackNotSeekable(NotSeekableReason::NoTrackID)),
+    }
+}
+
+/// Calculates the position (in microseconds) at `fraction` (`0.0..=1.0`) of `length`.
+fn fraction_of_length(fraction: f64, length: u64) -> u64 {
+    ((length as f64 * fraction).round() as u64).min(length)
+}
+
+/// Calculates how much of a track of `length` is played at `position` (both in microseconds),
+/// capped at `1.0`. `length` must not be zero.
+fn played_fraction(position: u64, length: u64) -> f64 {
+    (position as f64 / length as f64).min(1.0)
+}
+
+/// Calculates the position (in microseconds) `delta` times `length` away from `position`, clamped
+/// to `0..=length`.
+fn shift_by_fraction(position: u64, delta: f64, length: u64) -> u64 {
+    let target = (position as f64 + delta * length as f64).round();
+    (target.max(0.0) as u64).min(length)
+}
+
+/// Calculates the position (in microseconds) to end up at after seeking `offset` from `position`.
+/// Seeking backwards clamps at zero; seeking forwards clamps at `length` only when it is known.
+fn clamp_seek_target(position: u64, offset: i64, length: Option<u64>) -> u64 {
+    let target = if offset < 0 {
+        position.saturating_sub(offset.unsigned_abs())
+    } else {
+        position.saturating_add(offset as u64)
+    };
+
+    match length {
+        Some(length) => target.min(length),
+        None => target,
+    }
+}
+
+/// Reads every property of the `org.mpris.MediaPlayer2.Player` interface with a single `GetAll`
+/// call.
+pub(crate) fn get_all_player_properties(
+    connection: ConnPath<'_, &Connection>,
+) -> Result<HashMap<String, MetadataValue>, DBusError> {
+    get_all_interface_properties(connection, "org.mpris.MediaPlayer2.Player")
+}
+
+/// Reads every property of the given interface with a single `GetAll` call.
+pub(crate) fn get_all_interface_properties(
+    connection: ConnPath<'_, &Connection>,
+    interface: &str,
+) -> Result<HashMap<String, MetadataValue>, DBusError> {
+    use dbus::arg::IterAppend;
+
+    let reply = connection.method_call_with_args(
+        &"org.freedesktop.DBus.Properties".into(),
+        &"GetAll".into(),
+        |message| IterAppend::new(message).append(interface),
+    )?;
+    reply.read1().map_err(DBusError::from)
+}
+
+/// Returns [`DBusError::NotAllowedWhileStopped`] instead of `error` if the player rejected a
+/// control call while it is stopped. Errors that do not come from the player itself (like
+/// timeouts) are returned as they are.
+pub(crate) fn explain_rejection(
+    connection: ConnPath<'_, &Connection>,
+    action: &str,
+    error: DBusError,
+) -> DBusError {
+    let rejected = matches!(
+        error
+            .dbus_error()
+            .and_then(dbus::Error::name)
+            .map(dbus_error_kind),
+        Some(DBusErrorKind::Other)
+            | Some(DBusErrorKind::InvalidArguments)
+            | Some(DBusErrorKind::AccessDenied)
+    );
+    let stopped = || {
+        connection
+            .playback_status()
+            .map_or(false, |status| status == "Stopped")
+    };
+    if !rejected || !stopped() {
+        return error;
+    }
+    match error {
+        DBusError::TransportError(source) | DBusError::CallFailed { source, .. } => {
+            DBusError::NotAllowedWhileStopped {
+                action: String::from(action),
+                source,
+            }
+        }
+        other => other,
+    }
 }
 
 /// Checks if the Player implements the `org.mpris.MediaPlayer2.TrackList` interface.
@@ -1305,3 +2485,659 @@ fn has_tracklist_interface(connection: ConnPath<'_, &Connection>) -> Result<bool
     let xml: String = connection.introspect()?;
     Ok(xml.contains("org.mpris.MediaPlayer2.TrackList"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::connect_to_fake;
+
+    #[test]
+    fn it_scores_activity_by_status_first() {
+        let playing = activity_score(PlaybackStatus::Playing, false, false);
+        let paused = activity_score(PlaybackStatus::Paused, false, true);
+        let stopped_with_track = activity_score(PlaybackStatus::Stopped, true, true);
+        let stopped = activity_score(PlaybackStatus::Stopped, false, true);
+
+        assert!(playing > paused);
+        assert!(paused > stopped_with_track);
+        assert!(stopped_with_track > stopped);
+        assert_eq!(
+            activity_score(PlaybackStatus::Playing, false, true),
+            MAX_ACTIVITY_SCORE
+        );
+    }
+
+    #[test]
+    fn it_clamps_backward_seeks_at_zero() {
+        assert_eq!(clamp_seek_target(5_000, -10_000, Some(60_000)), 0);
+        assert_eq!(clamp_seek_target(5_000, -10_000, None), 0);
+        assert_eq!(clamp_seek_target(5_000, -1_000, None), 4_000);
+    }
+
+    #[test]
+    fn it_clamps_forward_seeks_at_known_length() {
+        assert_eq!(clamp_seek_target(55_000, 10_000, Some(60_000)), 60_000);
+        assert_eq!(clamp_seek_target(5_000, 10_000, Some(60_000)), 15_000);
+    }
+
+    #[test]
+    fn it_does_not_clamp_forward_seeks_on_unknown_length() {
+        assert_eq!(clamp_seek_target(55_000, 10_000, None), 65_000);
+    }
+
+    #[test]
+    fn it_shifts_positions_by_fractions_of_the_length() {
+        assert_eq!(shift_by_fraction(500, 0.05, 1_000), 550);
+        assert_eq!(shift_by_fraction(500, -0.05, 1_000), 450);
+        assert_eq!(shift_by_fraction(20, -0.05, 1_000), 0);
+        assert_eq!(shift_by_fraction(980, 0.05, 1_000), 1_000);
+        assert_eq!(shift_by_fraction(500, 0.0, 1_000), 500);
+    }
+
+    #[test]
+    fn it_seeks_by_fractions_on_a_player() {
+        let (fake, player) = connect_to_fake("seek_by_fraction");
+        let mut metadata = Metadata::new("/org/example/Track/1");
+        metadata.insert("mpris:length", MetadataValue::I64(100_000_000));
+        fake.set_metadata(metadata);
+        fake.set_position(Duration::from_secs(50));
+
+        player.seek_by_fraction(0.1).unwrap();
+        assert_eq!(
+            fake.property("Position"),
+            Some(MetadataValue::I64(60_000_000))
+        );
+        player.seek_by_fraction(-1.0).unwrap();
+        assert_eq!(fake.property("Position"), Some(MetadataValue::I64(0)));
+        assert!(player.seek_by_fraction(f64::NAN).is_err());
+
+        fake.set_property("CanSeek", MetadataValue::Bool(false));
+        assert!(matches!(
+            player.seek_by_fraction(0.1),
+            Err(DBusError::TrackNotSeekable(
+                NotSeekableReason::PlayerCanNotSeek
+            ))
+        ));
+        assert_eq!(
+            player.seek_by_fraction(0.1).unwrap_err().kind(),
+            DBusErrorKind::TrackNotSeekable
+        );
+    }
+
+    #[test]
+    fn it_sets_the_position_to_a_fraction_of_the_track() {
+        let (fake, player) = connect_to_fake("set_position_fraction");
+        let error = player.set_position_fraction(0.5).unwrap_err();
+        assert_eq!(error.kind(), DBusErrorKind::TrackNotSeekable);
+
+        let mut metadata = Metadata::new("/org/example/Track/1");
+        fake.set_metadata(metadata.clone());
+        assert!(matches!(
+            player.set_position_fraction(0.5),
+            Err(DBusError::TrackNotSeekable(
+                NotSeekableReason::UnknownLength
+            ))
+        ));
+
+        metadata.insert("mpris:length", MetadataValue::I64(100_000_000));
+        fake.set_metadata(metadata);
+        player.set_position_fraction(0.25).unwrap();
+        assert_eq!(
+            fake.property("Position"),
+            Some(MetadataValue::I64(25_000_000))
+        );
+        player.set_position_fraction(1.5).unwrap();
+        assert_eq!(
+            fake.property("Position"),
+            Some(MetadataValue::I64(100_000_000))
+        );
+        assert!(player.set_position_fraction(f64::NAN).is_err());
+
+        fake.set_property("CanSeek", MetadataValue::Bool(false));
+        assert!(!player.checked_set_position_fraction(0.0).unwrap());
+        assert_eq!(
+            fake.property("Position"),
+            Some(MetadataValue::I64(100_000_000))
+        );
+    }
+
+    #[test]
+    fn it_reads_the_played_fraction_of_the_track() {
+        let (fake, player) = connect_to_fake("position_fraction");
+        assert_eq!(player.position_fraction().unwrap(), None);
+
+        let mut metadata = Metadata::new("/org/example/Track/1");
+        metadata.insert("mpris:length", MetadataValue::I64(0));
+        fake.set_metadata(metadata.clone());
+        assert_eq!(player.position_fraction().unwrap(), None);
+
+        metadata.insert("mpris:length", MetadataValue::I64(200_000_000));
+        fake.set_metadata(metadata);
+        fake.set_position(Duration::from_secs(50));
+        assert_eq!(player.position_fraction().unwrap(), Some(0.25));
+
+        fake.set_position(Duration::from_secs(500));
+        assert_eq!(player.position_fraction().unwrap(), Some(1.0));
+    }
+
+    #[test]
+    fn it_checks_uris_before_opening_them() {
+        let (fake, player) = connect_to_fake("open_uri_checked");
+        let strings = |values: &[&str]| {
+            MetadataValue::Array(
+                values
+                    .iter()
+                    .map(|&value| MetadataValue::from(value))
+                    .collect(),
+            )
+        };
+        fake.set_root_property("SupportedUriSchemes", strings(&["file", "HTTPS"]));
+
+        assert_eq!(
+            player.open_uri_checked("smb://server/song.mp3").unwrap(),
+            OpenUriOutcome::SchemeUnsupported(vec![String::from("file"), String::from("HTTPS")])
+        );
+        assert!(fake.calls().is_empty());
+
+        assert_eq!(
+            player
+                .open_uri_checked("https://example.com/song.mp3")
+                .unwrap(),
+            OpenUriOutcome::Sent
+        );
+
+        fake.set_root_property("SupportedMimeTypes", strings(&["audio/x-flac"]));
+        assert_eq!(
+            player.open_uri_checked("/music/song.mp3").unwrap(),
+            OpenUriOutcome::MimeProbablyUnsupported(String::from("audio/mpeg"))
+        );
+        assert_eq!(
+            player.open_uri_checked("/music/song.flac").unwrap(),
+            OpenUriOutcome::Sent
+        );
+        assert_eq!(fake.calls(), vec!["OpenUri", "OpenUri", "OpenUri"]);
+    }
+
+    #[test]
+    fn it_explains_calls_rejected_while_stopped() {
+        let (fake, player) = connect_to_fake("rejected");
+        let failed = || dbus::Error::new_custom("org.freedesktop.DBus.Error.Failed", "Failed");
+
+        let error = player.explain_rejection("seek", failed().into());
+        assert_eq!(error.kind(), DBusErrorKind::NotAllowedWhileStopped);
+        assert_eq!(
+            error.to_string(),
+            "Can not seek while the player is stopped: Failed"
+        );
+
+        let timeout = dbus::Error::new_custom("org.freedesktop.DBus.Error.NoReply", "No reply");
+        let error = player.explain_rejection("seek", timeout.into());
+        assert_eq!(error.kind(), DBusErrorKind::Timeout);
+
+        fake.set_playback_status(PlaybackStatus::Playing);
+        let error = player.explain_rejection("seek", failed().into());
+        assert_eq!(error.kind(), DBusErrorKind::Other);
+    }
+
+    fn gets(fake: &crate::FakePlayer) -> usize {
+        fake.property_calls()
+            .iter()
+            .filter(|call| *call == "Get")
+            .count()
+    }
+
+    #[test]
+    fn it_caches_properties_until_they_change() {
+        let (fake, mut player) = connect_to_fake("cache_signals");
+        player.set_cache_policy(CachePolicy::SignalInvalidated);
+
+        let before = gets(&fake);
+        for _ in 0..10 {
+            assert!(player.can_seek().unwrap());
+        }
+        assert_eq!(gets(&fake) - before, 1);
+
+        // The position is always read again.
+        player.get_position().unwrap();
+        player.get_position().unwrap();
+        assert_eq!(gets(&fake) - before, 3);
+
+        fake.set_property("CanSeek", MetadataValue::Bool(false));
+        fake.emit_properties_changed(&["CanSeek"]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while player.can_seek().unwrap() {
+            assert!(Instant::now() < deadline, "The cache was never invalidated");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn it_caches_properties_for_a_time() {
+        let (fake, mut player) = connect_to_fake("cache_ttl");
+        player.set_cache_policy(CachePolicy::TimeToLive(Duration::from_millis(100)));
+        assert_eq!(
+            player.cache_policy(),
+            CachePolicy::TimeToLive(Duration::from_millis(100))
+        );
+
+        let before = gets(&fake);
+        assert_eq!(player.get_volume().unwrap(), 1.0);
+        fake.set_property("Volume", MetadataValue::F64(0.5));
+        assert_eq!(player.get_volume().unwrap(), 1.0);
+        assert_eq!(gets(&fake) - before, 1);
+
+        std::thread::sleep(Duration::from_millis(150));
+        assert_eq!(player.get_volume().unwrap(), 0.5);
+
+        player.set_cache_policy(CachePolicy::Off);
+        let before = gets(&fake);
+        player.get_volume().unwrap();
+        player.get_volume().unwrap();
+        assert_eq!(gets(&fake) - before, 2);
+    }
+
+    #[test]
+    fn it_reads_and_caches_root_capabilities() {
+        let (fake, mut player) = connect_to_fake("root_capabilities");
+        let capabilities = player.root_capabilities().unwrap();
+        assert!(capabilities.can_quit());
+        assert!(capabilities.can_raise());
+        assert!(!capabilities.can_set_fullscreen());
+        assert!(!capabilities.has_track_list());
+
+        player.set_cache_policy(CachePolicy::SignalInvalidated);
+        let get_alls = || {
+            fake.property_calls()
+                .iter()
+                .filter(|call| *call == "GetAll")
+                .count()
+        };
+        let before = get_alls();
+        for _ in 0..10 {
+            assert_eq!(player.root_capabilities().unwrap(), capabilities);
+        }
+        assert_eq!(get_alls() - before, 1);
+
+        fake.set_root_property("CanSetFullscreen", MetadataValue::Bool(true));
+        fake.emit_root_properties_changed(&["CanSetFullscreen"]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !player.root_capabilities().unwrap().can_set_fullscreen() {
+            assert!(Instant::now() < deadline, "The cache was never invalidated");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn it_caches_root_capabilities_read_one_by_one() {
+        let (fake, mut player) = connect_to_fake("root_capability_getters");
+        player.set_cache_policy(CachePolicy::SignalInvalidated);
+
+        let before = gets(&fake);
+        for _ in 0..10 {
+            assert!(player.can_raise().unwrap());
+            assert!(player.can_quit().unwrap());
+        }
+        assert_eq!(gets(&fake) - before, 2);
+
+        fake.set_root_property("CanRaise", MetadataValue::Bool(false));
+        fake.emit_root_properties_changed(&["CanRaise"]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while player.can_raise().unwrap() {
+            assert!(Instant::now() < deadline, "The cache was never invalidated");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn it_reports_what_the_player_implements() {
+        let (fake, player) = connect_to_fake("capability_report");
+        let report = player.capability_report().unwrap();
+        assert!(report.has_player_interface());
+        assert!(!report.has_track_list_interface());
+        assert!(report.properties.iter().any(|name| name == "Identity"));
+        assert!(report
+            .optional_properties
+            .iter()
+            .any(|name| name == "Shuffle"));
+        assert!(report.is_enabled("CanQuit"));
+        assert!(!report.is_enabled("HasTrackList"));
+        assert!(report.failed_probes.is_empty());
+
+        fake.set_reply_delay(Duration::from_millis(800));
+        let started = Instant::now();
+        let report = player.capability_report().unwrap();
+        assert!(started.elapsed() < Duration::from_secs(3));
+        assert_eq!(report.failed_probes.len(), 3);
+        assert!(report.interfaces.is_empty());
+    }
+
+    #[test]
+    fn it_lists_all_properties_of_the_player() {
+        let (fake, player) = connect_to_fake("all_properties");
+        fake.set_property("Volume", MetadataValue::from("loud"));
+
+        let before = fake.property_calls().len();
+        let properties = player.all_properties().unwrap();
+        assert_eq!(
+            properties.get("Identity").and_then(MetadataValue::as_str),
+            Some(player.identity())
+        );
+        assert_eq!(
+            properties.get("Volume").map(MetadataValue::kind),
+            Some(crate::MetadataValueKind::String)
+        );
+        assert!(properties.contains_key("PlaybackStatus"));
+        assert_eq!(fake.property_calls()[before..], ["GetAll", "GetAll"]);
+    }
+
+    #[test]
+    fn it_sets_the_position_of_the_current_track() {
+        let (fake, player) = connect_to_fake("set_current_position");
+        fake.set_metadata(Metadata::new("/org/example/Track/1"));
+
+        player
+            .set_current_position(Duration::from_secs(12))
+            .unwrap();
+        assert_eq!(
+            fake.property("Position"),
+            Some(MetadataValue::I64(12_000_000))
+        );
+        assert_eq!(
+            player
+                .set_current_position_verified(Duration::from_secs(5))
+                .unwrap(),
+            SetPositionOutcome::Sent
+        );
+
+        fake.set_metadata(Metadata::default());
+        let error = player
+            .set_current_position(Duration::from_secs(5))
+            .unwrap_err();
+        assert_eq!(error.kind(), DBusErrorKind::TrackNotSeekable);
+    }
+
+    #[test]
+    fn it_seeks_to_absolute_positions() {
+        let (fake, player) = connect_to_fake("seek_to");
+        let mut metadata = Metadata::new("/org/example/Track/1");
+        metadata.insert("mpris:length", MetadataValue::I64(100_000_000));
+        fake.set_metadata(metadata);
+
+        assert_eq!(
+            player.seek_to(Duration::from_secs(83)).unwrap(),
+            SeekMethod::SetPosition
+        );
+        assert_eq!(
+            fake.property("Position"),
+            Some(MetadataValue::I64(83_000_000))
+        );
+        player.seek_to(Duration::from_secs(500)).unwrap();
+        assert_eq!(
+            fake.property("Position"),
+            Some(MetadataValue::I64(100_000_000))
+        );
+        assert!(player
+            .seek_to_with(Duration::from_secs(500), SeekBounds::Reject)
+            .is_err());
+
+        let mut metadata = Metadata::default();
+        metadata.insert("mpris:length", MetadataValue::I64(100_000_000));
+        fake.set_metadata(metadata);
+        assert_eq!(
+            player.seek_to(Duration::from_secs(20)).unwrap(),
+            SeekMethod::RelativeSeek {
+                offset_in_us: -80_000_000
+            }
+        );
+        assert_eq!(
+            fake.property("Position"),
+            Some(MetadataValue::I64(20_000_000))
+        );
+        assert_eq!(fake.calls(), vec!["SetPosition", "SetPosition", "Seek"]);
+    }
+
+    #[test]
+    fn it_sets_and_reads_volume_levels() {
+        let (fake, player) = connect_to_fake("volume_level");
+        player.set_volume_level(Volume::from_percent(73)).unwrap();
+        assert_eq!(fake.property("Volume"), Some(MetadataValue::F64(0.73)));
+        assert_eq!(player.get_volume_level().unwrap().as_percent(), 73);
+
+        fake.set_property("Volume", MetadataValue::F64(1.5));
+        assert_eq!(player.get_volume_level().unwrap(), Volume::MAX);
+    }
+
+    #[test]
+    fn it_refuses_to_set_fullscreen_when_not_allowed() {
+        let (fake, player) = connect_to_fake("fullscreen");
+        assert!(!player.can_set_fullscreen().unwrap());
+        assert!(!player.set_fullscreen(true).unwrap());
+
+        fake.set_root_property("Fullscreen", MetadataValue::Bool(false));
+        fake.set_root_property("CanSetFullscreen", MetadataValue::Bool(false));
+        let error = player.set_fullscreen(true).unwrap_err();
+        assert_eq!(error.kind(), DBusErrorKind::NotAllowed);
+        assert!(
+            matches!(error, DBusError::NotAllowed(ref capability) if capability == "CanSetFullscreen")
+        );
+        assert_eq!(player.get_fullscreen().unwrap(), Some(false));
+
+        fake.set_root_property("CanSetFullscreen", MetadataValue::Bool(true));
+        assert!(player.can_set_fullscreen().unwrap());
+        assert!(player.set_fullscreen(true).unwrap());
+        assert_eq!(player.get_fullscreen().unwrap(), Some(true));
+    }
+
+    #[test]
+    fn it_calls_methods_that_are_not_wrapped() {
+        let (fake, player) = connect_to_fake("call_method");
+        fake.set_property("Volume", MetadataValue::F64(0.25));
+
+        let reply = player
+            .call_method(
+                "org.freedesktop.DBus.Properties",
+                "Get",
+                &[
+                    MetadataValue::from("org.mpris.MediaPlayer2.Player"),
+                    MetadataValue::from("Volume"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(reply, vec![MetadataValue::F64(0.25)]);
+
+        let reply = player
+            .call_method(
+                "org.mpris.MediaPlayer2.Player",
+                "Seek",
+                &[MetadataValue::I64(1_000)],
+            )
+            .unwrap();
+        assert_eq!(reply, Vec::new());
+        assert_eq!(fake.calls(), vec!["Seek"]);
+
+        assert!(player
+            .call_method("org.example.Missing", "Nothing", &[])
+            .is_err());
+        assert!(player
+            .call_method(
+                "org.mpris.MediaPlayer2.Player",
+                "Seek",
+                &[MetadataValue::Unsupported],
+            )
+            .is_err());
+        assert_eq!(fake.calls(), vec!["Seek"]);
+    }
+
+    #[test]
+    fn it_retries_failed_calls() {
+        use crate::{DBusErrorKind, RetryPolicy};
+
+        let (fake, mut player) = connect_to_fake("retry");
+        player.set_dbus_timeout_ms(5_000);
+        player.set_retry_policy(RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(1),
+            retry_on: &[DBusErrorKind::Timeout],
+            ..RetryPolicy::default()
+        });
+
+        fake.fail_next_calls(2, "NoReply");
+        assert_eq!(
+            player.get_playback_status().unwrap(),
+            PlaybackStatus::Stopped
+        );
+
+        fake.fail_next_calls(3, "NoReply");
+        let error = player.get_playback_status().unwrap_err();
+        assert_eq!(error.kind(), DBusErrorKind::Timeout);
+
+        fake.fail_next_calls(1, "UnknownMethod");
+        assert!(player.play().is_err());
+        player.play().unwrap();
+    }
+
+    #[test]
+    fn it_does_not_retry_non_idempotent_calls_unless_allowed() {
+        use crate::{DBusErrorKind, RetryPolicy};
+
+        let (fake, mut player) = connect_to_fake("retry_next");
+        player.set_dbus_timeout_ms(5_000);
+        let mut policy = RetryPolicy {
+            max_attempts: 2,
+            backoff: Duration::from_millis(1),
+            retry_on: &[DBusErrorKind::Timeout],
+            ..RetryPolicy::default()
+        };
+        player.set_retry_policy(policy);
+
+        fake.fail_next_calls(1, "NoReply");
+        assert!(player.next().is_err());
+        player.play().unwrap();
+        assert_eq!(fake.calls(), vec!["Play"]);
+
+        policy.retry_non_idempotent = true;
+        player.set_retry_policy(policy);
+        fake.fail_next_calls(1, "NoReply");
+        player.next().unwrap();
+        assert_eq!(fake.calls(), vec!["Play", "Next"]);
+    }
+
+    #[test]
+    fn it_keeps_retries_within_the_timeout() {
+        use crate::{DBusErrorKind, RetryPolicy};
+
+        let (fake, mut player) = connect_to_fake("retry_budget");
+        player.set_dbus_timeout_ms(100);
+        player.set_retry_policy(RetryPolicy {
+            max_attempts: 10,
+            backoff: Duration::from_millis(200),
+            retry_on: &[DBusErrorKind::Timeout],
+            ..RetryPolicy::default()
+        });
+
+        fake.fail_next_calls(1, "NoReply");
+        let started = Instant::now();
+        assert!(player.get_playback_status().is_err());
+        assert!(started.elapsed() < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn it_names_the_player_and_member_of_failed_calls() {
+        use std::error::Error;
+
+        let (fake, player) = connect_to_fake("call_context");
+        fake.fail_next_calls(1, "NoReply");
+        let error = player.get_playback_status().unwrap_err();
+        let prefix = format!("failed to get PlaybackStatus from {}: ", fake.bus_name());
+        assert!(error.to_string().starts_with(&prefix), "{}", error);
+        assert_eq!(error.kind(), DBusErrorKind::Timeout);
+        let source = error.source().expect("No source");
+        assert_eq!(
+            source.downcast_ref::<dbus::Error>().and_then(|e| e.name()),
+            Some("org.freedesktop.DBus.Error.NoReply")
+        );
+
+        fake.fail_next_calls(1, "Failed");
+        fake.set_playback_status(PlaybackStatus::Playing);
+        let error = player.play().unwrap_err();
+        let prefix = format!("failed to call Play on {}: ", fake.bus_name());
+        assert!(error.to_string().starts_with(&prefix), "{}", error);
+    }
+
+    #[test]
+    fn it_decodes_properties_leniently_unless_strict() {
+        let (fake, mut player) = connect_to_fake("lenient");
+        fake.set_property("LoopStatus", MetadataValue::from("off"));
+        fake.set_property("Volume", MetadataValue::from("loud"));
+
+        assert_eq!(player.get_loop_status().unwrap(), LoopStatus::None);
+        assert_eq!(player.get_volume().unwrap(), 1.0);
+        assert_eq!(player.get_volume().unwrap(), 1.0);
+        assert_eq!(
+            player.decode_warnings(),
+            vec![DecodeWarning {
+                property: String::from("Volume"),
+                value: MetadataValue::from("loud"),
+                fallback: String::from("1"),
+            }]
+        );
+
+        player.set_strict(true);
+        assert!(player.get_loop_status().is_err());
+        assert!(player.get_volume().is_err());
+    }
+
+    #[test]
+    fn it_calculates_fractions_of_the_length() {
+        assert_eq!(fraction_of_length(0.0, 1_000), 0);
+        assert_eq!(fraction_of_length(0.3, 1_000), 300);
+        assert_eq!(fraction_of_length(1.0, 1_000), 1_000);
+        assert_eq!(fraction_of_length(1.0, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn it_stops_tracking_progress_when_refreshing_fails() {
+        let (fake, player) = connect_to_fake("track_progress_failing");
+        let mut started = false;
+        let mut timed_out = false;
+        let result = player.track_progress_with(10, |tick| {
+            if !started {
+                started = true;
+                fake.fail_next_calls(1, "NoReply");
+                fake.emit_properties_changed(&["Volume"]);
+            } else if tick.refresh_error.map(DBusError::kind) == Some(DBusErrorKind::Timeout) {
+                // Timeouts don't stop the loop.
+                timed_out = true;
+                fake.fail_next_calls(10, "AccessDenied");
+                fake.emit_properties_changed(&["Volume"]);
+            }
+            true
+        });
+
+        assert!(timed_out);
+        assert_eq!(result.unwrap_err().kind(), DBusErrorKind::AccessDenied);
+    }
+
+    #[test]
+    fn it_stops_tracking_progress_when_the_player_is_gone() {
+        let (fake, player) = connect_to_fake("track_progress_gone");
+        let mut calls = 0;
+        let result = player.track_progress_with(10, |tick| {
+            calls += 1;
+            if calls == 1 {
+                assert!(!tick.player_quit);
+                fake.disappear();
+                // NameOwnerChanged is sent before the reply that says the name is gone, so it is
+                // already queued up for the next tick.
+                while player.is_running() {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            } else {
+                assert!(tick.player_quit);
+            }
+            true
+        });
+        assert!(matches!(result, Ok(())), "{:?}", result);
+        assert_eq!(calls, 2);
+    }
+}
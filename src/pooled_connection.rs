@@ -1,20 +1,80 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
-use dbus::ffidisp::{ConnPath, Connection};
+use dbus::arg::{Array, Dict, Iter, Variant};
+use dbus::ffidisp::{BusType, ConnPath, Connection};
 use dbus::strings::{BusName, Path};
 use dbus::Message;
 
 use crate::extensions::DurationExtensions;
 use crate::metadata::{Metadata, Value};
 use crate::player::MPRIS2_PATH;
+use crate::trace;
 use crate::track_list::TrackID;
 
 #[derive(Debug)]
 pub(crate) struct PooledConnection {
     connection: Connection,
-    events: RefCell<HashMap<String, Vec<MprisEvent>>>,
+    bus: Bus,
+    queues: RefCell<EventQueues>,
+}
+
+/// Which bus a [`PooledConnection`] is connected to, so that another connection to the same bus
+/// can be opened from another thread.
+///
+/// Connections that were opened outside of this crate are assumed to be on the session bus.
+#[derive(Debug, Clone)]
+pub(crate) enum Bus {
+    Type(BusType),
+    Address(String),
+}
+
+impl Bus {
+    /// Opens a new private connection to this bus.
+    pub(crate) fn connect(&self) -> Result<Connection, dbus::Error> {
+        match self {
+            Bus::Type(bus_type) => Connection::get_private(*bus_type),
+            Bus::Address(address) => {
+                let connection = Connection::open_private(address)?;
+                connection.register()?;
+                Ok(connection)
+            }
+        }
+    }
+}
+
+/// A queue of [`MprisEvent`]s for one player, owned by one consumer (like a
+/// [`PlayerEvents`](crate::PlayerEvents) or a [`ProgressTracker`](crate::ProgressTracker)).
+///
+/// Messages are read from the connection once, and every event is then pushed to each
+/// subscription of the player it came from. Each consumer therefore sees every event exactly
+/// once, no matter how many other consumers watch the same player or who happened to read the
+/// message from the connection. The queue is removed when the subscription is dropped.
+pub(crate) struct Subscription {
+    connection: Rc<PooledConnection>,
+    id: u64,
+}
+
+/// The queues of all [`Subscription`]s on a connection.
+#[derive(Debug, Default)]
+struct EventQueues {
+    next_id: u64,
+    queues: Vec<EventQueue>,
+}
+
+#[derive(Debug)]
+struct EventQueue {
+    id: u64,
+    unique_name: String,
+    events: Vec<MprisEvent>,
+
+    /// Only note that the player's properties changed in `changed`, instead of queueing events.
+    /// See [`Subscription::watch_changes`].
+    changes_only: bool,
+    changed: bool,
 }
 
 const GET_NAME_OWNER_TIMEOUT: i32 = 100; // ms
@@ -22,6 +82,10 @@ const NAME_HAS_OWNER_TIMEOUT: i32 = 100; // ms
 
 impl PooledConnection {
     pub(crate) fn new(connection: Connection) -> Self {
+        PooledConnection::on_bus(connection, Bus::Type(BusType::Session))
+    }
+
+    pub(crate) fn on_bus(connection: Connection, bus: Bus) -> Self {
         // Subscribe to events that relate to players. See [`MprisMessage`] below for details.
         let _ = connection.add_match(
             "interface='org.freedesktop.DBus.Properties',member='PropertiesChanged',path='/org/mpris/MediaPlayer2'",
@@ -37,7 +101,8 @@ impl PooledConnection {
         );
         PooledConnection {
             connection,
-            events: RefCell::new(HashMap::new()),
+            bus,
+            queues: RefCell::new(EventQueues::default()),
         }
     }
 
@@ -50,6 +115,10 @@ impl PooledConnection {
         self.connection.with_path(bus_name, path, timeout_ms)
     }
 
+    pub(crate) fn bus(&self) -> &Bus {
+        &self.bus
+    }
+
     pub(crate) fn underlying(&self) -> &Connection {
         &self.connection
     }
@@ -86,29 +155,6 @@ impl PooledConnection {
             .and_then(|reply| reply.get1())
     }
 
-    /// Returns [`true`] if the given bus name has any pending events waiting to be processed.
-    ///
-    /// If you want to actually act on the messages, use [`pending_events`](Self::pending_events).
-    pub(crate) fn has_pending_events(&self, bus_name: &str) -> bool {
-        self.events
-            .try_borrow()
-            .ok()
-            .map(|map| map.contains_key(bus_name))
-            .unwrap_or(false)
-    }
-
-    /// Removes all pending events from a bus' queue and returns them.
-    ///
-    /// If you want to non-destructively check if a bus has anything queued, use
-    /// [`has_pending_events`](Self::has_pending_events).
-    pub(crate) fn pending_events(&self, bus_name: &str) -> Vec<MprisEvent> {
-        self.events
-            .try_borrow_mut()
-            .ok()
-            .and_then(|mut events| events.remove(bus_name))
-            .unwrap_or_default()
-    }
-
     /// Process events in a blocking fashion until the deadline/timebox [`Duration`] runs out.
     pub(crate) fn process_events_blocking_for(&self, duration: Duration) {
         let start = Instant::now();
@@ -152,10 +198,54 @@ impl PooledConnection {
         }
     }
 
-    /// Takes a message and processes it appropriately. Returns the affected bus name, and a borrow
-    /// to the generated [`MprisEvent`], if applicable.
+    /// Process events in a blocking fashion until a new event is found or the timeout runs out.
+    ///
+    /// Returns [`true`] if an event was found.
+    pub(crate) fn process_next_event_blocking_for(&self, timeout: Duration) -> bool {
+        let timeout_ms = DurationExtensions::as_millis(&timeout).min(u64::from(u32::MAX)) as u32;
+
+        match self
+            .connection
+            .incoming(timeout_ms)
+            .flat_map(MprisMessage::try_parse)
+            .next()
+        {
+            Some(message) => {
+                self.process_message(message);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Process all events that have already arrived on the connection, without blocking.
+    pub(crate) fn process_events_nonblocking(&self) {
+        let messages: Vec<MprisMessage> = self
+            .connection
+            .incoming(0)
+            .flat_map(MprisMessage::try_parse)
+            .collect();
+
+        for message in messages {
+            self.process_message(message);
+        }
+    }
+
+    /// Returns the file descriptor of the connection's socket, to be polled for readability by an
+    /// external event loop.
+    #[cfg(unix)]
+    pub(crate) fn raw_fd(&self) -> Option<std::os::unix::io::RawFd> {
+        self.connection
+            .watch_fds()
+            .into_iter()
+            .find(|watch| watch.readable())
+            .map(|watch| watch.fd())
+    }
+
+    /// Takes a message and pushes the resulting [`MprisEvent`] to the queue of each subscription
+    /// of the affected player.
     fn process_message(&self, message: MprisMessage) {
-        let mut events = match self.events.try_borrow_mut() {
+        let mut queues = match self.queues.try_borrow_mut() {
             Ok(val) => val,
             Err(_) => {
                 // Drop the message. This is a better evil than triggering a panic inside a library
@@ -171,73 +261,72 @@ impl PooledConnection {
             } => {
                 // If `new_owner` is empty, then the client has quit.
                 if new_owner.is_empty() {
-                    // Clear out existing events, if any. Then add a "PlayerQuit" event on the
-                    // queue.
-                    events.insert(old_owner, vec![MprisEvent::PlayerQuit]);
+                    queues.player_quit(&old_owner);
                 }
             }
-            MprisMessage::PlayerPropertiesChanged { unique_name } => {
-                events
-                    .entry(unique_name)
-                    .or_default()
-                    .push(MprisEvent::PlayerPropertiesChanged);
+            MprisMessage::PlayerPropertiesChanged {
+                unique_name,
+                metadata_changed,
+                playback_status,
+            } => {
+                queues.push(
+                    &unique_name,
+                    MprisEvent::PlayerPropertiesChanged {
+                        metadata_changed,
+                        playback_status,
+                    },
+                );
             }
             MprisMessage::Seeked {
                 unique_name,
                 position_in_us,
             } => {
-                events
-                    .entry(unique_name)
-                    .or_default()
-                    .push(MprisEvent::Seeked { position_in_us });
+                queues.push(&unique_name, MprisEvent::Seeked { position_in_us });
+            }
+            MprisMessage::RootPropertiesChanged { unique_name } => {
+                queues.push(&unique_name, MprisEvent::RootPropertiesChanged);
             }
             MprisMessage::TrackListPropertiesChanged { unique_name } => {
-                events
-                    .entry(unique_name)
-                    .or_default()
-                    .push(MprisEvent::TrackListPropertiesChanged);
+                queues.push(&unique_name, MprisEvent::TrackListPropertiesChanged);
             }
             MprisMessage::TrackListReplaced {
                 unique_name, ids, ..
             } => {
-                events
-                    .entry(unique_name)
-                    .or_default()
-                    .push(MprisEvent::TrackListReplaced {
+                queues.push(
+                    &unique_name,
+                    MprisEvent::TrackListReplaced {
                         ids: ids.into_iter().map(TrackID::from).collect(),
-                    });
+                    },
+                );
             }
             MprisMessage::TrackAdded {
                 unique_name,
                 after_id,
                 metadata,
             } => {
-                events
-                    .entry(unique_name)
-                    .or_default()
-                    .push(MprisEvent::TrackAdded {
+                queues.push(
+                    &unique_name,
+                    MprisEvent::TrackAdded {
                         after_id,
                         metadata: Metadata::from(metadata),
-                    });
+                    },
+                );
             }
             MprisMessage::TrackRemoved { unique_name, id } => {
-                events
-                    .entry(unique_name)
-                    .or_default()
-                    .push(MprisEvent::TrackRemoved { id });
+                queues.push(&unique_name, MprisEvent::TrackRemoved { id });
             }
             MprisMessage::TrackMetadataChanged {
                 unique_name,
                 old_id,
                 metadata,
             } => {
-                events
-                    .entry(unique_name)
-                    .or_default()
-                    .push(MprisEvent::TrackMetadataChanged {
+                queues.push(
+                    &unique_name,
+                    MprisEvent::TrackMetadataChanged {
                         old_id,
                         metadata: Metadata::from(metadata),
-                    });
+                    },
+                );
             }
         }
     }
@@ -249,16 +338,190 @@ impl From<Connection> for PooledConnection {
     }
 }
 
+impl Subscription {
+    /// Starts queueing up events for the player with the given unique name. Events that were
+    /// received before this are not part of the queue.
+    pub(crate) fn new(connection: &Rc<PooledConnection>, unique_name: &str) -> Self {
+        let id = connection.queues.borrow_mut().subscribe(unique_name, false);
+        Subscription {
+            connection: Rc::clone(connection),
+            id,
+        }
+    }
+
+    /// Starts watching the player with the given unique name for changes to its properties,
+    /// without queueing up events. Use [`take_changed`](Self::take_changed) to see if there were
+    /// any; [`pending_events`](Self::pending_events) is always empty.
+    ///
+    /// This keeps a single flag, no matter how many signals arrive before it is checked.
+    pub(crate) fn watch_changes(connection: &Rc<PooledConnection>, unique_name: &str) -> Self {
+        let id = connection.queues.borrow_mut().subscribe(unique_name, true);
+        Subscription {
+            connection: Rc::clone(connection),
+            id,
+        }
+    }
+
+    /// The connection that the events are read from.
+    pub(crate) fn connection(&self) -> &PooledConnection {
+        &self.connection
+    }
+
+    /// Returns [`true`] if there are events waiting to be processed.
+    ///
+    /// If you want to actually act on the events, use [`pending_events`](Self::pending_events).
+    pub(crate) fn has_pending_events(&self) -> bool {
+        self.connection
+            .queues
+            .try_borrow()
+            .map(|queues| queues.has_events(self.id))
+            .unwrap_or(false)
+    }
+
+    /// Removes all pending events from the queue and returns them, in the order they were
+    /// received.
+    pub(crate) fn pending_events(&self) -> Vec<MprisEvent> {
+        self.connection
+            .queues
+            .try_borrow_mut()
+            .map(|mut queues| queues.take(self.id))
+            .unwrap_or_default()
+    }
+
+    /// Returns [`true`] if the player's properties changed or the player quit since the last call,
+    /// for subscriptions made with [`watch_changes`](Self::watch_changes).
+    pub(crate) fn take_changed(&self) -> bool {
+        self.connection
+            .queues
+            .try_borrow_mut()
+            .map(|mut queues| queues.take_changed(self.id))
+            .unwrap_or(false)
+    }
+
+    /// Blocks until this subscription gets an event.
+    ///
+    /// Events for other subscriptions will also be recorded, but will not cause this function to
+    /// return. Note that this will block forever if the player is not running.
+    pub(crate) fn process_events_blocking_until_received(&self) {
+        while !self.has_pending_events() {
+            self.connection.process_events_blocking_until_received();
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Ok(mut queues) = self.connection.queues.try_borrow_mut() {
+            queues.unsubscribe(self.id);
+        }
+    }
+}
+
+impl fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl EventQueues {
+    fn subscribe(&mut self, unique_name: &str, changes_only: bool) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.queues.push(EventQueue {
+            id,
+            unique_name: unique_name.to_string(),
+            events: Vec::new(),
+            changes_only,
+            changed: false,
+        });
+        id
+    }
+
+    fn unsubscribe(&mut self, id: u64) {
+        self.queues.retain(|queue| queue.id != id);
+    }
+
+    fn has_events(&self, id: u64) -> bool {
+        self.queues
+            .iter()
+            .any(|queue| queue.id == id && !queue.events.is_empty())
+    }
+
+    fn take(&mut self, id: u64) -> Vec<MprisEvent> {
+        self.queues
+            .iter_mut()
+            .find(|queue| queue.id == id)
+            .map(|queue| std::mem::take(&mut queue.events))
+            .unwrap_or_default()
+    }
+
+    fn take_changed(&mut self, id: u64) -> bool {
+        self.queues
+            .iter_mut()
+            .find(|queue| queue.id == id)
+            .map_or(false, |queue| std::mem::replace(&mut queue.changed, false))
+    }
+
+    /// Pushes a copy of the event to each queue of the player. Events for players without
+    /// subscriptions are dropped.
+    fn push(&mut self, unique_name: &str, event: MprisEvent) {
+        let changes_properties = matches!(
+            event,
+            MprisEvent::PlayerPropertiesChanged { .. } | MprisEvent::RootPropertiesChanged
+        );
+        for queue in self.queues_for(unique_name) {
+            if queue.changes_only {
+                queue.changed |= changes_properties;
+            } else {
+                queue.events.push(event.clone());
+            }
+        }
+    }
+
+    /// Replaces everything in the player's queues with a [`MprisEvent::PlayerQuit`], as nothing
+    /// else matters after that.
+    fn player_quit(&mut self, unique_name: &str) {
+        for queue in self.queues_for(unique_name) {
+            if queue.changes_only {
+                queue.changed = true;
+            } else {
+                queue.events = vec![MprisEvent::PlayerQuit];
+            }
+        }
+    }
+
+    fn queues_for<'a>(
+        &'a mut self,
+        unique_name: &'a str,
+    ) -> impl Iterator<Item = &'a mut EventQueue> + 'a {
+        self.queues
+            .iter_mut()
+            .filter(move |queue| queue.unique_name == unique_name)
+    }
+}
+
 /// Event that a Player / ProgressTracker / Event iterator should react on. These are read via the
-/// bus and placed on queues for each player. When a component asks for pending events of a player
-/// they will be returned in the same order as they were emitted in.
-#[derive(Debug)]
+/// bus and placed on the queue of each [`Subscription`] to the player. When a component asks for
+/// pending events of its subscription they will be returned in the same order as they were
+/// emitted in.
+#[derive(Debug, Clone)]
 pub(crate) enum MprisEvent {
     PlayerQuit,
-    PlayerPropertiesChanged,
+    PlayerPropertiesChanged {
+        /// If the `Metadata` property was part of the change. This is also [`true`] if the
+        /// signal could not be read in detail.
+        metadata_changed: bool,
+
+        /// The new `PlaybackStatus` as the player sent it, if the signal included one. It is
+        /// decoded by the [`Player`](crate::Player), so that its strictness applies.
+        playback_status: Option<Value>,
+    },
     Seeked {
         position_in_us: u64,
     },
+    RootPropertiesChanged,
     TrackListPropertiesChanged,
     TrackListReplaced {
         ids: Vec<TrackID>,
@@ -285,11 +548,16 @@ pub(crate) enum MprisMessage {
     },
     PlayerPropertiesChanged {
         unique_name: String,
+        metadata_changed: bool,
+        playback_status: Option<Value>,
     },
     Seeked {
         unique_name: String,
         position_in_us: u64,
     },
+    RootPropertiesChanged {
+        unique_name: String,
+    },
     TrackListPropertiesChanged {
         unique_name: String,
     },
@@ -318,8 +586,12 @@ impl MprisMessage {
     /// Tries to convert the provided [`D-Bus message`](Message) into a MprisMessage; returns [`None`] if the
     /// message was not supported.
     fn try_parse(message: Message) -> Option<Self> {
-        MprisMessage::try_parse_name_owner_changed(&message)
-            .or_else(|| MprisMessage::try_parse_mpris_signal(&message))
+        let parsed = MprisMessage::try_parse_name_owner_changed(&message)
+            .or_else(|| MprisMessage::try_parse_mpris_signal(&message));
+        if parsed.is_some() {
+            trace::signal(&message);
+        }
+        parsed
     }
 
     /// Return a [`MprisMessage::NameOwnerChanged`] if the provided D-Bus message is a
@@ -332,7 +604,9 @@ impl MprisMessage {
                 let mut iter = message.iter_init();
                 let name: String = iter.read().ok()?;
 
-                if !name.starts_with("org.mpris.") {
+                // Unique names (like `:1.42`) are released when a connection closes, which also
+                // covers players under other prefixes than `org.mpris.`.
+                if !name.starts_with("org.mpris.") && !name.starts_with(':') {
                     return None;
                 }
                 let old_owner: String = iter.read().ok()?;
@@ -373,8 +647,17 @@ fn try_parse_properties_changed(message: &Message) -> Option<MprisMessage> {
     let mut iter = message.iter_init();
     let interface_name: String = iter.read().ok()?;
     match interface_name.as_ref() {
+        "org.mpris.MediaPlayer2" => Some(MprisMessage::RootPropertiesChanged { unique_name }),
         "org.mpris.MediaPlayer2.Player" => {
-            Some(MprisMessage::PlayerPropertiesChanged { unique_name })
+            let changes = read_player_changes(iter).unwrap_or(PlayerChanges {
+                metadata_changed: true,
+                playback_status: None,
+            });
+            Some(MprisMessage::PlayerPropertiesChanged {
+                unique_name,
+                metadata_changed: changes.metadata_changed,
+                playback_status: changes.playback_status,
+            })
         }
         "org.mpris.MediaPlayer2.TrackList" => {
             Some(MprisMessage::TrackListPropertiesChanged { unique_name })
@@ -383,10 +666,44 @@ fn try_parse_properties_changed(message: &Message) -> Option<MprisMessage> {
     }
 }
 
+/// What a `PropertiesChanged` signal of the `org.mpris.MediaPlayer2.Player` interface changes.
+struct PlayerChanges {
+    metadata_changed: bool,
+    playback_status: Option<Value>,
+}
+
+/// Reads the rest of a `PropertiesChanged` signal. Only the value of `PlaybackStatus` is kept, as
+/// the other values (like `Metadata`) are read from the player when needed.
+fn read_player_changes(mut iter: Iter<'_>) -> Option<PlayerChanges> {
+    let mut changes = PlayerChanges {
+        metadata_changed: false,
+        playback_status: None,
+    };
+
+    let changed: Dict<'_, &str, Variant<Iter<'_>>, _> = iter.read().ok()?;
+    for (name, mut value) in changed {
+        match name {
+            "Metadata" => changes.metadata_changed = true,
+            "PlaybackStatus" => changes.playback_status = value.0.get::<&str>().map(Value::from),
+            _ => {}
+        }
+    }
+
+    let invalidated: Array<'_, &str, _> = iter.read().ok()?;
+    for name in invalidated {
+        changes.metadata_changed |= name == "Metadata";
+    }
+
+    Some(changes)
+}
+
 fn try_parse_seeked(message: &Message) -> Option<MprisMessage> {
     let unique_name = message.sender().map(|bus_name| bus_name.to_string())?;
-    let mut iter = message.iter_init();
-    let position_in_us: u64 = iter.read().ok()?;
+    // The specification uses a signed integer, but some players send an unsigned one.
+    let position_in_us = match message.get1::<i64>() {
+        Some(position) => position.max(0) as u64,
+        None => message.get1::<u64>()?,
+    };
 
     Some(MprisMessage::Seeked {
         unique_name,
@@ -443,3 +760,207 @@ fn try_parse_track_metadata_changed(message: &Message) -> Option<MprisMessage> {
         metadata,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(events: &[MprisEvent]) -> Vec<String> {
+        events.iter().map(|event| format!("{:?}", event)).collect()
+    }
+
+    #[test]
+    fn it_delivers_events_to_every_subscription_once() {
+        let mut queues = EventQueues::default();
+        let first = queues.subscribe(":1.1", false);
+        let second = queues.subscribe(":1.1", false);
+        let other = queues.subscribe(":1.2", false);
+
+        queues.push(
+            ":1.1",
+            MprisEvent::PlayerPropertiesChanged {
+                metadata_changed: false,
+                playback_status: None,
+            },
+        );
+        queues.push(":1.1", MprisEvent::Seeked { position_in_us: 5 });
+        queues.push(
+            ":1.3",
+            MprisEvent::PlayerPropertiesChanged {
+                metadata_changed: false,
+                playback_status: None,
+            },
+        );
+
+        let expected = vec![
+            String::from(
+                "PlayerPropertiesChanged { metadata_changed: false, playback_status: None }",
+            ),
+            String::from("Seeked { position_in_us: 5 }"),
+        ];
+        assert_eq!(kinds(&queues.take(first)), expected);
+        assert!(queues.take(first).is_empty());
+        assert!(queues.has_events(second));
+        assert_eq!(kinds(&queues.take(second)), expected);
+        assert!(!queues.has_events(other));
+    }
+
+    #[test]
+    fn it_replaces_queued_events_when_the_player_quits() {
+        let mut queues = EventQueues::default();
+        let id = queues.subscribe(":1.1", false);
+
+        queues.push(
+            ":1.1",
+            MprisEvent::PlayerPropertiesChanged {
+                metadata_changed: false,
+                playback_status: None,
+            },
+        );
+        queues.player_quit(":1.1");
+        assert!(matches!(queues.take(id)[..], [MprisEvent::PlayerQuit]));
+    }
+
+    #[test]
+    fn it_only_flags_changes_for_subscriptions_watching_changes() {
+        let mut queues = EventQueues::default();
+        let id = queues.subscribe(":1.1", true);
+
+        queues.push(":1.1", MprisEvent::Seeked { position_in_us: 5 });
+        assert!(!queues.take_changed(id));
+
+        for _ in 0..100 {
+            queues.push(
+                ":1.1",
+                MprisEvent::PlayerPropertiesChanged {
+                    metadata_changed: false,
+                    playback_status: None,
+                },
+            );
+        }
+        assert!(queues.queues[0].events.is_empty());
+        assert!(queues.take_changed(id));
+        assert!(!queues.take_changed(id));
+
+        queues.player_quit(":1.1");
+        assert!(queues.take_changed(id));
+        assert!(queues.take(id).is_empty());
+    }
+
+    #[test]
+    fn it_stops_queueing_for_removed_subscriptions() {
+        let mut queues = EventQueues::default();
+        let id = queues.subscribe(":1.1", false);
+        queues.unsubscribe(id);
+
+        queues.push(
+            ":1.1",
+            MprisEvent::PlayerPropertiesChanged {
+                metadata_changed: false,
+                playback_status: None,
+            },
+        );
+        assert!(queues.queues.is_empty());
+        assert!(queues.take(id).is_empty());
+    }
+
+    fn properties_changed(changed: &[&str], invalidated: &[&str]) -> Message {
+        let mut message = Message::new_signal(
+            MPRIS2_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+        )
+        .unwrap();
+        message.set_sender(Some(BusName::from(":1.5")));
+
+        let changed: HashMap<&str, Variant<f64>> =
+            changed.iter().map(|name| (*name, Variant(1.0))).collect();
+        let invalidated: Vec<&str> = invalidated.to_vec();
+        message.append3("org.mpris.MediaPlayer2.Player", changed, invalidated)
+    }
+
+    fn metadata_changed(message: Message) -> Option<bool> {
+        match MprisMessage::try_parse(message)? {
+            MprisMessage::PlayerPropertiesChanged {
+                unique_name,
+                metadata_changed,
+                ..
+            } => {
+                assert_eq!(unique_name, ":1.5");
+                Some(metadata_changed)
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn it_detects_metadata_in_properties_changed_signals() {
+        assert_eq!(
+            metadata_changed(properties_changed(&["Volume", "PlaybackStatus"], &[])),
+            Some(false)
+        );
+        assert_eq!(
+            metadata_changed(properties_changed(&["PlaybackStatus", "Metadata"], &[])),
+            Some(true)
+        );
+        assert_eq!(
+            metadata_changed(properties_changed(&["Volume"], &["Metadata"])),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn it_reads_playback_status_from_properties_changed_signals() {
+        let status = |value: &str| {
+            let mut message = Message::new_signal(
+                MPRIS2_PATH,
+                "org.freedesktop.DBus.Properties",
+                "PropertiesChanged",
+            )
+            .unwrap();
+            message.set_sender(Some(BusName::from(":1.5")));
+            let changed: HashMap<&str, Variant<&str>> = vec![("PlaybackStatus", Variant(value))]
+                .into_iter()
+                .collect();
+            let message =
+                message.append3("org.mpris.MediaPlayer2.Player", changed, Vec::<&str>::new());
+
+            match MprisMessage::try_parse(message) {
+                Some(MprisMessage::PlayerPropertiesChanged {
+                    playback_status, ..
+                }) => playback_status,
+                other => panic!("Unexpected message: {:?}", other),
+            }
+        };
+
+        assert_eq!(status("Paused"), Some(Value::from("Paused")));
+        // Decoding is up to the player, which might accept other spellings.
+        assert_eq!(status("playing"), Some(Value::from("playing")));
+        assert_eq!(
+            metadata_changed(properties_changed(&["PlaybackStatus"], &[])),
+            Some(false)
+        );
+    }
+
+    fn seeked<T: dbus::arg::Append>(position: T) -> Message {
+        let mut message =
+            Message::new_signal(MPRIS2_PATH, "org.mpris.MediaPlayer2.Player", "Seeked").unwrap();
+        message.set_sender(Some(BusName::from(":1.5")));
+        message.append1(position)
+    }
+
+    fn seeked_position(message: Message) -> Option<u64> {
+        match MprisMessage::try_parse(message)? {
+            MprisMessage::Seeked { position_in_us, .. } => Some(position_in_us),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn it_reads_signed_and_unsigned_seeked_positions() {
+        assert_eq!(seeked_position(seeked(5_000_000i64)), Some(5_000_000));
+        assert_eq!(seeked_position(seeked(5_000_000u64)), Some(5_000_000));
+        assert_eq!(seeked_position(seeked(-1i64)), Some(0));
+        assert_eq!(seeked_position(seeked("5")), None);
+    }
+}
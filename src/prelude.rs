@@ -0,0 +1,36 @@
+//! The types that most programs need, to import them with a single `use`.
+//!
+//! Everything here is also exported from the crate root, so this only saves typing. Types that
+//! are mostly useful for advanced uses (like [`CachePolicy`](crate::CachePolicy) or
+//! [`RetryPolicy`](crate::RetryPolicy)) are left out, to keep glob imports from clashing with
+//! names in your own code.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use mpris::prelude::*;
+//!
+//! fn show(player: &Player) -> Result<(), DBusError> {
+//!     let metadata: Metadata = player.get_metadata()?;
+//!     let status: PlaybackStatus = player.get_playback_status()?;
+//!     println!("{} ({})", metadata.display_title(), status);
+//!     Ok(())
+//! }
+//!
+//! let player = PlayerFinder::new()
+//!     .expect("Could not connect to D-Bus")
+//!     .find_active()
+//!     .expect("Could not find a player");
+//! show(&player).unwrap();
+//!
+//! let mut tracker: ProgressTracker<'_> = player.track_progress(100).unwrap();
+//! let tick: ProgressTick<'_> = tracker.tick();
+//! let progress: &Progress = tick.progress;
+//! println!("{:?}", progress.position());
+//! ```
+
+pub use crate::{
+    DBusError, DBusErrorKind, Event, EventError, FindingError, LoopStatus, Metadata, MetadataValue,
+    PlaybackStatus, Player, PlayerEvents, PlayerFinder, Progress, ProgressTick, ProgressTracker,
+    TrackID, TrackList,
+};
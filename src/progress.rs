@@ -1,11 +1,16 @@
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
+
 use thiserror::Error;
 
 use super::{DBusError, LoopStatus, PlaybackStatus, TrackList, TrackListError};
+use crate::decode::{self, PropertyDecoder};
+use crate::event::is_track_change;
 use crate::extensions::DurationExtensions;
-use crate::metadata::Metadata;
+use crate::floats_equal;
+use crate::metadata::{Metadata, Value};
 use crate::player::Player;
-use crate::pooled_connection::MprisEvent;
+use crate::pooled_connection::{MprisEvent, Subscription};
 
 /// Struct containing information about current progress of a [`Player`].
 ///
@@ -14,10 +19,15 @@ use crate::pooled_connection::MprisEvent;
 ///
 /// It is up to you to decide on how outdated information you want to rely on when implementing
 /// progress rendering.
-#[derive(Debug)]
+///
+/// [`Progress`] only contains owned data, so it can be cloned and sent to other threads, like a
+/// render thread.
+#[derive(Debug, Clone)]
 pub struct Progress {
     metadata: Metadata,
     playback_status: PlaybackStatus,
+    raw_playback_status: Option<String>,
+    playback_status_known: bool,
     shuffle: bool,
     loop_status: LoopStatus,
 
@@ -25,20 +35,37 @@ pub struct Progress {
     instant: Instant,
 
     position: Duration,
+    position_known: bool,
+    capabilities: Capabilities,
+    /// If a non-zero position has been seen for this track.
+    position_moved: bool,
     rate: f64,
     current_volume: f64,
+    track_list_position: Option<(usize, usize)>,
+    stale_after: Option<Duration>,
 }
 
 /// Controller for calculating [`Progress`] and maintaining a [`TrackList`] (if supported) for a given [`Player`].
 ///
 /// Call the [`tick`](Self::tick) method to get the most current [`Progress`] data.
+///
+/// A tracker gets its own copy of the player's signals, so it can be used together with
+/// [`PlayerEvents`](crate::PlayerEvents) and other trackers for the same player. See
+/// [`PlayerEvents`](crate::PlayerEvents#several-watchers).
 #[derive(Debug)]
 pub struct ProgressTracker<'a> {
     player: &'a Player,
+    subscription: Subscription,
     track_list: Option<TrackList>,
     interval: Duration,
+    target_resolution: Option<Duration>,
     last_tick: Instant,
     last_progress: Progress,
+    refresh_requested: bool,
+    track_list_position_enabled: bool,
+    stale_after: Option<Duration>,
+    last_stale_refresh: Option<Instant>,
+    refresh_error: Option<DBusError>,
 }
 
 /// Return value of [`ProgressTracker::tick`](ProgressTracker::tick), which gives details about the latest refresh.
@@ -60,6 +87,22 @@ pub struct ProgressTick<'a> {
     /// * Volume was decreased
     pub progress_changed: bool,
 
+    /// [`true`] if the player changed to another track.
+    ///
+    /// This uses the same comparison as [`Event::TrackChanged`](crate::Event::TrackChanged), so
+    /// the two never disagree.
+    pub track_changed: bool,
+
+    /// [`true`] if the [`PlaybackStatus`] changed.
+    pub status_changed: bool,
+
+    /// [`true`] if the position jumped within the current track, instead of advancing normally.
+    ///
+    /// This happens when the player emitted a `Seeked` signal, or when the position read from the
+    /// player differs more than a second from the position that was calculated from the previous
+    /// [`Progress`].
+    pub position_jumped: bool,
+
     /// [`true`] if [`TrackList`] data changed. This will always be [`false`] if player does not support
     /// track lists.
     ///
@@ -77,10 +120,63 @@ pub struct ProgressTick<'a> {
     /// The current [`TrackList`] from the [`ProgressTracker`]. [`track_list_changed`](Self::track_list_changed)
     /// tells you if this was changed since the last tick.
     pub track_list: Option<&'a TrackList>,
+
+    /// The position of the track at the time of the tick, and whether it was read from the player
+    /// during this tick or calculated from an older read.
+    pub position: Position,
+
+    /// The error from refreshing the [`Progress`] during this tick, if that failed. The
+    /// [`progress`](Self::progress) of the last successful refresh is reused in that case.
+    ///
+    /// Errors with a [`DBusErrorKind::Timeout`](crate::DBusErrorKind::Timeout) kind tend to go
+    /// away by themselves, but others (like a lost connection to the bus) usually mean that later
+    /// refreshes will fail too.
+    pub refresh_error: Option<&'a DBusError>,
+}
+
+/// A track position, as returned in [`ProgressTick::position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// The position in the track.
+    pub value: Duration,
+
+    /// Where the position came from.
+    pub source: PositionSource,
+
+    /// When the position was last read from the player. The same as
+    /// [`Progress::created_at`].
+    pub fetched_at: Instant,
+}
+
+/// Where a [`Position`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PositionSource {
+    /// The position was read from the player (or from a `Seeked` signal) during this tick.
+    Fetched,
+
+    /// The position was calculated from an older read, see [`Progress::position`]. This is also
+    /// used when the player's position could not be read at all (see
+    /// [`Progress::position_known`]).
+    Interpolated,
+}
+
+impl Position {
+    fn of(progress: &Progress, fetched: bool) -> Self {
+        Position {
+            value: progress.position(),
+            source: if fetched && progress.position_known() {
+                PositionSource::Fetched
+            } else {
+                PositionSource::Interpolated
+            },
+            fetched_at: progress.instant,
+        }
+    }
 }
 
 /// Errors that can occur while refreshing progress.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum ProgressError {
     /// Something went wrong with the D-Bus communication. See the [`DBusError`] type.
     #[error("D-Bus communication failed: {0}")]
@@ -103,17 +199,80 @@ impl<'a> ProgressTracker<'a> {
     ///
     /// Returns an error in case Player metadata or state retrieval over DBus fails.
     pub fn new(player: &'a Player, interval_ms: u32) -> Result<Self, DBusError> {
+        // Subscribe before taking the snapshot so no changes are missed in between.
+        let subscription = player.subscribe();
+        let interval = Duration::from_millis(u64::from(interval_ms));
+        let stale_after = Some(default_stale_after(interval));
+        let mut last_progress = Progress::from_player(player)?;
+        last_progress.stale_after = stale_after;
         Ok(ProgressTracker {
             player,
-            interval: Duration::from_millis(u64::from(interval_ms)),
+            subscription,
+            interval,
+            target_resolution: None,
             last_tick: Instant::now(),
-            last_progress: Progress::from_player(player)?,
+            last_progress,
             track_list: player.checked_get_track_list()?,
+            refresh_requested: false,
+            track_list_position_enabled: false,
+            stale_after,
+            last_stale_refresh: None,
+            refresh_error: None,
         })
     }
 
+    /// Switch to an adaptive schedule, where [`tick`](Self::tick) returns each time the track
+    /// position reaches a multiple of `resolution` instead of at a fixed interval.
+    ///
+    /// For example, a display that shows whole seconds can use a resolution of one second to be
+    /// woken up right when the displayed second changes. The wake-up time takes the playback rate
+    /// into account. A tick also returns early when the player emits a signal, so changes to the
+    /// rate or position are picked up before the next wake-up is calculated.
+    ///
+    /// While the player is not playing, no wake-ups are scheduled at all and
+    /// [`tick`](Self::tick) blocks until the player emits a signal (like when playback resumes).
+    ///
+    /// A zero `resolution` switches back to the fixed interval.
+    pub fn with_target_resolution(mut self, resolution: Duration) -> Self {
+        self.target_resolution = if resolution > Duration::new(0, 0) {
+            Some(resolution)
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Make each [`Progress`] include where the current track is in the [`TrackList`], see
+    /// [`Progress::track_list_position`].
+    ///
+    /// The position is recalculated when the track or the track list changes, and not on every
+    /// tick. Players without a track list always get [`None`].
+    pub fn with_track_list_position(mut self) -> Self {
+        self.track_list_position_enabled = true;
+        self.update_track_list_position();
+        self
+    }
+
+    /// Set how old the [`Progress`] of a playing player can get before it is refreshed, and before
+    /// the calculated position stops moving forward. See [`Progress::is_stale_at`].
+    ///
+    /// The default is twice the interval, but at least 5 seconds. A shorter limit causes more D-Bus traffic while playing, as the
+    /// player is asked for its state each time the limit runs out. A zero `limit` disables the
+    /// limit, so positions are extrapolated from snapshots of any age.
+    pub fn with_stale_after(mut self, limit: Duration) -> Self {
+        self.stale_after = if limit > Duration::new(0, 0) {
+            Some(limit)
+        } else {
+            None
+        };
+        self.last_progress.stale_after = self.stale_after;
+        self
+    }
+
     /// Returns a [`ProgressTick`] at each interval, or as close to each interval as possible.
     ///
+    /// See [`with_target_resolution`](Self::with_target_resolution) for an alternative schedule.
+    ///
     /// The returned struct contains borrows of the current data along with booleans telling you if
     /// the underlying data changed or not. See [`ProgressTick`] for more information about that.
     ///
@@ -211,36 +370,103 @@ impl<'a> ProgressTracker<'a> {
     /// }
     /// ```
     pub fn tick(&mut self) -> ProgressTick<'_> {
+        match self.target_resolution {
+            _ if self.refresh_requested => {
+                // Don't wait; the refresh was asked for now.
+            }
+            Some(resolution) => {
+                // Sleep until the next boundary, or until the next signal; whichever is first.
+                match self.last_progress.time_until_next_boundary(resolution) {
+                    Some(time_left) => {
+                        self.player
+                            .pooled_connection()
+                            .process_next_event_blocking_for(time_left);
+                    }
+                    None => self.subscription.process_events_blocking_until_received(),
+                }
+            }
+            None => {
+                // Calculate time left until we're expected to return with new data.
+                let time_left = self
+                    .interval
+                    .checked_sub(self.last_tick.elapsed())
+                    .unwrap_or_else(|| Duration::from_millis(0));
+
+                // Refresh events if we're not late.
+                if time_left > Duration::from_millis(0) {
+                    self.player
+                        .pooled_connection()
+                        .process_events_blocking_for(time_left);
+                }
+            }
+        }
+
+        self.process_pending_events()
+    }
+
+    /// Like [`tick`](Self::tick), but never blocks. Cheap enough to call on every frame of a
+    /// render loop.
+    ///
+    /// D-Bus messages that have already arrived are processed, and the [`Progress`] is only
+    /// refreshed from the player if any of them were relevant, or if it became stale. Returns
+    /// [`None`] if there were no such messages and the interval has not passed since the last
+    /// tick, so there is nothing new to show. Once the interval has passed, a tick is returned even
+    /// if nothing changed, so you can update the calculated position.
+    ///
+    /// A stale [`Progress`] is refreshed at most once per interval (and at most once a second),
+    /// even if the refresh fails and it stays stale.
+    pub fn try_tick(&mut self) -> Option<ProgressTick<'_>> {
+        self.player.pooled_connection().process_events_nonblocking();
+
+        if self.refresh_requested
+            || self.stale_refresh_due()
+            || self.subscription.has_pending_events()
+            || self.last_tick.elapsed() >= self.interval
+        {
+            Some(self.process_pending_events())
+        } else {
+            None
+        }
+    }
+
+    /// Applies the events that are queued up for the player and returns the resulting tick.
+    fn process_pending_events(&mut self) -> ProgressTick<'_> {
         let mut player_quit = false;
-        let mut progress_changed = false;
+        let mut refresh = false;
+        let mut refresh_metadata = false;
+        let mut seek_position: Option<u64> = None;
         let mut track_list_changed = false;
         let old_shuffle = self.last_progress.shuffle;
+        self.refresh_error = None;
 
-        // Calculate time left until we're expected to return with new data.
-        let time_left = self
-            .interval
-            .checked_sub(self.last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_millis(0));
-
-        // Refresh events if we're not late.
-        if time_left > Duration::from_millis(0) {
-            self.player
-                .connection()
-                .process_events_blocking_for(time_left);
+        if self.refresh_requested {
+            self.refresh_requested = false;
+            refresh = true;
+            refresh_metadata = true;
+            track_list_changed = self.refresh_track_list();
+        } else if self.stale_refresh_due() {
+            self.last_stale_refresh = Some(Instant::now());
+            refresh = true;
         }
 
         // Process events that are queued up for us
-        for event in self.player.pending_events().into_iter() {
+        for event in self.subscription.pending_events().into_iter() {
             match event {
                 MprisEvent::PlayerQuit => {
                     player_quit = true;
                     break;
                 }
-                MprisEvent::PlayerPropertiesChanged | MprisEvent::Seeked { .. } => {
-                    if !progress_changed {
-                        progress_changed |= self.refresh_player();
-                    }
+                MprisEvent::PlayerPropertiesChanged {
+                    metadata_changed, ..
+                } => {
+                    refresh = true;
+                    refresh_metadata |= metadata_changed;
+                }
+                MprisEvent::Seeked { position_in_us } => {
+                    seek_position = Some(position_in_us);
+                    refresh = true;
                 }
+                MprisEvent::RootPropertiesChanged => {}
                 MprisEvent::TrackListPropertiesChanged => {
                     track_list_changed |= self.refresh_track_list();
                 }
@@ -250,7 +476,11 @@ impl<'a> ProgressTracker<'a> {
                     }
                     track_list_changed = true;
                 }
-                MprisEvent::TrackAdded { after_id, metadata } => {
+                MprisEvent::TrackAdded {
+                    after_id,
+                    mut metadata,
+                } => {
+                    self.player.normalize_metadata(&mut metadata);
                     if let Some(ref mut list) = self.track_list {
                         list.insert(&after_id, metadata);
                     }
@@ -262,7 +492,11 @@ impl<'a> ProgressTracker<'a> {
                     }
                     track_list_changed = true;
                 }
-                MprisEvent::TrackMetadataChanged { old_id, metadata } => {
+                MprisEvent::TrackMetadataChanged {
+                    old_id,
+                    mut metadata,
+                } => {
+                    self.player.normalize_metadata(&mut metadata);
                     if let Some(ref mut list) = self.track_list {
                         list.replace_track_metadata(&old_id, metadata);
                     }
@@ -271,6 +505,14 @@ impl<'a> ProgressTracker<'a> {
             }
         }
 
+        // Refresh once for the whole batch. The metadata is the most expensive part to read, so it
+        // is only read again when a signal said that it changed.
+        let previous_progress = if refresh && !player_quit {
+            self.refresh_player(refresh_metadata)
+        } else {
+            None
+        };
+
         if old_shuffle != self.last_progress.shuffle {
             // Shuffle changed, which means that the tracklist is likely to have been changed too.
             // Do a reload, even if track_list_changed was true so the correct order is loaded even
@@ -278,38 +520,134 @@ impl<'a> ProgressTracker<'a> {
             track_list_changed |= self.refresh_track_list();
         }
 
+        // A refresh reads the position after the seek, but if it failed the position from the
+        // signal is still better than the old one.
+        let folded_seek = match seek_position {
+            Some(position_in_us) if previous_progress.is_none() => {
+                self.last_progress
+                    .apply_seek(Duration::from_micros_ext(position_in_us));
+                true
+            }
+            _ => false,
+        };
+
+        if previous_progress.is_some() || track_list_changed {
+            self.update_track_list_position();
+        }
+
+        let changes = previous_progress
+            .as_ref()
+            .map(|previous| ProgressChanges::between(previous, &self.last_progress))
+            .unwrap_or_default();
+
+        let progress_changed = previous_progress.is_some() || folded_seek;
+
         self.last_tick = Instant::now();
         ProgressTick {
+            position: Position::of(&self.last_progress, progress_changed),
             progress: &self.last_progress,
             track_list: self.track_list.as_ref(),
             player_quit,
             progress_changed,
+            track_changed: changes.track_changed,
+            status_changed: changes.status_changed,
+            position_jumped: changes.position_jumped
+                || (seek_position.is_some() && !changes.track_changed),
             track_list_changed,
+            refresh_error: self.refresh_error.as_ref(),
         }
     }
 
     /// Force a refresh right now.
     ///
     /// This will ignore the interval and perform a refresh anyway. The new [`Progress`] will be
-    /// saved, and the [`TrackList`] will be refreshed.
+    /// saved, and the [`TrackList`] will be refreshed. Use
+    /// [`request_refresh`](Self::request_refresh) to leave the refresh to the next tick instead.
     ///
     /// # Errors
     ///
     /// Returns an error if the refresh failed.
     pub fn force_refresh(&mut self) -> Result<(), ProgressError> {
-        self.last_progress = Progress::from_player(self.player)?;
+        self.refresh_now()?;
         if let Some(ref mut list) = self.track_list {
             list.reload(self.player)?;
         }
+        self.update_track_list_position();
         Ok(())
     }
 
-    fn refresh_player(&mut self) -> bool {
-        if let Ok(progress) = Progress::from_player(self.player) {
-            self.last_progress = progress;
-            return true;
+    /// Make the next [`tick`](Self::tick) or [`try_tick`](Self::try_tick) refresh everything.
+    ///
+    /// Call this after sending a command to the player (like a seek, or skipping to the next
+    /// track) so that the next tick shows the result of it instead of the calculated position. The
+    /// next tick returns right away without waiting for the interval, and reloads both the
+    /// [`Progress`] and the [`TrackList`]. Calling this several times before a tick still only
+    /// refreshes once.
+    pub fn request_refresh(&mut self) {
+        self.refresh_requested = true;
+    }
+
+    /// Reload the [`Progress`] from the player right now, ignoring the interval, and return it.
+    ///
+    /// The [`TrackList`] is not reloaded; use [`force_refresh`](Self::force_refresh) if it needs
+    /// to be. Changes found by this refresh are not reported by the next [`ProgressTick`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the refresh failed. The previous [`Progress`] is kept in that case.
+    pub fn refresh_now(&mut self) -> Result<&Progress, DBusError> {
+        let mut progress = Progress::from_player(self.player)?;
+        progress.stale_after = self.stale_after;
+        progress.inherit_position_support(&self.last_progress);
+        self.last_progress = progress;
+        self.update_track_list_position();
+        Ok(&self.last_progress)
+    }
+
+    /// Returns [`true`] if the last [`Progress`] is stale and no refresh of a stale [`Progress`] was
+    /// tried recently.
+    fn stale_refresh_due(&self) -> bool {
+        self.last_progress.is_stale()
+            && self.last_stale_refresh.map_or(true, |attempt| {
+                attempt.elapsed() >= self.interval.max(MIN_STALE_RETRY)
+            })
+    }
+
+    /// Takes the error of the last failed refresh, see [`ProgressTick::refresh_error`].
+    pub(crate) fn take_refresh_error(&mut self) -> Option<DBusError> {
+        self.refresh_error.take()
+    }
+
+    /// Replaces the last [`Progress`] with a new one. Returns the replaced [`Progress`], or
+    /// [`None`] if refreshing failed. The error is kept for the [`ProgressTick`].
+    ///
+    /// Unless `refresh_metadata` is set, the [`Metadata`] of the last [`Progress`] is reused.
+    fn refresh_player(&mut self, refresh_metadata: bool) -> Option<Progress> {
+        let metadata = if refresh_metadata {
+            None
+        } else {
+            Some(self.last_progress.metadata.clone())
+        };
+        match Progress::from_player_with_metadata(self.player, metadata) {
+            Ok(mut progress) => {
+                progress.stale_after = self.stale_after;
+                progress.inherit_position_support(&self.last_progress);
+                Some(std::mem::replace(&mut self.last_progress, progress))
+            }
+            Err(error) => {
+                self.refresh_error = Some(error);
+                None
+            }
         }
-        false
+    }
+
+    fn update_track_list_position(&mut self) {
+        self.last_progress.track_list_position = match self.track_list {
+            Some(ref list) if self.track_list_position_enabled => {
+                position_in_track_list(list, &self.last_progress.metadata)
+            }
+            _ => None,
+        };
     }
 
     fn refresh_track_list(&mut self) -> bool {
@@ -320,21 +658,306 @@ impl<'a> ProgressTracker<'a> {
     }
 }
 
+/// The index of the track in `metadata` in `list`, and the length of `list`.
+fn position_in_track_list(list: &TrackList, metadata: &Metadata) -> Option<(usize, usize)> {
+    let id = metadata.track_id()?;
+    list.ids()
+        .iter()
+        .position(|other| *other == id)
+        .map(|index| (index, list.len()))
+}
+
+/// What a [`Player`] allows a client to do, as returned by [`Player::get_capabilities`] and
+/// [`Progress::capabilities`].
+///
+/// The MPRIS2 specification says that a player that can not be controlled can not do any of the
+/// other things either, so all of them are [`false`] when [`can_control`](Self::can_control) is.
+/// If the player does not report one of them, it is assumed to be the same as
+/// [`can_control`](Self::can_control).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    can_control: bool,
+    can_go_next: bool,
+    can_go_previous: bool,
+    can_play: bool,
+    can_pause: bool,
+    can_seek: bool,
+}
+
+impl Capabilities {
+    /// Reads the capabilities out of the properties of the `org.mpris.MediaPlayer2.Player`
+    /// interface.
+    pub(crate) fn from_properties(properties: &HashMap<String, Value>) -> Self {
+        let flag = |key: &str| properties.get(key).and_then(Value::as_bool);
+        let can_control = flag("CanControl").unwrap_or(false);
+        let read = |key: &str| can_control && flag(key).unwrap_or(true);
+
+        Capabilities {
+            can_control,
+            can_go_next: read("CanGoNext"),
+            can_go_previous: read("CanGoPrevious"),
+            can_play: read("CanPlay"),
+            can_pause: read("CanPause"),
+            can_seek: read("CanSeek"),
+        }
+    }
+
+    /// If the player can be controlled at all.
+    ///
+    /// See: [MPRIS2 specification about `CanControl`][can_control].
+    ///
+    /// [can_control]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanControl
+    pub fn can_control(&self) -> bool {
+        self.can_control
+    }
+
+    /// If the player can go to the next track.
+    ///
+    /// See: [MPRIS2 specification about `CanGoNext`][can_next].
+    ///
+    /// [can_next]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanGoNext
+    pub fn can_go_next(&self) -> bool {
+        self.can_go_next
+    }
+
+    /// If the player can go to the previous track.
+    ///
+    /// See: [MPRIS2 specification about `CanGoPrevious`][can_prev].
+    ///
+    /// [can_prev]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanGoPrevious
+    pub fn can_go_previous(&self) -> bool {
+        self.can_go_previous
+    }
+
+    /// If the player can start playing.
+    ///
+    /// See: [MPRIS2 specification about `CanPlay`][can_play].
+    ///
+    /// [can_play]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanPlay
+    pub fn can_play(&self) -> bool {
+        self.can_play
+    }
+
+    /// If the player can pause.
+    ///
+    /// See: [MPRIS2 specification about `CanPause`][can_pause].
+    ///
+    /// [can_pause]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanPause
+    pub fn can_pause(&self) -> bool {
+        self.can_pause
+    }
+
+    /// If the player can seek within the current track.
+    ///
+    /// See: [MPRIS2 specification about `CanSeek`][can_seek].
+    ///
+    /// [can_seek]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanSeek
+    pub fn can_seek(&self) -> bool {
+        self.can_seek
+    }
+}
+
+/// What a client can do with a [`Player`] itself, rather than with what it plays, as returned by
+/// [`Player::root_capabilities`].
+///
+/// These are the capabilities of the `org.mpris.MediaPlayer2` interface. Properties that the
+/// player does not report are assumed to be [`false`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RootCapabilities {
+    can_quit: bool,
+    can_raise: bool,
+    can_set_fullscreen: bool,
+    has_track_list: bool,
+}
+
+impl RootCapabilities {
+    /// The properties of the `org.mpris.MediaPlayer2` interface that make up the capabilities.
+    pub(crate) const PROPERTIES: [&'static str; 4] =
+        ["CanQuit", "CanRaise", "CanSetFullscreen", "HasTrackList"];
+
+    /// Reads the capabilities out of the properties of the `org.mpris.MediaPlayer2` interface.
+    pub(crate) fn from_properties(properties: &HashMap<String, Value>) -> Self {
+        let flag = |key: &str| {
+            properties
+                .get(key)
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        };
+
+        RootCapabilities {
+            can_quit: flag("CanQuit"),
+            can_raise: flag("CanRaise"),
+            can_set_fullscreen: flag("CanSetFullscreen"),
+            has_track_list: flag("HasTrackList"),
+        }
+    }
+
+    /// If the player can be asked to quit.
+    ///
+    /// See: [MPRIS2 specification about `CanQuit`][can_quit].
+    ///
+    /// [can_quit]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanQuit
+    pub fn can_quit(&self) -> bool {
+        self.can_quit
+    }
+
+    /// If the player can be asked to raise its window.
+    ///
+    /// See: [MPRIS2 specification about `CanRaise`][can_raise].
+    ///
+    /// [can_raise]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanRaise
+    pub fn can_raise(&self) -> bool {
+        self.can_raise
+    }
+
+    /// If the player can be asked to enter or leave fullscreen. This property was added in MPRIS
+    /// 2.2, so older players never allow it.
+    ///
+    /// See: [MPRIS2 specification about `CanSetFullscreen`][can_full].
+    ///
+    /// [can_full]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanSetFullscreen
+    pub fn can_set_fullscreen(&self) -> bool {
+        self.can_set_fullscreen
+    }
+
+    /// If the player says that it implements the `org.mpris.MediaPlayer2.TrackList` interface.
+    ///
+    /// See: [MPRIS2 specification about `HasTrackList`][has_track_list].
+    ///
+    /// [has_track_list]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:HasTrackList
+    pub fn has_track_list(&self) -> bool {
+        self.has_track_list
+    }
+}
+
+/// The shortest time that the [`Progress`] of a playing player can get old in a
+/// [`ProgressTracker`] before it is considered stale.
+const MIN_STALE_AFTER: Duration = Duration::from_secs(5);
+
+/// The shortest time between two refreshes of a stale [`Progress`], so that a player that fails to
+/// reply is not asked again on every frame.
+const MIN_STALE_RETRY: Duration = Duration::from_secs(1);
+
+/// How old the [`Progress`] of a [`ProgressTracker`] with this interval can get before it is
+/// considered stale. Twice the interval, so that snapshots don't become stale between ticks.
+fn default_stale_after(interval: Duration) -> Duration {
+    MIN_STALE_AFTER.max(interval * 2)
+}
+
+/// How much the position read from a player may differ from the calculated position before it is
+/// considered a jump.
+const POSITION_JUMP_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// The changes between two [`Progress`] snapshots, as reported in [`ProgressTick`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct ProgressChanges {
+    track_changed: bool,
+    status_changed: bool,
+    position_jumped: bool,
+}
+
+impl ProgressChanges {
+    fn between(old: &Progress, new: &Progress) -> Self {
+        let track_changed = is_track_change(old.metadata(), new.metadata());
+
+        let position_jumped = !track_changed && old.position_known && new.position_known && {
+            let expected = old.extrapolate(new.instant);
+            let actual = new.position;
+            let difference = if actual > expected {
+                actual - expected
+            } else {
+                expected - actual
+            };
+            difference > POSITION_JUMP_TOLERANCE
+        };
+
+        ProgressChanges {
+            track_changed,
+            status_changed: old.playback_status() != new.playback_status()
+                || old.unknown_playback_status() != new.unknown_playback_status(),
+            position_jumped,
+        }
+    }
+}
+
 impl Progress {
     pub(crate) fn from_player(player: &Player) -> Result<Progress, DBusError> {
+        Progress::from_player_with_metadata(player, None)
+    }
+
+    /// Like [`from_player`](Self::from_player), but uses the given [`Metadata`] instead of reading
+    /// it from the player, if there is one.
+    fn from_player_with_metadata(
+        player: &Player,
+        metadata: Option<Metadata>,
+    ) -> Result<Progress, DBusError> {
+        // Read everything with a single call. Players that leave out one of the required
+        // properties get an individual read for it below.
+        let mut properties = player.get_all_properties()?;
+        // The position is interpolated from here, so take the instant right after reading it.
+        let instant = Instant::now();
+
+        // Players without a readable position should still get a snapshot so that events can be
+        // detected, so treat a position that can't be read as unknown.
+        let position = match properties.get("Position") {
+            Some(value) => player
+                .decode_property_or_unknown(&decode::POSITION, value)?
+                .map(Duration::from_micros_ext),
+            None if player.supports_position().unwrap_or(false) => player.get_position().ok(),
+            None => None,
+        };
+
+        let metadata = match (metadata, properties.remove("Metadata")) {
+            (Some(metadata), _) => metadata,
+            (None, Some(Value::Map(values))) => {
+                let mut metadata = Metadata::from(values);
+                player.normalize_metadata(&mut metadata);
+                metadata
+            }
+            (None, _) => player.get_metadata()?,
+        };
+
+        let playback_status = match properties.get("PlaybackStatus") {
+            Some(value) => player.decode_property(&decode::PLAYBACK_STATUS, value)?,
+            None => player.get_playback_status()?,
+        };
+        let raw_playback_status = properties
+            .get("PlaybackStatus")
+            .and_then(Value::as_str)
+            .map(String::from);
+        let playback_status_known = properties
+            .get("PlaybackStatus")
+            .map_or(true, decode::is_known_playback_status);
+
+        // Optional properties that are missing are not supported by the player.
+        let optional = |decoder: &PropertyDecoder<f64>, default: f64| {
+            properties
+                .get(decoder.name)
+                .map_or(Ok(default), |value| player.decode_property(decoder, value))
+        };
+
         Ok(Progress {
-            metadata: player.get_metadata()?,
-            playback_status: player.get_playback_status()?,
-            shuffle: player.checked_get_shuffle()?.unwrap_or(false),
-            loop_status: player
-                .checked_get_loop_status()?
-                .unwrap_or(LoopStatus::None),
-            rate: player.checked_get_playback_rate()?.unwrap_or(1.0),
-            position: player
-                .checked_get_position()?
-                .unwrap_or_else(|| Duration::new(0, 0)),
-            current_volume: player.checked_get_volume()?.unwrap_or(1.0),
-            instant: Instant::now(),
+            playback_status,
+            raw_playback_status,
+            playback_status_known,
+            shuffle: match properties.get("Shuffle") {
+                Some(value) => player.decode_property(&decode::SHUFFLE, value)?,
+                None => false,
+            },
+            loop_status: match properties.get("LoopStatus") {
+                Some(value) => player.decode_property(&decode::LOOP_STATUS, value)?,
+                None => LoopStatus::None,
+            },
+            rate: optional(&decode::RATE, 1.0)?,
+            current_volume: optional(&decode::VOLUME, 1.0)?,
+            capabilities: Capabilities::from_properties(&properties),
+            position: position.unwrap_or_else(|| Duration::new(0, 0)),
+            position_known: position.is_some(),
+            position_moved: position.map_or(false, |position| position > Duration::new(0, 0)),
+            metadata,
+            track_list_position: None,
+            stale_after: None,
+            instant,
         })
     }
 
@@ -344,10 +967,40 @@ impl Progress {
     }
 
     /// The playback status at the point in time that this Progress was constructed.
+    ///
+    /// If the player reported a status that is not in the specification, this is
+    /// [`PlaybackStatus::Stopped`]. See [`playback_status_known`](Self::playback_status_known).
     pub fn playback_status(&self) -> PlaybackStatus {
         self.playback_status
     }
 
+    /// The playback status exactly as the player reported it, like `"Playing"` or `"Buffering"`.
+    ///
+    /// This is [`None`] if the player did not report its status as a string.
+    pub fn raw_playback_status(&self) -> Option<&str> {
+        self.raw_playback_status.as_deref()
+    }
+
+    /// Returns [`false`] if the player reported a playback status that is not in the
+    /// specification, like `"Buffering"` from some browser integrations.
+    ///
+    /// [`playback_status`](Self::playback_status) is then [`PlaybackStatus::Stopped`], and user
+    /// interfaces can show the [`raw_playback_status`](Self::raw_playback_status) or a
+    /// placeholder instead. Players that report "Buffering" usually go back to a known status
+    /// soon.
+    pub fn playback_status_known(&self) -> bool {
+        self.playback_status_known
+    }
+
+    /// The status that the player reported, if it is not in the specification.
+    pub(crate) fn unknown_playback_status(&self) -> Option<&str> {
+        if self.playback_status_known {
+            None
+        } else {
+            self.raw_playback_status()
+        }
+    }
+
     /// The shuffle status at the point in time that this Progress was constructed.
     pub fn shuffle(&self) -> bool {
         self.shuffle
@@ -359,6 +1012,8 @@ impl Progress {
     }
 
     /// The playback rate at the point in time that this Progress was constructed.
+    ///
+    /// This is the rate as reported by the player, even when it is `0.0`.
     pub fn playback_rate(&self) -> f64 {
         self.rate
     }
@@ -372,7 +1027,7 @@ impl Progress {
     ///
     /// This method will calculate the expected position of the track at the instant of the
     /// invocation using the [`initial_position`](Self::initial_position) and knowledge of how long ago that position was
-    /// determined.
+    /// determined. See [`position_at`](Self::position_at) for how this is calculated.
     ///
     /// **Note:** Some players might not support this and will return a bad position. Spotify is
     /// one such example. There is no reliable way of detecting problematic players, so it will be
@@ -382,7 +1037,118 @@ impl Progress {
     /// [`PlaybackStatus::Playing`] and if both are `0`, then it is likely that this client does not
     /// support positions.
     pub fn position(&self) -> Duration {
-        self.position + self.elapsed()
+        self.position_at(Instant::now())
+    }
+
+    /// Returns the expected position of the current track at the given [`Instant`].
+    ///
+    /// Useful to get a position that matches the timestamp of a rendered frame exactly.
+    ///
+    /// * While [`Playing`](PlaybackStatus::Playing), the time since this [`Progress`] was created
+    ///   is multiplied by the [`playback_rate`](Self::playback_rate) and added to the
+    ///   [`initial_position`](Self::initial_position). A rate of `0.0`, which some buggy players
+    ///   report, is treated as `1.0`.
+    /// * While [`Paused`](PlaybackStatus::Paused) or [`Stopped`](PlaybackStatus::Stopped), the
+    ///   position does not change.
+    /// * Instants before [`created_at`](Self::created_at) give the initial position.
+    /// * The position never goes past the [`length`](Self::length) of the track, when it is known.
+    /// * Instants after the [`Progress`] became stale (see [`is_stale_at`](Self::is_stale_at))
+    ///   give the position at the moment it became stale.
+    pub fn position_at(&self, instant: Instant) -> Duration {
+        match self.stale_after {
+            Some(limit) => self.extrapolate(instant.min(self.instant + limit)),
+            None => self.extrapolate(instant),
+        }
+    }
+
+    /// Returns [`true`] if the [`Progress`] is too old to calculate the position from, as of
+    /// `instant`.
+    ///
+    /// A snapshot of a playing player in a [`ProgressTracker`] becomes stale when it is older than
+    /// the limit set with [`ProgressTracker::with_stale_after`]. After that,
+    /// [`position_at`](Self::position_at) stops moving forward instead of extrapolating, so a
+    /// long stall in your program (or the machine being suspended) does not make the position
+    /// jump ahead. A [`ProgressTracker`] refreshes stale snapshots on its next tick. Snapshots of
+    /// paused or stopped players never become stale, as their position does not change, and neither
+    /// do snapshots that were not made by a [`ProgressTracker`] (like from
+    /// [`Progress::from_player`]).
+    pub fn is_stale_at(&self, instant: Instant) -> bool {
+        match self.stale_after {
+            Some(limit) => {
+                self.playback_status == PlaybackStatus::Playing
+                    && instant.saturating_duration_since(self.instant) > limit
+            }
+            None => false,
+        }
+    }
+
+    /// Returns [`true`] if the [`Progress`] is stale now. See [`is_stale_at`](Self::is_stale_at).
+    pub fn is_stale(&self) -> bool {
+        self.is_stale_at(Instant::now())
+    }
+
+    /// The position at `instant` without the limit for stale snapshots, or [`None`] if the
+    /// position is not known. Used for the final position of a track that was left.
+    pub(crate) fn last_position_at(&self, instant: Instant) -> Option<Duration> {
+        if self.position_known {
+            Some(self.extrapolate(instant))
+        } else {
+            None
+        }
+    }
+
+    /// Calculates the position at `instant`, without the limit of
+    /// [`is_stale_at`](Self::is_stale_at).
+    fn extrapolate(&self, instant: Instant) -> Duration {
+        let elapsed = match self.playback_status {
+            PlaybackStatus::Playing => instant.saturating_duration_since(self.instant),
+            PlaybackStatus::Paused | PlaybackStatus::Stopped => return self.clamp(self.position),
+        };
+
+        let rate = if self.rate == 0.0 || !self.rate.is_finite() {
+            1.0
+        } else {
+            self.rate
+        };
+
+        let offset_secs = elapsed.as_secs_f64() * rate.abs();
+        let offset = if offset_secs < Duration::MAX.as_secs_f64() {
+            Duration::from_secs_f64(offset_secs)
+        } else {
+            Duration::MAX
+        };
+
+        if rate < 0.0 {
+            self.clamp(self.position.saturating_sub(offset))
+        } else {
+            self.clamp(self.position.saturating_add(offset))
+        }
+    }
+
+    /// Returns how much of the current track is left to play, based on the
+    /// [`position`](Self::position).
+    ///
+    /// Returns [`None`] if the length of the track is unknown or zero.
+    pub fn remaining(&self) -> Option<Duration> {
+        let length = self.known_length()?;
+        Some(length.saturating_sub(self.position()))
+    }
+
+    /// Returns how much of the current track has been played, from `0.0` to `1.0`, based on the
+    /// [`position`](Self::position).
+    ///
+    /// Returns [`None`] if the length of the track is unknown or zero. Positions past the end of
+    /// the track, which live streams often report, give `1.0`.
+    pub fn fraction(&self) -> Option<f64> {
+        let length = self.known_length()?;
+        Some((self.position().as_secs_f64() / length.as_secs_f64()).min(1.0))
+    }
+
+    /// Returns how much of the current track has been played, from `0.0` to `100.0`.
+    ///
+    /// See [`fraction`](Self::fraction).
+    pub fn percentage(&self) -> Option<f64> {
+        self.fraction().map(|fraction| fraction * 100.0)
     }
 
     /// Returns the position that the current track was at when the [`Progress`] was created.
@@ -394,20 +1160,102 @@ impl Progress {
         self.position
     }
 
+    /// What the player allowed at the point in time that this Progress was constructed.
+    ///
+    /// If the player's properties could not be read, this is [`Capabilities::default`], where
+    /// nothing is allowed.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Where the current track is in the player's [`TrackList`], as a zero-based index and the
+    /// number of tracks in the list.
+    ///
+    /// This is only calculated by a [`ProgressTracker`] that has
+    /// [`with_track_list_position`](ProgressTracker::with_track_list_position) enabled. It is also
+    /// [`None`] if the player does not have a track list, or if the current track is not in it.
+    pub fn track_list_position(&self) -> Option<(usize, usize)> {
+        self.track_list_position
+    }
+
+    /// Returns [`true`] if the player seems to report a meaningful position.
+    ///
+    /// Some players (like web browsers and some streaming daemons) have a "Position" property that
+    /// is always zero. The position is considered meaningful if it could be read, and any of these
+    /// are true:
+    ///
+    /// * The player can seek ([`Capabilities::can_seek`]).
+    /// * The track has a length.
+    /// * A non-zero position has been seen for this track. A [`ProgressTracker`] remembers this
+    ///   between refreshes of the same track.
+    ///
+    /// This means that a player that is paused at the start of a track is only considered to
+    /// support the position if it can seek or knows the length of the track.
+    pub fn supports_position(&self) -> bool {
+        self.position_known
+            && (self.capabilities.can_seek || self.length().is_some() || self.position_moved)
+    }
+
+    /// Like [`position`](Self::position), but returns [`None`] if the player does not seem to
+    /// report a meaningful position; see [`supports_position`](Self::supports_position). Use this
+    /// to hide a progress bar instead of showing it stuck at `0:00`.
+    pub fn position_checked(&self) -> Option<Duration> {
+        if self.supports_position() {
+            Some(self.position())
+        } else {
+            None
+        }
+    }
+
+    /// Returns [`false`] if the player's position could not be read when this [`Progress`] was
+    /// created.
+    ///
+    /// This happens for players that do not implement the "Position" property (see
+    /// [`Player::supports_position`]). The position is then counted from zero.
+    pub fn position_known(&self) -> bool {
+        self.position_known
+    }
+
     /// The instant where this [`Progress`] was recorded.
     ///
+    /// This is when the player's position was read, before the rest of the state was loaded.
+    /// [`position`](Self::position) and [`position_at`](Self::position_at) interpolate from this
+    /// instant, so your own interpolation will match theirs if you start from here and the
+    /// [`initial_position`](Self::initial_position).
+    ///
     /// See: [`age`](Self::age).
     pub fn created_at(&self) -> &Instant {
         &self.instant
     }
 
-    /// Returns the age of the data as a [`Duration`].
+    /// Returns the age of the data as a [`Duration`], counted from
+    /// [`created_at`](Self::created_at).
     ///
     /// If the [`Progress`] has a high age it is more likely to be out of date.
     pub fn age(&self) -> Duration {
         self.instant.elapsed()
     }
 
+    /// How much two volumes or playback rates may differ while still being considered equal by
+    /// [`volume_equals`](Self::volume_equals) and [`rate_equals`](Self::rate_equals).
+    ///
+    /// [`PlayerEvents`](crate::PlayerEvents) uses the same comparison, so use this if you diff
+    /// stored values yourself and want to agree with it. [`Volume`](crate::Volume) compares with
+    /// the same tolerance.
+    pub const FLOAT_TOLERANCE: f64 = crate::FLOAT_TOLERANCE;
+
+    /// Returns [`true`] if both snapshots have the same volume, within
+    /// [`FLOAT_TOLERANCE`](Self::FLOAT_TOLERANCE).
+    pub fn volume_equals(&self, other: &Progress) -> bool {
+        floats_equal(self.current_volume, other.current_volume)
+    }
+
+    /// Returns [`true`] if both snapshots have the same playback rate, within
+    /// [`FLOAT_TOLERANCE`](Self::FLOAT_TOLERANCE).
+    pub fn rate_equals(&self, other: &Progress) -> bool {
+        floats_equal(self.rate, other.rate)
+    }
+
     /// Returns the player's volume as it was at the time of refresh.
     ///
     /// See: [`Player::get_volume`].
@@ -415,31 +1263,134 @@ impl Progress {
         self.current_volume
     }
 
-    fn elapsed(&self) -> Duration {
-        let elapsed_ms = match self.playback_status {
-            PlaybackStatus::Playing => {
-                DurationExtensions::as_millis(&self.age()) as f64 * self.rate
+    /// Returns the wall-clock time until the interpolated position reaches the next multiple of
+    /// `resolution`, or [`None`] if the position is not advancing.
+    fn time_until_next_boundary(&self, resolution: Duration) -> Option<Duration> {
+        match self.playback_status {
+            PlaybackStatus::Playing => {}
+            PlaybackStatus::Paused | PlaybackStatus::Stopped => return None,
+        }
+
+        let rate = if self.rate == 0.0 || !self.rate.is_finite() {
+            1.0
+        } else {
+            self.rate
+        };
+        let position = self.position().as_nanos();
+        let resolution = resolution.as_nanos();
+
+        let track_time_left = if rate > 0.0 {
+            let mut next = (position / resolution + 1) * resolution;
+            if let Some(length) = self.length() {
+                next = next.min(length.as_nanos());
+            }
+            next.checked_sub(position).filter(|left| *left > 0)?
+        } else {
+            match position % resolution {
+                0 if position == 0 => return None,
+                0 => resolution,
+                since_boundary => since_boundary,
             }
-            _ => 0.0,
         };
-        Duration::from_millis(elapsed_ms as u64)
+
+        // Round up, to wake up after the boundary rather than right before it.
+        let wall_time_left = (track_time_left as f64 / rate.abs()).ceil();
+        if wall_time_left < u64::MAX as f64 {
+            Some(Duration::from_nanos(wall_time_left as u64))
+        } else {
+            None
+        }
+    }
+
+    /// Moves the position to where the player seeked to, as of now.
+    pub(crate) fn apply_seek(&mut self, position: Duration) {
+        self.position = position;
+        self.position_known = true;
+        self.position_moved |= position > Duration::new(0, 0);
+        self.instant = Instant::now();
+    }
+
+    /// Remembers that the position has moved before, if `previous` is for the same track.
+    fn inherit_position_support(&mut self, previous: &Progress) {
+        if !is_track_change(&previous.metadata, &self.metadata) {
+            self.position_moved |= previous.position_moved;
+        }
+    }
+
+    fn known_length(&self) -> Option<Duration> {
+        self.length().filter(|length| *length > Duration::new(0, 0))
+    }
+
+    fn clamp(&self, position: Duration) -> Duration {
+        match self.length() {
+            Some(length) => position.min(length),
+            None => position,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Progress {
+    /// Builds a [`Progress`] for the current instant, for tests outside of this module.
+    pub(crate) fn for_test(
+        metadata: Metadata,
+        playback_status: PlaybackStatus,
+        shuffle: bool,
+        loop_status: LoopStatus,
+        current_volume: f64,
+        rate: f64,
+    ) -> Self {
+        Progress {
+            metadata,
+            playback_status,
+            raw_playback_status: Some(playback_status.to_string()),
+            playback_status_known: true,
+            shuffle,
+            loop_status,
+            instant: Instant::now(),
+            position: Duration::new(0, 0),
+            position_known: true,
+            rate,
+            current_volume,
+            track_list_position: None,
+            stale_after: None,
+            capabilities: Capabilities::default(),
+            position_moved: false,
+        }
+    }
+
+    /// Marks the playback status as one outside of the specification, reported as `raw`.
+    pub(crate) fn with_unknown_playback_status(mut self, raw: &str) -> Self {
+        self.playback_status = PlaybackStatus::Stopped;
+        self.raw_playback_status = Some(String::from(raw));
+        self.playback_status_known = false;
+        self
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::fake_player::connect_to_fake;
+    use crate::TrackID;
 
     #[test]
     fn it_progresses_position_when_playing_at_microseconds() {
         let progress = Progress {
             metadata: Metadata::new(String::from("id")),
             playback_status: PlaybackStatus::Playing,
+            raw_playback_status: None,
+            playback_status_known: true,
             shuffle: false,
             loop_status: LoopStatus::None,
             rate: 1.0,
             position: Duration::from_micros_ext(1),
+            position_known: true,
             current_volume: 0.0,
+            track_list_position: None,
+            stale_after: None,
+            capabilities: Capabilities::default(),
+            position_moved: false,
             instant: Instant::now(),
         };
 
@@ -452,14 +1403,625 @@ mod test {
         let progress = Progress {
             metadata: Metadata::new(String::from("id")),
             playback_status: PlaybackStatus::Paused,
+            raw_playback_status: None,
+            playback_status_known: true,
             shuffle: false,
             loop_status: LoopStatus::None,
             rate: 1.0,
             position: Duration::from_micros_ext(1336),
+            position_known: true,
             current_volume: 0.0,
+            track_list_position: None,
+            stale_after: None,
+            capabilities: Capabilities::default(),
+            position_moved: false,
             instant: Instant::now() - Duration::from_millis(500),
         };
 
         assert_eq!(progress.position(), progress.initial_position());
     }
+
+    #[test]
+    fn it_counts_from_zero_when_position_is_unknown() {
+        let progress = Progress {
+            metadata: Metadata::new(String::from("id")),
+            playback_status: PlaybackStatus::Paused,
+            raw_playback_status: None,
+            playback_status_known: true,
+            shuffle: false,
+            loop_status: LoopStatus::None,
+            rate: 1.0,
+            position: Duration::new(0, 0),
+            position_known: false,
+            current_volume: 0.0,
+            track_list_position: None,
+            stale_after: None,
+            capabilities: Capabilities::default(),
+            position_moved: false,
+            instant: Instant::now(),
+        };
+
+        assert!(!progress.position_known());
+        assert_eq!(progress.position(), Duration::new(0, 0));
+    }
+
+    fn playing_at_rate(rate: f64, length: Option<Duration>) -> Progress {
+        let mut values = HashMap::new();
+        if let Some(length) = length {
+            values.insert(
+                String::from("mpris:length"),
+                crate::MetadataValue::U64(DurationExtensions::as_micros(&length)),
+            );
+        }
+
+        Progress {
+            metadata: Metadata::from(values),
+            playback_status: PlaybackStatus::Playing,
+            raw_playback_status: None,
+            playback_status_known: true,
+            shuffle: false,
+            loop_status: LoopStatus::None,
+            rate,
+            position: Duration::from_secs(10),
+            position_known: true,
+            current_volume: 0.0,
+            track_list_position: None,
+            stale_after: None,
+            capabilities: Capabilities::default(),
+            position_moved: false,
+            instant: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn it_interpolates_with_playback_rate() {
+        let progress = playing_at_rate(1.5, None);
+        let at = progress.instant + Duration::from_secs(2);
+        assert_eq!(progress.position_at(at), Duration::from_secs(13));
+
+        let progress = playing_at_rate(0.0, None);
+        let at = progress.instant + Duration::from_secs(2);
+        assert_eq!(progress.position_at(at), Duration::from_secs(12));
+        assert_eq!(progress.playback_rate(), 0.0);
+    }
+
+    #[test]
+    fn it_clamps_interpolated_positions() {
+        let progress = playing_at_rate(1.0, Some(Duration::from_secs(11)));
+        let at = progress.instant + Duration::from_secs(5);
+        assert_eq!(progress.position_at(at), Duration::from_secs(11));
+
+        let progress = playing_at_rate(1.0, None);
+        let before = progress.instant - Duration::from_secs(5);
+        assert_eq!(progress.position_at(before), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn it_reports_track_and_status_changes() {
+        let old = Progress::for_test(
+            Metadata::new("/1"),
+            PlaybackStatus::Paused,
+            false,
+            LoopStatus::None,
+            1.0,
+            1.0,
+        );
+        let new = Progress::for_test(
+            Metadata::new("/2"),
+            PlaybackStatus::Playing,
+            false,
+            LoopStatus::None,
+            1.0,
+            1.0,
+        );
+
+        assert_eq!(
+            ProgressChanges::between(&old, &new),
+            ProgressChanges {
+                track_changed: true,
+                status_changed: true,
+                position_jumped: false,
+            }
+        );
+        assert_eq!(
+            ProgressChanges::between(&new, &new),
+            ProgressChanges::default()
+        );
+    }
+
+    #[test]
+    fn it_reports_position_jumps_within_a_track() {
+        let old = playing_at_rate(1.0, None);
+        let mut new = playing_at_rate(1.0, None);
+        new.instant = old.instant + Duration::from_secs(2);
+
+        new.position = Duration::from_millis(12_300);
+        assert!(!ProgressChanges::between(&old, &new).position_jumped);
+
+        new.position = Duration::from_secs(60);
+        assert!(ProgressChanges::between(&old, &new).position_jumped);
+
+        new.position_known = false;
+        assert!(!ProgressChanges::between(&old, &new).position_jumped);
+    }
+
+    #[test]
+    fn it_calculates_remaining_time_and_fractions() {
+        let mut progress = playing_at_rate(1.0, Some(Duration::from_secs(40)));
+        progress.playback_status = PlaybackStatus::Paused;
+
+        assert_eq!(progress.remaining(), Some(Duration::from_secs(30)));
+        assert_eq!(progress.fraction(), Some(0.25));
+        assert_eq!(progress.percentage(), Some(25.0));
+    }
+
+    #[test]
+    fn it_clamps_fractions_past_the_length() {
+        let mut progress = playing_at_rate(1.0, Some(Duration::from_secs(5)));
+        progress.playback_status = PlaybackStatus::Paused;
+
+        assert_eq!(progress.remaining(), Some(Duration::new(0, 0)));
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert_eq!(progress.percentage(), Some(100.0));
+    }
+
+    #[test]
+    fn it_needs_a_length_for_fractions() {
+        let progress = playing_at_rate(1.0, None);
+        assert_eq!(progress.remaining(), None);
+        assert_eq!(progress.fraction(), None);
+
+        let progress = playing_at_rate(1.0, Some(Duration::new(0, 0)));
+        assert_eq!(progress.percentage(), None);
+    }
+
+    fn at_position(position: Duration, rate: f64, length: Option<Duration>) -> Progress {
+        let mut progress = playing_at_rate(rate, length);
+        progress.position = position;
+        // Keep the interpolated position stable while the test runs.
+        progress.playback_status = PlaybackStatus::Paused;
+        progress
+    }
+
+    fn boundary(progress: &mut Progress, resolution: Duration) -> Option<Duration> {
+        progress.playback_status = PlaybackStatus::Playing;
+        progress.instant = Instant::now() + Duration::from_secs(3600);
+        progress.time_until_next_boundary(resolution)
+    }
+
+    #[test]
+    fn it_wakes_up_at_the_next_boundary() {
+        let second = Duration::from_secs(1);
+
+        let mut progress = at_position(Duration::from_millis(10_250), 1.0, None);
+        assert_eq!(
+            boundary(&mut progress, second),
+            Some(Duration::from_millis(750))
+        );
+
+        let mut progress = at_position(Duration::from_secs(10), 1.0, None);
+        assert_eq!(boundary(&mut progress, second), Some(second));
+    }
+
+    #[test]
+    fn it_scales_wake_ups_by_rate() {
+        let second = Duration::from_secs(1);
+
+        let mut progress = at_position(Duration::from_millis(10_250), 1.5, None);
+        assert_eq!(
+            boundary(&mut progress, second),
+            Some(Duration::from_millis(500))
+        );
+
+        // A rate change (as a new snapshot) moves the next wake-up.
+        let mut progress = at_position(Duration::from_millis(10_250), 0.5, None);
+        assert_eq!(
+            boundary(&mut progress, second),
+            Some(Duration::from_millis(1500))
+        );
+
+        let mut progress = at_position(Duration::from_millis(10_250), -1.0, None);
+        assert_eq!(
+            boundary(&mut progress, second),
+            Some(Duration::from_millis(250))
+        );
+
+        let mut progress = at_position(Duration::from_millis(10_250), 0.0, None);
+        assert_eq!(
+            boundary(&mut progress, second),
+            Some(Duration::from_millis(750))
+        );
+    }
+
+    #[test]
+    fn it_does_not_schedule_wake_ups_without_progress() {
+        let second = Duration::from_secs(1);
+
+        let progress = at_position(Duration::from_millis(10_250), 1.0, None);
+        assert_eq!(progress.time_until_next_boundary(second), None);
+
+        let mut progress = at_position(
+            Duration::from_millis(10_250),
+            1.0,
+            Some(Duration::from_millis(10_500)),
+        );
+        assert_eq!(
+            boundary(&mut progress, second),
+            Some(Duration::from_millis(250))
+        );
+
+        let mut progress = at_position(Duration::from_secs(11), 1.0, Some(Duration::from_secs(11)));
+        assert_eq!(boundary(&mut progress, second), None);
+
+        let mut progress = at_position(Duration::new(0, 0), -1.0, None);
+        assert_eq!(boundary(&mut progress, second), None);
+    }
+
+    #[test]
+    fn it_folds_seeks_into_the_snapshot() {
+        let mut progress = at_position(Duration::from_secs(10), 1.0, None);
+        progress.position_known = false;
+
+        progress.apply_seek(Duration::from_secs(42));
+        assert!(progress.position_known());
+        assert_eq!(progress.initial_position(), Duration::from_secs(42));
+        assert_eq!(progress.position(), Duration::from_secs(42));
+    }
+
+    #[test]
+    fn it_does_not_report_jumps_for_ordinary_progress() {
+        let old = playing_at_rate(1.0, None);
+        let mut new = playing_at_rate(1.0, None);
+        new.instant = old.instant + Duration::from_secs(30);
+        new.position = Duration::from_millis(40_200);
+        assert!(!ProgressChanges::between(&old, &new).position_jumped);
+
+        new.metadata = Metadata::new("/other");
+        new.position = Duration::new(0, 0);
+        assert!(!ProgressChanges::between(&old, &new).position_jumped);
+    }
+
+    #[test]
+    fn it_finds_the_current_track_in_the_track_list() {
+        let ids = ["/a", "/b", "/c"]
+            .iter()
+            .map(|id| TrackID::new(*id).unwrap())
+            .collect();
+        let list = TrackList::new(ids);
+
+        assert_eq!(
+            position_in_track_list(&list, &Metadata::new("/b")),
+            Some((1, 3))
+        );
+        assert_eq!(position_in_track_list(&list, &Metadata::new("/d")), None);
+        assert_eq!(position_in_track_list(&list, &Metadata::default()), None);
+    }
+
+    #[test]
+    fn it_does_not_support_positions_that_never_move() {
+        // Like a browser: Position is always 0, CanSeek is false and there is no length.
+        let mut progress = at_position(Duration::new(0, 0), 1.0, None);
+        assert!(!progress.supports_position());
+        assert_eq!(progress.position_checked(), None);
+
+        progress.capabilities.can_seek = true;
+        assert!(progress.supports_position());
+    }
+
+    #[test]
+    fn it_supports_positions_of_players_paused_at_the_start() {
+        let mut progress = at_position(Duration::new(0, 0), 1.0, Some(Duration::from_secs(300)));
+        progress.playback_status = PlaybackStatus::Paused;
+        assert!(progress.supports_position());
+        assert_eq!(progress.position_checked(), Some(Duration::new(0, 0)));
+    }
+
+    #[test]
+    fn it_supports_positions_that_moved_on_the_same_track() {
+        let mut moved = at_position(Duration::from_secs(3), 1.0, None);
+        moved.position_moved = true;
+        assert!(moved.supports_position());
+
+        let mut refreshed = at_position(Duration::new(0, 0), 1.0, None);
+        refreshed.inherit_position_support(&moved);
+        assert!(refreshed.supports_position());
+
+        let mut next_track = at_position(Duration::new(0, 0), 1.0, None);
+        next_track.metadata = Metadata::new("/other");
+        next_track.inherit_position_support(&moved);
+        assert!(!next_track.supports_position());
+    }
+
+    #[test]
+    fn it_does_not_support_unreadable_positions() {
+        let mut progress = at_position(Duration::new(0, 0), 1.0, Some(Duration::from_secs(300)));
+        progress.capabilities.can_seek = true;
+        progress.position_known = false;
+        assert!(!progress.supports_position());
+    }
+
+    #[test]
+    fn it_reports_where_positions_came_from() {
+        let progress = at_position(Duration::from_secs(5), 1.0, None);
+
+        let fetched = Position::of(&progress, true);
+        assert_eq!(fetched.value, Duration::from_secs(5));
+        assert_eq!(fetched.source, PositionSource::Fetched);
+        assert_eq!(fetched.fetched_at, *progress.created_at());
+
+        assert_eq!(
+            Position::of(&progress, false).source,
+            PositionSource::Interpolated
+        );
+
+        let mut unknown = progress;
+        unknown.position_known = false;
+        assert_eq!(
+            Position::of(&unknown, true).source,
+            PositionSource::Interpolated
+        );
+    }
+
+    #[test]
+    fn it_can_be_sent_to_other_threads() {
+        fn assert_snapshot<T: Clone + Send + Sync + 'static>(_: &T) {}
+
+        let progress = at_position(Duration::from_secs(5), 1.0, None);
+        assert_snapshot(&progress);
+
+        let copy = progress.clone();
+        let position = std::thread::spawn(move || copy.initial_position())
+            .join()
+            .unwrap();
+        assert_eq!(position, progress.initial_position());
+    }
+
+    #[test]
+    fn it_stops_extrapolating_stale_snapshots() {
+        let mut progress = playing_at_rate(1.0, None);
+        progress.stale_after = Some(Duration::from_secs(5));
+        let created = progress.instant;
+
+        assert!(!progress.is_stale_at(created + Duration::from_secs(5)));
+        assert_eq!(
+            progress.position_at(created + Duration::from_secs(4)),
+            Duration::from_secs(14)
+        );
+
+        // The machine was suspended for an hour.
+        let resumed = created + Duration::from_secs(3600);
+        assert!(progress.is_stale_at(resumed));
+        assert_eq!(progress.position_at(resumed), Duration::from_secs(15));
+
+        progress.stale_after = None;
+        assert!(!progress.is_stale_at(resumed));
+        assert_eq!(progress.position_at(resumed), Duration::from_secs(3610));
+    }
+
+    #[test]
+    fn it_derives_the_stale_limit_from_the_interval() {
+        assert_eq!(
+            default_stale_after(Duration::from_millis(100)),
+            MIN_STALE_AFTER
+        );
+        assert_eq!(
+            default_stale_after(Duration::from_secs(10)),
+            Duration::from_secs(20)
+        );
+    }
+
+    #[test]
+    fn it_never_considers_paused_snapshots_stale() {
+        let mut progress = at_position(Duration::from_secs(10), 1.0, None);
+        progress.stale_after = Some(Duration::from_secs(5));
+
+        let later = progress.instant + Duration::from_secs(3600);
+        assert!(!progress.is_stale_at(later));
+        assert_eq!(progress.position_at(later), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn it_compares_volumes_and_rates_with_a_tolerance() {
+        let base = Progress::for_test(
+            Metadata::new("/1"),
+            PlaybackStatus::Playing,
+            false,
+            LoopStatus::None,
+            0.5,
+            1.0,
+        );
+        let nudged = Progress::for_test(
+            Metadata::new("/1"),
+            PlaybackStatus::Playing,
+            false,
+            LoopStatus::None,
+            0.5 + 1e-6,
+            1.0 - 1e-5,
+        );
+        let changed = Progress::for_test(
+            Metadata::new("/1"),
+            PlaybackStatus::Playing,
+            false,
+            LoopStatus::None,
+            0.5 + 1e-3,
+            1.0 + 1e-3,
+        );
+
+        assert!(base.current_volume() != nudged.current_volume());
+        assert!(base.playback_rate() != nudged.playback_rate());
+        assert!(base.volume_equals(&nudged));
+        assert!(base.rate_equals(&nudged));
+        assert!(!base.volume_equals(&changed));
+        assert!(!base.rate_equals(&changed));
+    }
+
+    fn properties(values: &[(&str, bool)]) -> HashMap<String, Value> {
+        values
+            .iter()
+            .map(|(key, value)| (String::from(*key), Value::Bool(*value)))
+            .collect()
+    }
+
+    #[test]
+    fn it_reads_capabilities_from_properties() {
+        let capabilities = Capabilities::from_properties(&properties(&[
+            ("CanControl", true),
+            ("CanGoNext", false),
+            ("CanGoPrevious", true),
+            ("CanPlay", true),
+            ("CanPause", true),
+            ("CanSeek", false),
+        ]));
+
+        assert!(capabilities.can_control());
+        assert!(!capabilities.can_go_next());
+        assert!(capabilities.can_go_previous());
+        assert!(capabilities.can_play());
+        assert!(capabilities.can_pause());
+        assert!(!capabilities.can_seek());
+    }
+
+    #[test]
+    fn it_defaults_missing_capabilities_to_can_control() {
+        let capabilities = Capabilities::from_properties(&properties(&[("CanControl", true)]));
+        assert!(capabilities.can_go_next());
+        assert!(capabilities.can_seek());
+
+        let capabilities = Capabilities::from_properties(&properties(&[
+            ("CanControl", false),
+            ("CanGoNext", true),
+        ]));
+        assert!(!capabilities.can_go_next());
+
+        let capabilities = Capabilities::from_properties(&properties(&[("CanSeek", true)]));
+        assert_eq!(capabilities, Capabilities::default());
+    }
+
+    #[test]
+    fn it_reads_progress_with_a_single_call() {
+        let (fake, player) = connect_to_fake("progress_single_call");
+        let mut metadata = Metadata::new("/org/example/Track/1");
+        metadata.insert("mpris:length", Value::I64(180_000_000));
+        fake.set_metadata(metadata);
+        fake.set_playback_status(PlaybackStatus::Paused);
+        fake.set_position(Duration::from_secs(30));
+        fake.set_property("Volume", Value::F64(0.5));
+        fake.set_property("LoopStatus", Value::from("Playlist"));
+
+        let before = fake.property_calls().len();
+        let progress = Progress::from_player(&player).unwrap();
+        assert_eq!(fake.property_calls()[before..], ["GetAll"]);
+
+        assert_eq!(progress.playback_status(), PlaybackStatus::Paused);
+        assert_eq!(progress.initial_position(), Duration::from_secs(30));
+        assert_eq!(progress.length(), Some(Duration::from_secs(180)));
+        assert_eq!(progress.loop_status(), LoopStatus::Playlist);
+        assert!((progress.current_volume() - 0.5).abs() < f64::EPSILON);
+        assert!(progress.capabilities().can_seek());
+    }
+
+    #[test]
+    fn it_reads_missing_progress_properties_individually() {
+        let (fake, player) = connect_to_fake("progress_missing");
+        fake.remove_property("PlaybackStatus");
+        fake.remove_property("Shuffle");
+        fake.remove_property("Volume");
+
+        let before = fake.property_calls().len();
+        // The status is required, so the individual read fails just like it would for a real
+        // player that does not have it.
+        assert!(Progress::from_player(&player).is_err());
+        assert_eq!(fake.property_calls()[before..], ["GetAll", "Get"]);
+
+        fake.set_playback_status(PlaybackStatus::Playing);
+        fake.remove_property("Position");
+        let progress = Progress::from_player(&player).unwrap();
+        assert_eq!(progress.playback_status(), PlaybackStatus::Playing);
+        assert!(!progress.shuffle());
+        assert!((progress.current_volume() - 1.0).abs() < f64::EPSILON);
+        assert!(!progress.position_known());
+    }
+
+    #[test]
+    fn it_decodes_positions_leniently_unless_strict() {
+        let (fake, mut player) = connect_to_fake("progress_position_decoding");
+        fake.set_property("Position", Value::from("12"));
+        let progress = Progress::from_player(&player).unwrap();
+        assert_eq!(progress.initial_position(), Duration::from_micros(12));
+
+        fake.set_property("Position", Value::from("soon"));
+        let progress = Progress::from_player(&player).unwrap();
+        assert!(!progress.position_known());
+        assert_eq!(player.decode_warnings()[0].property, "Position");
+
+        player.set_strict(true);
+        assert!(Progress::from_player(&player).is_err());
+    }
+
+    #[test]
+    fn it_refreshes_right_away_when_forced() {
+        let (fake, player) = connect_to_fake("progress_force_refresh");
+        fake.set_playback_status(PlaybackStatus::Paused);
+        let mut tracker = player.track_progress(60_000).unwrap();
+
+        fake.set_playback_status(PlaybackStatus::Stopped);
+        tracker.force_refresh().unwrap();
+        assert_eq!(
+            tracker.last_progress.playback_status(),
+            PlaybackStatus::Stopped
+        );
+
+        fake.set_playback_status(PlaybackStatus::Playing);
+        tracker.request_refresh();
+        tracker.request_refresh();
+        assert_eq!(
+            tracker.last_progress.playback_status(),
+            PlaybackStatus::Stopped
+        );
+        let tick = tracker
+            .try_tick()
+            .expect("requested refresh should not wait");
+        assert_eq!(tick.progress.playback_status(), PlaybackStatus::Playing);
+        assert!(tracker.try_tick().is_none());
+
+        drop(fake);
+        assert!(tracker.force_refresh().is_err());
+    }
+
+    #[test]
+    fn it_does_not_limit_the_age_of_standalone_snapshots() {
+        let (fake, player) = connect_to_fake("progress_standalone");
+        fake.set_playback_status(PlaybackStatus::Playing);
+
+        let progress = Progress::from_player(&player).unwrap();
+        assert!(!progress.is_stale_at(progress.instant + Duration::from_secs(3600)));
+
+        let tracker = player.track_progress(10_000).unwrap();
+        let progress = &tracker.last_progress;
+        assert!(!progress.is_stale_at(progress.instant + Duration::from_secs(20)));
+        assert!(progress.is_stale_at(progress.instant + Duration::from_secs(21)));
+    }
+
+    #[test]
+    fn it_does_not_retry_stale_refreshes_on_every_tick() {
+        let (fake, player) = connect_to_fake("progress_stale_retry");
+        fake.set_playback_status(PlaybackStatus::Playing);
+        let mut tracker = player
+            .track_progress(10)
+            .unwrap()
+            .with_stale_after(Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(5));
+
+        // Only the first refresh fails, so a retry would succeed and show up in the calls.
+        fake.fail_next_calls(1, "AccessDenied");
+        let before = fake.property_calls().len();
+        let tick = tracker.try_tick().expect("stale snapshots are refreshed");
+        assert!(tick.refresh_error.is_some());
+        for _ in 0..20 {
+            std::thread::sleep(Duration::from_millis(10));
+            tracker.try_tick();
+        }
+        assert!(tracker.last_progress.is_stale());
+        assert_eq!(fake.property_calls().len(), before);
+    }
 }
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::metadata::Value;
+use crate::pooled_connection::Subscription;
+
+/// How a [`Player`](crate::Player) caches the properties that it reads from the player.
+///
+/// See [`Player::set_cache_policy`](crate::Player::set_cache_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Read every property from the player every time. This is the default.
+    Off,
+
+    /// Keep properties until the player sends a `PropertiesChanged` signal.
+    ///
+    /// Values are only read from the bus again after the player said that something changed,
+    /// which makes repeated reads (like checking capabilities every frame) free. Players that do
+    /// not send `PropertiesChanged` for every change are not a good fit for this.
+    SignalInvalidated,
+
+    /// Keep properties for this long after they were read.
+    TimeToLive(Duration),
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy::Off
+    }
+}
+
+/// Cached values of the properties of one player, from the `org.mpris.MediaPlayer2.Player` and
+/// `org.mpris.MediaPlayer2` interfaces. Both interfaces use different property names, so the names
+/// are used as keys directly.
+#[derive(Debug, Default)]
+pub(crate) struct PropertyCache {
+    policy: CachePolicy,
+    values: HashMap<String, (Instant, Value)>,
+
+    /// Watches the player's signals, to see when values must be read again. Only set for
+    /// [`CachePolicy::SignalInvalidated`]. It only keeps a flag, so signals don't pile up while
+    /// nothing is read from the cache.
+    subscription: Option<Subscription>,
+}
+
+impl PropertyCache {
+    pub(crate) fn new(policy: CachePolicy, subscription: Option<Subscription>) -> Self {
+        PropertyCache {
+            policy,
+            values: HashMap::new(),
+            subscription,
+        }
+    }
+
+    pub(crate) fn policy(&self) -> CachePolicy {
+        self.policy
+    }
+
+    /// Returns the cached value of the property, if it is still valid.
+    pub(crate) fn get(&mut self, name: &str) -> Option<&Value> {
+        match self.policy {
+            CachePolicy::Off => return None,
+            CachePolicy::SignalInvalidated => self.process_signals(),
+            CachePolicy::TimeToLive(ttl) => {
+                let expired = self
+                    .values
+                    .get(name)
+                    .map_or(false, |(read_at, _)| read_at.elapsed() >= ttl);
+                if expired {
+                    self.values.remove(name);
+                }
+            }
+        }
+
+        self.values.get(name).map(|(_, value)| value)
+    }
+
+    /// Stores a value that was just read from the player.
+    pub(crate) fn insert(&mut self, name: &str, value: Value) {
+        if self.policy != CachePolicy::Off && is_cacheable(name) {
+            self.values
+                .insert(String::from(name), (Instant::now(), value));
+        }
+    }
+
+    /// Forgets every value if the player has sent a `PropertiesChanged` signal or quit since the
+    /// last call.
+    fn process_signals(&mut self) {
+        let subscription = match self.subscription {
+            Some(ref subscription) => subscription,
+            None => return,
+        };
+
+        subscription.connection().process_events_nonblocking();
+        if subscription.take_changed() {
+            self.values.clear();
+        }
+    }
+}
+
+/// `Position` changes all the time without any signal, so it is always read from the player.
+fn is_cacheable(name: &str) -> bool {
+    name != "Position"
+}
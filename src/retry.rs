@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use crate::{dbus_error_kind, CallOperation, DBusError, DBusErrorKind};
+
+/// When a [`Player`](crate::Player) repeats calls that failed.
+///
+/// See [`Player::set_retry_policy`](crate::Player::set_retry_policy).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use mpris::{DBusErrorKind, PlayerFinder, RetryPolicy};
+/// # use std::time::Duration;
+/// let mut player = PlayerFinder::new().unwrap().find_active().unwrap();
+/// player.set_retry_policy(RetryPolicy {
+///     max_attempts: 3,
+///     backoff: Duration::from_millis(100),
+///     retry_on: &[DBusErrorKind::Timeout, DBusErrorKind::Disconnected],
+///     ..RetryPolicy::default()
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times a call is made in total, including the first attempt. Both `0` and `1`
+    /// mean that calls are never repeated.
+    pub max_attempts: u32,
+
+    /// How long to wait before the first retry. The wait doubles for every retry after that.
+    pub backoff: Duration,
+
+    /// The kinds of errors to retry. Other errors are returned right away.
+    pub retry_on: &'static [DBusErrorKind],
+
+    /// Also retry calls that do something different when they are made twice, like `Next`,
+    /// `Seek` or `PlayPause`.
+    ///
+    /// A call that timed out might still have reached the player, so repeating one of these can
+    /// skip two tracks instead of one. Off by default.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    /// Never retries.
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            backoff: Duration::from_millis(100),
+            retry_on: &[],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+/// If making a call twice has the same effect as making it once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Idempotent {
+    Yes,
+    No,
+}
+
+impl RetryPolicy {
+    /// Returns how long to wait before the next attempt, after `attempt` attempts of a call have
+    /// failed and the last one failed with `error`. Returns [`None`] to give up.
+    pub(crate) fn delay_before_retry<E: RetryableError>(
+        &self,
+        attempt: u32,
+        error: &E,
+        idempotent: Idempotent,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts
+            || (idempotent == Idempotent::No && !self.retry_non_idempotent)
+        {
+            return None;
+        }
+
+        let kind = error.retry_kind()?;
+        if !self.retry_on.contains(&kind) {
+            return None;
+        }
+
+        2u32.checked_pow(attempt - 1)
+            .and_then(|factor| self.backoff.checked_mul(factor))
+    }
+}
+
+/// The method or property that a call is made for, to name it in errors and instrumentation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Member<'a> {
+    pub(crate) operation: CallOperation,
+    pub(crate) name: &'a str,
+}
+
+impl<'a> Member<'a> {
+    /// Reading the property `name`.
+    pub(crate) fn get(name: &'a str) -> Self {
+        Member {
+            operation: CallOperation::Get,
+            name,
+        }
+    }
+
+    /// Writing the property `name`.
+    pub(crate) fn set(name: &'a str) -> Self {
+        Member {
+            operation: CallOperation::Set,
+            name,
+        }
+    }
+
+    /// Calling the method `name`.
+    pub(crate) fn call(name: &'a str) -> Self {
+        Member {
+            operation: CallOperation::Call,
+            name,
+        }
+    }
+}
+
+/// Errors that a [`RetryPolicy`] can decide about.
+pub(crate) trait RetryableError {
+    /// The kind of the error, if it came from D-Bus.
+    fn retry_kind(&self) -> Option<DBusErrorKind>;
+}
+
+impl RetryableError for dbus::Error {
+    fn retry_kind(&self) -> Option<DBusErrorKind> {
+        self.name().map(dbus_error_kind)
+    }
+}
+
+impl RetryableError for DBusError {
+    fn retry_kind(&self) -> Option<DBusErrorKind> {
+        match self {
+            DBusError::TransportError(error) | DBusError::CallFailed { source: error, .. } => {
+                error.retry_kind()
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeout() -> dbus::Error {
+        dbus::Error::new_custom("org.freedesktop.DBus.Error.NoReply", "Busy")
+    }
+
+    #[test]
+    fn it_never_retries_by_default() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay_before_retry(1, &timeout(), Idempotent::Yes),
+            None
+        );
+    }
+
+    #[test]
+    fn it_doubles_the_backoff_until_attempts_run_out() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(10),
+            retry_on: &[DBusErrorKind::Timeout],
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(
+            policy.delay_before_retry(1, &timeout(), Idempotent::Yes),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(
+            policy.delay_before_retry(2, &timeout(), Idempotent::Yes),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(
+            policy.delay_before_retry(3, &timeout(), Idempotent::Yes),
+            None
+        );
+    }
+
+    #[test]
+    fn it_only_retries_the_listed_errors() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            retry_on: &[DBusErrorKind::Timeout],
+            ..RetryPolicy::default()
+        };
+        let unknown = dbus::Error::new_custom("org.freedesktop.DBus.Error.UnknownMethod", "No");
+
+        assert_eq!(
+            policy.delay_before_retry(1, &unknown, Idempotent::Yes),
+            None
+        );
+        assert_eq!(
+            policy.delay_before_retry(1, &DBusError::Miscellaneous(String::new()), Idempotent::Yes),
+            None
+        );
+    }
+
+    #[test]
+    fn it_only_retries_non_idempotent_calls_when_allowed() {
+        let mut policy = RetryPolicy {
+            max_attempts: 3,
+            retry_on: &[DBusErrorKind::Timeout],
+            ..RetryPolicy::default()
+        };
+        assert_eq!(
+            policy.delay_before_retry(1, &timeout(), Idempotent::No),
+            None
+        );
+
+        policy.retry_non_idempotent = true;
+        assert!(policy
+            .delay_before_retry(1, &timeout(), Idempotent::No)
+            .is_some());
+    }
+}
@@ -0,0 +1,100 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use crate::{DBusError, TrackID};
+
+/// How [`Player::seek_to`](crate::Player::seek_to) moved the position, as returned by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeekMethod {
+    /// The position was set with `SetPosition`, using the current track ID.
+    SetPosition,
+
+    /// The track had no usable track ID, or the player rejected `SetPosition`, so the position
+    /// was moved with a relative `Seek` instead. Contains the offset that was sent, in
+    /// microseconds, as calculated from the position that was read just before.
+    RelativeSeek {
+        /// The offset that was sent with `Seek`, in microseconds.
+        offset_in_us: i64,
+    },
+}
+
+/// The result of
+/// [`Player::set_current_position_verified`](crate::Player::set_current_position_verified).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetPositionOutcome {
+    /// The position was set on the track that is still playing.
+    Sent,
+
+    /// The player changed tracks between reading the track ID and setting the position, so the
+    /// call was for a track that is no longer current. Players ignore such calls, as the
+    /// specification requires.
+    StalePosition {
+        /// The track that the position was meant for.
+        requested_track: TrackID,
+
+        /// The track that is playing now, if it has a valid track ID.
+        current_track: Option<TrackID>,
+    },
+}
+
+/// What [`Player::seek_to_with`](crate::Player::seek_to_with) does with a position past the end
+/// of the track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeekBounds {
+    /// Seek to the end of the track instead. This is what
+    /// [`Player::seek_to`](crate::Player::seek_to) does.
+    Clamp,
+
+    /// Return an [`Err`] without seeking.
+    Reject,
+}
+
+impl Default for SeekBounds {
+    fn default() -> Self {
+        SeekBounds::Clamp
+    }
+}
+
+/// Returns the position (in microseconds) to seek to for `position`, checked against the
+/// `length` of the track (in microseconds) if it is known.
+pub(crate) fn bounded_target(
+    position: Duration,
+    length: Option<u64>,
+    bounds: SeekBounds,
+) -> Result<u64, DBusError> {
+    let target = u64::try_from(position.as_micros()).unwrap_or(u64::MAX);
+    match length {
+        Some(length) if target > length => match bounds {
+            SeekBounds::Clamp => Ok(length),
+            SeekBounds::Reject => Err(DBusError::Miscellaneous(format!(
+                "Can not seek to {:?}, as the track is only {:?} long",
+                position,
+                Duration::from_micros(length)
+            ))),
+        },
+        _ => Ok(target),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checks_targets_against_the_track_length() {
+        let position = Duration::from_secs(83);
+        assert_eq!(
+            bounded_target(position, Some(200_000_000), SeekBounds::Reject).unwrap(),
+            83_000_000
+        );
+        assert_eq!(
+            bounded_target(position, None, SeekBounds::Reject).unwrap(),
+            83_000_000
+        );
+        assert_eq!(
+            bounded_target(position, Some(60_000_000), SeekBounds::Clamp).unwrap(),
+            60_000_000
+        );
+        assert!(bounded_target(position, Some(60_000_000), SeekBounds::Reject).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use serde::de::{Deserialize, Deserializer, Error};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{CapabilityReport, LoopStatus, PlaybackStatus};
+
+impl Serialize for PlaybackStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PlaybackStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for LoopStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for LoopStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for CapabilityReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut report = serializer.serialize_struct("CapabilityReport", 6)?;
+        report.serialize_field("interfaces", &self.interfaces)?;
+        report.serialize_field("properties", &self.properties)?;
+        report.serialize_field("optional_properties", &self.optional_properties)?;
+        report.serialize_field("enabled_flags", &self.enabled_flags)?;
+        report.serialize_field("declares_seeked", &self.declares_seeked)?;
+        report.serialize_field("failed_probes", &self.failed_probes)?;
+        report.end()
+    }
+}
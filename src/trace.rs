@@ -0,0 +1,117 @@
+//! Instrumentation of D-Bus traffic with [`tracing`](https://docs.rs/tracing), behind the
+//! `tracing` feature.
+//!
+//! Without the feature every function here does nothing, so call sites do not need their own
+//! `#[cfg]` attributes.
+
+use dbus::Message;
+
+use crate::decode::DecodeWarning;
+
+/// A span around one call to a player, including its retries.
+pub(crate) struct CallSpan {
+    /// Leaves the span when dropped.
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    started: std::time::Instant,
+}
+
+impl CallSpan {
+    /// Enters a span for calling `member`, the name of a method or of a property that is read or
+    /// written.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn enter(bus_name: &str, member: &str) -> Self {
+        CallSpan {
+            #[cfg(feature = "tracing")]
+            _span: tracing::debug_span!("mpris_call", bus_name, member).entered(),
+            #[cfg(feature = "tracing")]
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Records the outcome and duration of the call, and leaves the span.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn finish(self, succeeded: bool, attempts: u32) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            duration = ?self.started.elapsed(),
+            succeeded,
+            attempts,
+            "D-Bus call finished"
+        );
+    }
+}
+
+/// Records a signal that was received from a player.
+///
+/// Only the names of changed properties and metadata keys are recorded, never their values, as
+/// metadata can be large (like embedded lyrics or cover art).
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn signal(message: &Message) {
+    #[cfg(feature = "tracing")]
+    {
+        if !tracing::enabled!(tracing::Level::DEBUG) {
+            return;
+        }
+
+        let sender = message.sender();
+        let interface = message.interface();
+        let member = message.member();
+        tracing::debug!(
+            sender = sender.as_deref().unwrap_or(""),
+            interface = interface.as_deref().unwrap_or(""),
+            member = member.as_deref().unwrap_or(""),
+            keys = ?summary::changed_keys(message),
+            "Received signal"
+        );
+    }
+}
+
+/// Records that a property value could not be decoded, and was replaced with a default.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn decode_fallback(warning: &DecodeWarning) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        property = %warning.property,
+        value = ?warning.value,
+        fallback = %warning.fallback,
+        "Could not decode property, using a default"
+    );
+}
+
+#[cfg(feature = "tracing")]
+mod summary {
+    use dbus::arg::{Array, Dict, Iter, Variant};
+    use dbus::Message;
+
+    /// The names of the properties or metadata keys that a signal carries.
+    pub(super) fn changed_keys(message: &Message) -> Vec<String> {
+        let mut iter = message.iter_init();
+        match message.member().as_deref() {
+            Some("PropertiesChanged") => {
+                // Skip the interface name.
+                iter.next();
+                let mut keys = dict_keys(&mut iter);
+                iter.next();
+                if let Ok(invalidated) = iter.read::<Array<'_, &str, _>>() {
+                    keys.extend(invalidated.map(String::from));
+                }
+                keys
+            }
+            Some("TrackAdded") => dict_keys(&mut iter),
+            Some("TrackMetadataChanged") => {
+                // Skip the track ID.
+                iter.next();
+                dict_keys(&mut iter)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn dict_keys(iter: &mut Iter<'_>) -> Vec<String> {
+        iter.get::<Dict<'_, &str, Variant<Iter<'_>>, _>>()
+            .map(|dict| dict.map(|(key, _)| String::from(key)).collect())
+            .unwrap_or_default()
+    }
+}
@@ -1,8 +1,10 @@
 use super::{DBusError, Metadata, Player};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fmt;
 use std::iter::{FromIterator, IntoIterator};
+use std::str::FromStr;
 use thiserror::Error;
 
 pub(crate) const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
@@ -30,6 +32,50 @@ pub(crate) const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub struct TrackID(pub(crate) String);
 
+/// A string that is not a valid [`TrackID`].
+///
+/// Some players put other identifiers in `mpris:trackid`, like `spotify:track:xyz`. These can
+/// still be read from [`Metadata`], but can never be sent back to the [`Player`].
+///
+/// ```rust
+/// # use mpris::{InvalidTrackIDReason, TrackID};
+/// let error = "spotify:track:xyz".parse::<TrackID>().unwrap_err();
+/// assert_eq!(error.value, "spotify:track:xyz");
+/// assert_eq!(error.reason, InvalidTrackIDReason::MissingLeadingSlash);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Invalid track ID {value:?}: {reason}")]
+pub struct InvalidTrackID {
+    /// The rejected string.
+    pub value: String,
+
+    /// Why the string is not a valid D-Bus object path.
+    pub reason: InvalidTrackIDReason,
+}
+
+/// Why a string is not a valid [`TrackID`]. See [`InvalidTrackID`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[non_exhaustive]
+pub enum InvalidTrackIDReason {
+    /// The string is empty.
+    #[error("it is empty")]
+    Empty,
+
+    /// The string does not start with a `/`.
+    #[error("it does not start with a slash")]
+    MissingLeadingSlash,
+
+    /// The string has two slashes in a row, or ends with a slash.
+    #[error("it has an empty path segment")]
+    EmptySegment,
+
+    /// The string contains a character other than ASCII letters, digits, `_` and `/`.
+    #[error(
+        "it contains {0:?}, but only ASCII letters, digits, underscores and slashes are allowed"
+    )]
+    InvalidCharacter(char),
+}
+
 /// Represents a [`MediaPlayer2.TrackList`][track_list].
 ///
 /// This type offers an iterator of the track's metadata, when provided a [`Player`] instance that
@@ -50,6 +96,7 @@ pub struct TrackList {
 /// This is mostly [`DBusError`] with the extra possibility of borrow errors of the internal metadata
 /// cache.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum TrackListError {
     /// Something went wrong with the D-Bus communication. See the [`DBusError`] type.
     #[error("D-Bus communication failed: {0}")]
@@ -62,6 +109,8 @@ pub enum TrackListError {
     BorrowError(String),
 }
 
+/// Iterates the tracks of a [`TrackList`] with their [`Metadata`]. See
+/// [`TrackList::metadata_iter`].
 #[derive(Debug)]
 pub struct MetadataIter {
     order: Vec<TrackID>,
@@ -112,14 +161,11 @@ impl TrackID {
     /// use mpris::TrackID;
     /// let id = TrackID::new("/dbus/path/id").expect("Parse error");
     /// ```
+    ///
+    /// Use [`str::parse`] or [`TrackID::try_from`] to get an [`InvalidTrackID`] error instead of a
+    /// message.
     pub fn new<S: Into<String>>(id: S) -> Result<Self, String> {
-        let id = id.into();
-        // Validate the ID by constructing a dbus::Path.
-        if let Err(error) = dbus::Path::new(id.as_str()) {
-            Err(error)
-        } else {
-            Ok(TrackID(id))
-        }
+        TrackID::try_from(id.into()).map_err(|error| error.to_string())
     }
 
     /// Return a new [`TrackID`] that matches the MPRIS standard for the "No track" sentinel value.
@@ -150,6 +196,60 @@ impl TrackID {
     }
 }
 
+impl FromStr for TrackID {
+    type Err = InvalidTrackID;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        TrackID::try_from(id.to_owned())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for TrackID {
+    type Error = InvalidTrackID;
+
+    fn try_from(id: &'a str) -> Result<Self, Self::Error> {
+        id.parse()
+    }
+}
+
+impl TryFrom<String> for TrackID {
+    type Error = InvalidTrackID;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        match validate_object_path(&id) {
+            Ok(()) => Ok(TrackID(id)),
+            Err(reason) => Err(InvalidTrackID { value: id, reason }),
+        }
+    }
+}
+
+/// Checks `path` against the D-Bus rules for object paths.
+fn validate_object_path(path: &str) -> Result<(), InvalidTrackIDReason> {
+    if path.is_empty() {
+        return Err(InvalidTrackIDReason::Empty);
+    }
+    if !path.starts_with('/') {
+        return Err(InvalidTrackIDReason::MissingLeadingSlash);
+    }
+    if path == "/" {
+        return Ok(());
+    }
+
+    for segment in path[1..].split('/') {
+        if segment.is_empty() {
+            return Err(InvalidTrackIDReason::EmptySegment);
+        }
+        if let Some(c) = segment
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && *c != '_')
+        {
+            return Err(InvalidTrackIDReason::InvalidCharacter(c));
+        }
+    }
+
+    Ok(())
+}
+
 impl AsRef<str> for TrackID {
     fn as_ref(&self) -> &str {
         &self.0
@@ -285,7 +385,12 @@ impl TrackList {
         if let Some(new_id) = new_metadata.track_id() {
             if let Some(index) = self.index_of_id(old_id) {
                 self.ids[index] = new_id.to_owned();
-                self.change_metadata(|cache| cache.insert(new_id.to_owned(), new_metadata));
+                self.change_metadata(|cache| {
+                    if *old_id != new_id {
+                        cache.remove(old_id);
+                    }
+                    cache.insert(new_id.to_owned(), new_metadata)
+                });
 
                 return Some(new_id);
             }
@@ -311,6 +416,45 @@ impl TrackList {
         })
     }
 
+    /// Returns the cached [`Metadata`] for a track, without asking the player.
+    ///
+    /// The cache is filled by [`metadata_iter`](Self::metadata_iter),
+    /// [`metadata_for`](Self::metadata_for) and signals from the player, and entries are removed
+    /// or replaced when the player signals that a track was removed or changed.
+    pub fn metadata_cached(&self, id: &TrackID) -> Option<Metadata> {
+        self.metadata_cache
+            .try_borrow()
+            .ok()
+            .and_then(|cache| cache.get(id).cloned())
+    }
+
+    /// Returns the [`Metadata`] for each of the given tracks, in the same order.
+    ///
+    /// Only the tracks that are not in the cache are loaded from the player, in a single call.
+    /// Loaded metadata is added to the cache.
+    pub fn metadata_for(
+        &self,
+        player: &Player,
+        ids: &[TrackID],
+    ) -> Result<Vec<Metadata>, TrackListError> {
+        // We only have a &self reference, so fail if we cannot borrow.
+        let mut cache = self.metadata_cache.try_borrow_mut()?;
+
+        let mut missing: Vec<TrackID> = Vec::new();
+        for id in ids {
+            if !cache.contains_key(id) && !missing.contains(id) {
+                missing.push(id.clone());
+            }
+        }
+
+        if !missing.is_empty() {
+            let metadata = player.get_tracks_metadata(&missing)?;
+            cache.extend(missing.into_iter().zip(metadata));
+        }
+
+        Ok(ids.iter().filter_map(|id| cache.get(id).cloned()).collect())
+    }
+
     /// Reloads the tracklist from the given player. This can be compared with loading a new track
     /// list, but in this case the metadata cache can be maintained for tracks that remain on the
     /// list.
@@ -445,6 +589,50 @@ mod tests {
         TrackID::new(s).expect("Failed to parse a TrackID fixture")
     }
 
+    mod track_id {
+        use super::*;
+
+        #[test]
+        fn it_parses_valid_object_paths() {
+            for id in &["/", "/1", "/org/mpris/MediaPlayer2/Track/_42", "/a/B/c_9"] {
+                assert_eq!(id.parse::<TrackID>().map(String::from), Ok(id.to_string()));
+            }
+            assert_eq!(TrackID::try_from("/1"), Ok(track_id("/1")));
+            assert_eq!(TrackID::try_from(String::from("/1")), Ok(track_id("/1")));
+        }
+
+        #[test]
+        fn it_explains_why_an_id_is_invalid() {
+            use super::InvalidTrackIDReason::*;
+
+            let cases = [
+                ("", Empty),
+                ("spotify:track:xyz", MissingLeadingSlash),
+                ("//1", EmptySegment),
+                ("/1/", EmptySegment),
+                ("/track/4-2", InvalidCharacter('-')),
+                ("/track/é", InvalidCharacter('é')),
+            ];
+            for (id, reason) in &cases {
+                let error = id.parse::<TrackID>().unwrap_err();
+                assert_eq!(error.value, *id);
+                assert_eq!(error.reason, *reason, "for {:?}", id);
+                assert!(dbus::Path::new(*id).is_err(), "dbus accepts {:?}", id);
+            }
+        }
+
+        #[test]
+        fn it_shows_the_original_string() {
+            assert_eq!(track_id("/track/1").to_string(), "/track/1");
+
+            let error = TrackID::new("spotify:track:xyz").unwrap_err();
+            assert_eq!(
+                error,
+                "Invalid track ID \"spotify:track:xyz\": it does not start with a slash"
+            );
+        }
+    }
+
     mod track_list {
         use super::*;
 
@@ -515,5 +703,26 @@ mod tests {
             assert_eq!(&list.ids, &[track_id("/path/new")]);
             assert!(list.ids_without_cache().is_empty());
         }
+
+        #[test]
+        fn it_serves_and_invalidates_cached_metadata() {
+            let mut list = TrackList::new(vec![track_id("/path/1"), track_id("/path/2")]);
+            list.add_metadata(Metadata::new("/path/1"));
+            list.add_metadata(Metadata::new("/path/2"));
+
+            assert_eq!(
+                list.metadata_cached(&track_id("/path/1"))
+                    .and_then(|metadata| metadata.track_id()),
+                Some(track_id("/path/1"))
+            );
+
+            list.remove(&track_id("/path/1"));
+            assert!(list.metadata_cached(&track_id("/path/1")).is_none());
+
+            list.replace_track_metadata(&track_id("/path/2"), Metadata::new("/path/3"));
+            assert!(list.metadata_cached(&track_id("/path/2")).is_none());
+            assert!(list.metadata_cached(&track_id("/path/3")).is_some());
+            assert!(list.metadata_cached(&track_id("/path/missing")).is_none());
+        }
     }
 }
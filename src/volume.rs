@@ -0,0 +1,149 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::floats_equal;
+
+/// A volume level between silent and full volume.
+///
+/// Players use a fraction for the volume, so it is easy to pass `50` when `0.5` was meant, or a
+/// negative value. A [`Volume`] can only hold values from `0.0` to `1.0`, and converts from and to
+/// percentages without rounding errors: every percentage from 0 to 100 comes back unchanged
+/// from [`as_percent`](Self::as_percent).
+///
+/// Volumes that differ by less than [`TOLERANCE`](Self::TOLERANCE) are equal, so that a value
+/// read from a player compares equal to the percentage it was set to.
+///
+/// Players may support volumes above `1.0` for amplification. Use [`Player::set_volume`] and
+/// [`Player::get_volume`] with raw values for those.
+///
+/// # Examples
+///
+/// ```rust
+/// # use mpris::Volume;
+/// let volume = Volume::from_percent(73);
+/// assert_eq!(volume.as_fraction(), 0.73);
+/// assert_eq!(volume.to_string(), "73%");
+///
+/// assert_eq!(Volume::from_fraction(1.5), Volume::MAX);
+/// assert_eq!(Volume::from_fraction(-0.1), Volume::MUTED);
+/// ```
+///
+/// [`Player::set_volume`]: crate::Player::set_volume
+/// [`Player::get_volume`]: crate::Player::get_volume
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Volume(f64);
+
+impl Volume {
+    /// No sound at all.
+    pub const MUTED: Volume = Volume(0.0);
+
+    /// Full volume.
+    pub const MAX: Volume = Volume(1.0);
+
+    /// The largest difference between two volumes that are still considered equal.
+    ///
+    /// This is the same as [`Progress::FLOAT_TOLERANCE`](crate::Progress::FLOAT_TOLERANCE), so
+    /// volumes compare the same way as the volume changes that
+    /// [`PlayerEvents`](crate::PlayerEvents) reports.
+    pub const TOLERANCE: f64 = crate::FLOAT_TOLERANCE;
+
+    /// Creates a volume from a fraction of full volume, like `0.5` for half. Values outside of
+    /// `0.0..=1.0` are clamped, and `NaN` is treated as muted.
+    pub fn from_fraction(fraction: f64) -> Self {
+        if fraction.is_nan() {
+            Volume::MUTED
+        } else {
+            Volume(fraction.clamp(0.0, 1.0))
+        }
+    }
+
+    /// Creates a volume from a percentage of full volume. Values above `100` are clamped.
+    pub fn from_percent(percent: u8) -> Self {
+        Volume(f64::from(percent.min(100)) / 100.0)
+    }
+
+    /// Returns the volume as a fraction of full volume, from `0.0` to `1.0`.
+    pub fn as_fraction(self) -> f64 {
+        self.0
+    }
+
+    /// Returns the volume as a percentage of full volume, rounded to the nearest whole percent.
+    pub fn as_percent(self) -> u8 {
+        (self.0 * 100.0).round() as u8
+    }
+}
+
+impl From<Volume> for f64 {
+    fn from(volume: Volume) -> Self {
+        volume.as_fraction()
+    }
+}
+
+impl PartialEq for Volume {
+    fn eq(&self, other: &Self) -> bool {
+        floats_equal(self.0, other.0)
+    }
+}
+
+impl PartialOrd for Volume {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+}
+
+/// Writes the volume as a whole percentage, like `73%`.
+impl fmt::Display for Volume {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.as_percent())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_every_percentage() {
+        for percent in 0..=100 {
+            assert_eq!(Volume::from_percent(percent).as_percent(), percent);
+        }
+        assert_eq!(Volume::from_percent(255), Volume::MAX);
+        assert_eq!(Volume::from_fraction(0.7300001).as_percent(), 73);
+    }
+
+    #[test]
+    fn it_compares_volumes_with_a_tolerance() {
+        assert_eq!(Volume::from_fraction(0.73), Volume::from_percent(73));
+        assert_eq!(Volume::from_fraction(0.73 + 1e-6), Volume::from_percent(73));
+        assert!(Volume::from_percent(72) < Volume::from_percent(73));
+        assert!(Volume::MAX > Volume::MUTED);
+        assert_eq!(Volume::from_fraction(f64::NAN), Volume::MUTED);
+        assert_eq!(Volume::from_percent(5).to_string(), "5%");
+    }
+
+    #[test]
+    fn it_agrees_with_progress_about_volume_changes() {
+        use crate::{LoopStatus, Metadata, PlaybackStatus, Progress};
+
+        let progress = |volume| {
+            Progress::for_test(
+                Metadata::new("/1"),
+                PlaybackStatus::Playing,
+                false,
+                LoopStatus::None,
+                volume,
+                1.0,
+            )
+        };
+        for &(a, b) in &[(0.5, 0.5 + 1e-6), (0.5, 0.5 + 5e-5), (0.5, 0.5 + 1e-3)] {
+            assert_eq!(
+                Volume::from_fraction(a) == Volume::from_fraction(b),
+                progress(a).volume_equals(&progress(b))
+            );
+        }
+    }
+}
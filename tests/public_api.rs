@@ -0,0 +1,110 @@
+//! Checks that every type in a public signature can be named from outside of the crate, through
+//! the crate root and the prelude. These tests only have to compile.
+
+use std::time::Duration;
+
+use mpris::{
+    CachePolicy, CallOperation, Capabilities, CapabilityReport, DBusError, DBusErrorKind,
+    DecodeWarning, DefaultNormalizer, Event, EventError, FindingError, InvalidLoopStatus,
+    InvalidPlaybackStatus, InvalidTrackID, InvalidTrackIDReason, LoopStatus, MediaLocation,
+    Metadata, MetadataIssue, MetadataIssueKind, MetadataIter, MetadataNormalizer, MetadataSortKey,
+    MetadataValue, MetadataValueKind, NotSeekableReason, NowPlaying, OpenUriOutcome,
+    PlaybackStatus, Player, PlayerCategory, PlayerClassifier, PlayerControls, PlayerEvents,
+    PlayerEventsBuilder, PlayerFinder, PlayerIter, Position, PositionSource, Progress,
+    ProgressError, ProgressTick, ProgressTracker, Rating, RatingSource, RetryPolicy,
+    RootCapabilities, SeekBounds, SeekMethod, SetPositionOutcome, StopHandle, Timestamp,
+    TrackChanges, TrackID, TrackList, TrackListError, Volume,
+};
+
+#[test]
+fn it_names_the_types_of_public_signatures() {
+    let _: fn(&PlayerFinder) -> Result<PlayerIter, DBusError> = PlayerFinder::iter_players;
+    let _: fn(&PlayerFinder) -> Result<Player, FindingError> = PlayerFinder::find_active;
+    let _: fn(&Player) -> Result<PlayerEvents<'_>, DBusError> = Player::events;
+    let _: fn(&Player) -> Result<TrackChanges<'_>, DBusError> = Player::track_change_stream;
+    let _: fn(&Player) -> PlayerControls = Player::controls;
+    let _: fn(&Player) -> Vec<DecodeWarning> = Player::decode_warnings;
+    let _: fn(&Player) -> RetryPolicy = Player::retry_policy;
+    let _: fn(&Player) -> CachePolicy = Player::cache_policy;
+    let _: fn(&Player, u32) -> Result<ProgressTracker<'_>, DBusError> = Player::track_progress;
+    let _: fn(&Player) -> Result<TrackList, DBusError> = Player::get_track_list;
+    let _: fn(&Player, &str) -> Result<OpenUriOutcome, DBusError> = Player::open_uri_checked;
+    let _: fn(&Player) -> Result<RootCapabilities, DBusError> = Player::root_capabilities;
+    let _: fn(&Player) -> PlayerCategory = Player::category;
+    let _: fn(&Player) -> Result<CapabilityReport, DBusError> = Player::capability_report;
+    let _: fn(&Player) -> Result<std::collections::BTreeMap<String, MetadataValue>, DBusError> =
+        Player::all_properties;
+    let _: fn(&Player, Duration, SeekBounds) -> Result<SeekMethod, DBusError> =
+        Player::seek_to_with;
+    let _: fn(&Player, Duration) -> Result<SetPositionOutcome, DBusError> =
+        Player::set_current_position_verified;
+    let _: fn(&Player) -> Result<Volume, DBusError> = Player::get_volume_level;
+    let _: fn(&Event) -> Option<Volume> = Event::volume;
+    let _: fn(&PlayerFinder) -> &PlayerClassifier = PlayerFinder::classifier;
+    let _: fn(&PlayerFinder) -> Result<Option<NowPlaying>, FindingError> =
+        PlayerFinder::now_playing;
+    let _: fn(&NowPlaying) -> &Progress = NowPlaying::progress;
+    let _: fn(&TrackList, &Player) -> Result<MetadataIter, TrackListError> =
+        TrackList::metadata_iter;
+    let _: fn(&PlayerEvents<'static>) -> StopHandle = PlayerEvents::stop_handle;
+    let _: fn(&Progress) -> Capabilities = Progress::capabilities;
+    let _: fn(&Progress) -> Duration = Progress::position;
+    let _: fn(&DBusError) -> DBusErrorKind = DBusError::kind;
+    let _: fn(NotSeekableReason) -> DBusError = DBusError::TrackNotSeekable;
+    let _: fn(&EventError) -> Option<DBusErrorKind> = EventError::kind;
+
+    let _: fn(&Metadata) -> Option<MediaLocation<'_>> = Metadata::url_parsed;
+    let _: fn(&Metadata) -> Option<Rating> = Metadata::rating;
+    let _: fn(&Metadata) -> Option<Timestamp> = Metadata::content_created;
+    let _: fn(&Metadata) -> Vec<MetadataIssue> = Metadata::validate;
+    let _: fn(&Metadata) -> MetadataSortKey = Metadata::sort_key;
+    let _: fn(&MetadataValue) -> MetadataValueKind = MetadataValue::kind;
+    let _: fn(&Rating) -> RatingSource = Rating::source;
+    let _: fn(&Metadata) -> Option<Result<TrackID, InvalidTrackID>> = Metadata::checked_track_id;
+
+    fn named<T>() {}
+    named::<Event>();
+    named::<PlayerEventsBuilder>();
+    named::<ProgressTick<'_>>();
+    named::<ProgressError>();
+    named::<Position>();
+    named::<PositionSource>();
+    named::<MetadataIssueKind>();
+    named::<InvalidTrackIDReason>();
+    named::<InvalidPlaybackStatus>();
+    named::<InvalidLoopStatus>();
+    named::<LoopStatus>();
+    named::<PlaybackStatus>();
+    named::<Box<dyn MetadataNormalizer>>();
+    named::<DefaultNormalizer>();
+    named::<CallOperation>();
+}
+
+#[test]
+fn it_makes_errors_sendable_between_threads() {
+    fn error<T: std::error::Error + Send + Sync + 'static>() {}
+    error::<DBusError>();
+    error::<FindingError>();
+    error::<EventError>();
+    error::<ProgressError>();
+    error::<TrackListError>();
+    error::<InvalidTrackID>();
+    error::<InvalidPlaybackStatus>();
+    error::<InvalidLoopStatus>();
+}
+
+#[test]
+fn it_exports_the_same_types_from_the_prelude() {
+    fn same<T>(_: Option<T>, _: Option<T>) {}
+
+    same::<mpris::prelude::Player>(None, None::<Player>);
+    same::<mpris::prelude::PlayerFinder>(None, None::<PlayerFinder>);
+    same::<mpris::prelude::Metadata>(None, None::<Metadata>);
+    same::<mpris::prelude::MetadataValue>(None, None::<MetadataValue>);
+    same::<mpris::prelude::Event>(None, None::<Event>);
+    same::<mpris::prelude::DBusError>(None, None::<DBusError>);
+    same::<mpris::prelude::PlaybackStatus>(None, None::<PlaybackStatus>);
+    same::<mpris::prelude::LoopStatus>(None, None::<LoopStatus>);
+    same::<mpris::prelude::Progress>(None, None::<Progress>);
+    same::<mpris::prelude::TrackID>(None, None::<TrackID>);
+}